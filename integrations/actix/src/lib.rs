@@ -7,127 +7,172 @@
 //! directory in the Leptos repository.
 
 use actix_web::{
-    body::BoxBody,
-    dev::{ServiceFactory, ServiceRequest},
-    http::header,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    http::{header, StatusCode},
     web::Bytes,
     *,
 };
 use futures::{Future, StreamExt};
-use http::StatusCode;
 use leptos::{
-    leptos_dom::ssr::render_to_stream_with_prefix_undisposed_with_context,
-    leptos_server::{server_fn_by_path, Payload},
+    leptos_config::{AssetManifest, HtmlShell},
+    leptos_server::{
+        check_server_fn_urls, openapi_document, subscribe_to_channel,
+        CachedPage, PageCache, Session, SessionOptions,
+    },
+    ssr::{Nonce, SsrMode},
     *,
 };
 use leptos_meta::*;
 use leptos_router::*;
-use parking_lot::RwLock;
+pub use leptos_tower::redirect;
+use leptos_tower::{
+    authorize_channel, bundle_paths, dispatch_server_fn, page_response,
+    request_span, set_status, stream_page, ContentEncoding, SERVER_TIMING,
+};
+/// The response options every integration shares. [ResponseParts::headers] is an
+/// [http::HeaderMap](::http::HeaderMap), not Actix’s own `HeaderMap`, though the names and
+/// values in it are the same types Actix uses.
+pub use leptos_tower::{ResponseOptions, ResponseParts};
 use regex::Regex;
-use std::sync::Arc;
-
-/// This struct lets you define headers and override the status of the Response from an Element or a Server Function
-/// Typically contained inside of a ResponseOptions. Setting this is useful for cookies and custom responses.
-#[derive(Debug, Clone, Default)]
-pub struct ResponseParts {
-    pub headers: header::HeaderMap,
-    pub status: Option<StatusCode>,
-}
+use std::{collections::HashMap, io, path::PathBuf, time::Instant};
+use tracing::Instrument;
 
-impl ResponseParts {
-    /// Insert a header, overwriting any previous value with the same key
-    pub fn insert_header(
-        &mut self,
-        key: header::HeaderName,
-        value: header::HeaderValue,
-    ) {
-        self.headers.insert(key, value);
-    }
-    /// Append a header, leaving any header with the same key intact
-    pub fn append_header(
-        &mut self,
-        key: header::HeaderName,
-        value: header::HeaderValue,
-    ) {
-        self.headers.append(key, value);
-    }
+/// Runs an Actix extractor against the current request, from inside a server function or
+/// a component being rendered on the server. This works for any extractor that implements
+/// [FromRequest] using the request’s head, like [Query](actix_web::web::Query), app data
+/// with [Data](actix_web::web::Data), or an authenticated user added by a middleware.
+/// The body of the request has already been read, so extractors that need it will fail.
+///
+/// If the extractor fails, its error is returned as a [ServerFnError::ServerError].
+/// ```ignore
+/// #[server(CountPosts, "/api")]
+/// pub async fn count_posts(cx: Scope) -> Result<usize, ServerFnError> {
+///     let pool: web::Data<PgPool> = leptos_actix::extract(cx).await?;
+///     Ok(count(&pool).await?)
+/// }
+/// ```
+pub async fn extract<T>(cx: leptos::Scope) -> Result<T, ServerFnError>
+where
+    T: FromRequest,
+{
+    let req = use_context::<HttpRequest>(cx).ok_or_else(|| {
+        ServerFnError::ServerError(
+            "couldn't find the request to extract from; `extract` only works \
+             in server functions and components run by leptos_actix"
+                .to_string(),
+        )
+    })?;
+    T::from_request(&req, &mut dev::Payload::None)
+        .await
+        .map_err(|e| ServerFnError::ServerError(e.into().to_string()))
 }
 
-/// Adding this Struct to your Scope inside of a Server Fn or Elements will allow you to override details of the Response
-/// like StatusCode and add Headers/Cookies. Because Elements and Server Fns are lower in the tree than the Response generation
-/// code, it needs to be wrapped in an `Arc<RwLock<>>` so that it can be surfaced
-#[derive(Debug, Clone, Default)]
-pub struct ResponseOptions(pub Arc<RwLock<ResponseParts>>);
-
-impl ResponseOptions {
-    /// A less boilerplatey way to overwrite the contents of `ResponseOptions` with a new `ResponseParts`
-    pub fn overwrite(&self, parts: ResponseParts) {
-        let mut writable = self.0.write();
-        *writable = parts
-    }
-    /// Set the status of the returned Response
-    pub fn set_status(&self, status: StatusCode) {
-        let mut writeable = self.0.write();
-        let res_parts = &mut *writeable;
-        res_parts.status = Some(status);
-    }
-    /// Insert a header, overwriting any previous value with the same key
-    pub fn insert_header(
-        &self,
-        key: header::HeaderName,
-        value: header::HeaderValue,
-    ) {
-        let mut writeable = self.0.write();
-        let res_parts = &mut *writeable;
-        res_parts.headers.insert(key, value);
-    }
-    /// Append a header, leaving any header with the same key intact
-    pub fn append_header(
-        &self,
-        key: header::HeaderName,
-        value: header::HeaderValue,
-    ) {
-        let mut writeable = self.0.write();
-        let res_parts = &mut *writeable;
-        res_parts.headers.append(key, value);
+/// An Actix middleware, for use with [from_fn](actix_web::middleware::from_fn), that gives
+/// each visitor a [Session], identified by a signed cookie. It is configured by the
+/// [SessionOptions] in the app’s data.
+///
+/// Pages rendered by the integration, and server functions, can read the session from
+/// context with `use_context::<Session>(cx)`. The page also gets a
+/// `<meta name="csrf-token">` tag with the session’s CSRF token, which `<ActionForm/>`,
+/// `<MultiActionForm/>` and server function calls send back, and [handle_server_fns]
+/// rejects server function calls without it with `403 Forbidden`.
+///
+/// ```
+/// use actix_web::{middleware, web, App, HttpServer};
+/// use leptos::leptos_server::SessionOptions;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     let sessions =
+///         SessionOptions::new(std::env::var("SESSION_KEY").unwrap());
+///     HttpServer::new(move || {
+///         App::new()
+///             .route("/api/{tail:.*}", leptos_actix::handle_server_fns())
+///             .app_data(web::Data::new(sessions.clone()))
+///             .wrap(middleware::from_fn(leptos_actix::handle_sessions))
+///     })
+///     .bind(("127.0.0.1", 8080))?
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+pub async fn handle_sessions(
+    options: web::Data<SessionOptions>,
+    req: ServiceRequest,
+    next: middleware::Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>> {
+    let cookies = req
+        .headers()
+        .get_all(header::COOKIE)
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let session = options.load(Some(&cookies));
+    req.extensions_mut().insert(session.clone());
+
+    let mut res = next.call(req).await?;
+    if let Some(cookie) = options
+        .save(&session)
+        .and_then(|cookie| header::HeaderValue::from_str(&cookie).ok())
+    {
+        res.headers_mut().append(header::SET_COOKIE, cookie);
     }
+    Ok(res)
 }
 
-/// Provides an easy way to redirect the user from within a server function. Mimicing the Remix `redirect()`,
-/// it sets a [StatusCode] of 302 and a [LOCATION](header::LOCATION) header with the provided value.
-/// If looking to redirect from the client, `leptos_router::use_navigate()` should be used instead.
-pub fn redirect(cx: leptos::Scope, path: &str) {
-    if let Some(response_options) = use_context::<ResponseOptions>(cx) {
-        response_options.set_status(StatusCode::FOUND);
-        response_options.insert_header(
-            header::LOCATION,
-            header::HeaderValue::from_str(path)
-                .expect("Failed to create HeaderValue"),
+/// An Actix middleware, for use with [from_fn](actix_web::middleware::from_fn), that sets
+/// the `Cache-Control` header of the static files it wraps from the [AssetManifest] in the
+/// app’s data, so that fingerprinted files are cached forever and anything else is
+/// revalidated. A `Cache-Control` header set by the wrapped service is left alone.
+///
+/// ```ignore
+/// let manifest = options
+///     .load_asset_manifest()?
+///     .expect("an asset manifest is configured");
+/// App::new().service(
+///     web::scope("")
+///         .app_data(web::Data::new(manifest))
+///         .wrap(middleware::from_fn(leptos_actix::asset_cache_control))
+///         .service(Files::new("/", &options.site_root)),
+/// )
+/// ```
+pub async fn asset_cache_control(
+    manifest: web::Data<AssetManifest>,
+    req: ServiceRequest,
+    next: middleware::Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>> {
+    let cache_control = manifest.cache_control(req.path());
+    let mut res = next.call(req).await?;
+    if res.status().is_success()
+        && !res.headers().contains_key(header::CACHE_CONTROL)
+    {
+        res.headers_mut().insert(
+            header::CACHE_CONTROL,
+            header::HeaderValue::from_static(cache_control),
         );
     }
+    Ok(res)
 }
 
 /// An Actix [Route](actix_web::Route) that listens for a `POST` request with
 /// Leptos server function arguments in the body, runs the server function if found,
 /// and returns the resulting [HttpResponse].
 ///
-/// This provides the [HttpRequest] to the server [Scope](leptos::Scope).
+/// This provides the [HttpRequest] to the server [Scope](leptos::Scope). Server functions
+/// are registered automatically, and a warning is logged when the route is created if two
+/// of them share a URL. (See [check_server_fn_urls](leptos::leptos_server::check_server_fn_urls).)
 ///
 /// This can then be set up at an appropriate route in your application:
 ///
 /// ```
 /// use actix_web::*;
 ///
-/// fn register_server_functions() {
-///   // call ServerFn::register() for each of the server functions you've defined
-/// }
-///
 /// # if false { // don't actually try to run a server in a doctest...
 /// #[actix_web::main]
 /// async fn main() -> std::io::Result<()> {
-///     // make sure you actually register your server functions
-///     register_server_functions();
-///
 ///     HttpServer::new(|| {
 ///         App::new()
 ///             // "/api" should match the prefix, if any, declared when defining server functions
@@ -145,6 +190,9 @@ pub fn redirect(cx: leptos::Scope, path: &str) {
 /// This function always provides context values including the following types:
 /// - [ResponseOptions]
 /// - [HttpRequest](actix_web::HttpRequest)
+///
+/// It also provides the [Session], if the request went through [handle_sessions], and
+/// responds with `403 Forbidden` if the request doesn’t carry the session’s CSRF token.
 pub fn handle_server_fns() -> Route {
     handle_server_fns_with_context(|_cx| {})
 }
@@ -164,115 +212,193 @@ pub fn handle_server_fns() -> Route {
 /// This function always provides context values including the following types:
 /// - [ResponseOptions]
 /// - [HttpRequest](actix_web::HttpRequest)
+///
+/// It also provides the [Session], if the request went through [handle_sessions], and
+/// responds with `403 Forbidden` if the request doesn’t carry the session’s CSRF token.
 pub fn handle_server_fns_with_context(
     additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
 ) -> Route {
+    check_server_fn_urls();
+
     web::post().to(
         move |req: HttpRequest, params: web::Path<String>, body: web::Bytes| {
             let additional_context = additional_context.clone();
+            let span = request_span(req.method(), req.path());
             async move {
-                let additional_context = additional_context.clone();
-
                 let path = params.into_inner();
-                let accept_header = req
+                let headers = req
                     .headers()
-                    .get("Accept")
-                    .and_then(|value| value.to_str().ok());
-
-                if let Some(server_fn) = server_fn_by_path(path.as_str()) {
-                    let body: &[u8] = &body;
-
-                    let runtime = create_runtime();
-                    let (cx, disposer) = raw_scope_and_disposer(runtime);
-
-                    // Add additional info to the context of the server function
-                    additional_context(cx);
-                    let res_options = ResponseOptions::default();
-
-                    // provide HttpRequest as context in server scope
-                    provide_context(cx, req.clone());
-                    provide_context(cx, res_options.clone());
-
-                    match server_fn(cx, body).await {
-                        Ok(serialized) => {
-                            let res_options =
-                                use_context::<ResponseOptions>(cx).unwrap();
-
-                            // clean up the scope, which we only needed to run the server fn
-                            disposer.dispose();
-                            runtime.dispose();
-
-                            let mut res: HttpResponseBuilder;
-                            let mut res_parts = res_options.0.write();
-
-                            if accept_header == Some("application/json")
-                                || accept_header
-                                    == Some("application/x-www-form-urlencoded")
-                                || accept_header == Some("application/cbor")
-                            {
-                                res = HttpResponse::Ok();
-                            }
-                            // otherwise, it's probably a <form> submit or something: redirect back to the referrer
-                            else {
-                                let referer = req
-                                    .headers()
-                                    .get("Referer")
-                                    .and_then(|value| value.to_str().ok())
-                                    .unwrap_or("/");
-                                res = HttpResponse::SeeOther();
-                                res.insert_header(("Location", referer))
-                                    .content_type("application/json");
-                            };
-                            // Override StatusCode if it was set in a Resource or Element
-                            if let Some(status) = res_parts.status {
-                                res.status(status);
-                            }
-
-                            // Use provided ResponseParts headers if they exist
-                            let _count = res_parts
-                                .headers
-                                .drain()
-                                .map(|(k, v)| {
-                                    if let Some(k) = k {
-                                        res.append_header((k, v));
-                                    }
-                                })
-                                .count();
-
-                            match serialized {
-                                Payload::Binary(data) => {
-                                    res.content_type("application/cbor");
-                                    res.body(Bytes::from(data))
-                                }
-                                Payload::Url(data) => {
-                                    res.content_type(
-                                        "application/x-www-form-urlencoded",
-                                    );
-                                    res.body(data)
-                                }
-                                Payload::Json(data) => {
-                                    res.content_type("application/json");
-                                    res.body(data)
-                                }
-                            }
-                        }
-                        Err(e) => HttpResponse::InternalServerError()
-                            .body(e.to_string()),
-                    }
-                } else {
-                    HttpResponse::BadRequest().body(format!(
-                        "Could not find a server function at the route {:?}. \
-                         \n\nIt's likely that you need to call \
-                         ServerFn::register() on the server function type, \
-                         somewhere in your `main` function.",
-                        req.path()
-                    ))
-                }
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                let session = req.extensions().get::<Session>().cloned();
+
+                let res = dispatch_server_fn(
+                    &path,
+                    &headers,
+                    &body,
+                    session,
+                    move |cx| {
+                        // Add additional info to the context of the server function
+                        additional_context(cx);
+                        // provide HttpRequest as context in server scope
+                        provide_context(cx, req);
+                    },
+                )
+                .await;
+                let (parts, body) = res.into_parts();
+                response_builder(&parts).body(body)
             }
+            .instrument(span)
         },
     )
 }
 
+/// An Actix [Route](actix_web::Route) that streams the messages broadcast on a
+/// [Channel](leptos::Channel) to the browser, as
+/// [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events),
+/// for [create_channel_signal](leptos::create_channel_signal). The stream ends when the
+/// browser disconnects.
+///
+/// Mount it at `/channels/{name}`, or at another prefix set with
+/// [Channel::with_prefix](leptos::Channel::with_prefix):
+/// ```
+/// use actix_web::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     HttpServer::new(|| {
+///         App::new().route("/channels/{name}", leptos_actix::handle_channel())
+///     })
+///     .bind(("127.0.0.1", 8080))?
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+pub fn handle_channel() -> Route {
+    handle_channel_with_authorization(|_, _| true)
+}
+
+/// Like [handle_channel], but only lets the browser subscribe if `authorize` returns `true`,
+/// and responds with `403 Forbidden` otherwise.
+///
+/// `authorize` is called with the URL of the channel, in a [Scope](leptos::Scope) that has
+/// the [HttpRequest], and the [Session] if the request went through [handle_sessions].
+/// ```
+/// use actix_web::*;
+/// use leptos::{leptos_server::Session, *};
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     HttpServer::new(|| {
+///         App::new().route(
+///             "/channels/{name}",
+///             leptos_actix::handle_channel_with_authorization(|cx, _| {
+///                 use_context::<Session>(cx)
+///                     .and_then(|session| session.get::<String>("user"))
+///                     .is_some()
+///             }),
+///         )
+///     })
+///     .bind(("127.0.0.1", 8080))?
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+pub fn handle_channel_with_authorization(
+    authorize: impl Fn(leptos::Scope, &str) -> bool + Clone + 'static,
+) -> Route {
+    web::get().to(move |req: HttpRequest| {
+        let authorize = authorize.clone();
+        async move {
+            let url = req.path().to_string();
+            let session = req.extensions().get::<Session>().cloned();
+            let authorized = authorize_channel(
+                &url,
+                session,
+                {
+                    let req = req.clone();
+                    move |cx| provide_context(cx, req)
+                },
+                authorize,
+            );
+            if !authorized {
+                return HttpResponse::Forbidden()
+                    .body("not allowed to subscribe to this channel");
+            }
+
+            let messages = subscribe_to_channel(&url).map(|message| {
+                let mut event = String::new();
+                for line in message.split('\n') {
+                    event.push_str("data: ");
+                    event.push_str(line);
+                    event.push('\n');
+                }
+                event.push('\n');
+                Bytes::from(event)
+            });
+            // a comment now and then keeps proxies from closing an idle connection
+            let keep_alive = futures::stream::unfold(
+                actix_web::rt::time::interval(std::time::Duration::from_secs(
+                    15,
+                )),
+                |mut interval| async move {
+                    interval.tick().await;
+                    Some((Bytes::from_static(b":\n\n"), interval))
+                },
+            );
+            HttpResponse::Ok()
+                .content_type("text/event-stream")
+                .insert_header((header::CACHE_CONTROL, "no-cache"))
+                .streaming(
+                    futures::stream::select(messages, keep_alive)
+                        .map(Ok::<_, Error>),
+                )
+        }
+    })
+}
+
+/// An Actix [Route](actix_web::Route) that listens for a `GET` request and responds with an
+/// [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document describing all of your server
+/// functions, which can be used to generate API clients or documentation. (See
+/// [openapi_document](leptos::leptos_server::openapi_document).)
+///
+/// ```
+/// use actix_web::*;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     HttpServer::new(|| {
+///         App::new()
+///             .route(
+///                 "/api/openapi.json",
+///                 leptos_actix::handle_openapi("My App", "1.0.0"),
+///             )
+///             .route("/api/{tail:.*}", leptos_actix::handle_server_fns())
+///     })
+///     .bind(("127.0.0.1", 8080))?
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+pub fn handle_openapi(title: &str, version: &str) -> Route {
+    let document = Bytes::from(openapi_document(title, version).to_string());
+    web::get().to(move || {
+        let document = document.clone();
+        async move {
+            HttpResponse::Ok()
+                .content_type("application/json")
+                .body(document)
+        }
+    })
+}
+
 /// Returns an Actix [Route](actix_web::Route) that listens for a `GET` request and tries
 /// to route it using [leptos_router], serving an HTML stream of your application.
 ///
@@ -282,6 +408,10 @@ pub fn handle_server_fns_with_context(
 /// The HTML stream is rendered using [render_to_stream], and includes everything described in
 /// the documentation for that function.
 ///
+/// A `Link` header preloads the JS and WASM bundle and the page’s stylesheets. It is sent
+/// with the response, rather than in a `103 Early Hints` response ahead of it, which Actix
+/// can’t send.
+///
 /// This can then be set up at an appropriate route in your application:
 /// ```
 /// use actix_web::{App, HttpServer};
@@ -325,6 +455,8 @@ pub fn handle_server_fns_with_context(
 /// - [HttpRequest](actix_web::HttpRequest)
 /// - [MetaContext](leptos_meta::MetaContext)
 /// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
 pub fn render_app_to_stream<IV>(
     options: LeptosOptions,
     app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
@@ -347,6 +479,8 @@ where
 /// - [HttpRequest](actix_web::HttpRequest)
 /// - [MetaContext](leptos_meta::MetaContext)
 /// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
 pub fn render_app_to_stream_with_context<IV>(
     options: LeptosOptions,
     additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
@@ -355,27 +489,393 @@ pub fn render_app_to_stream_with_context<IV>(
 where
     IV: IntoView,
 {
+    render_app_to_stream_with_mode(
+        options,
+        additional_context,
+        app_fn,
+        SsrMode::OutOfOrder,
+    )
+}
+
+/// Returns an Actix [Route](actix_web::Route) that listens for a `GET` request and tries
+/// to route it using [leptos_router], serving an HTML stream of your application in
+/// document order.
+///
+/// The stream is held at each `<Suspense/>` until the resources read under it have resolved,
+/// and its HTML is written in place, so clients that don't run JavaScript, like search
+/// engine crawlers, receive the complete page. Everything before the first pending
+/// `<Suspense/>` is still sent immediately. (See [render_to_stream_in_order](leptos::ssr::render_to_stream_in_order).)
+///
+/// This can be used for individual routes, alongside [render_app_to_stream] for the rest:
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::*;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> impl IntoView {
+///     view! { cx, <main>"Hello, world!"</main> }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+///     let addr = conf.leptos_options.site_addr.clone();
+///     HttpServer::new(move || {
+///         let leptos_options = &conf.leptos_options;
+///
+///         App::new()
+///             .route(
+///                 "/blog/{slug}",
+///                 leptos_actix::render_app_to_stream_in_order(
+///                     leptos_options.to_owned(),
+///                     |cx| view! { cx, <MyApp/> },
+///                 ),
+///             )
+///             .route(
+///                 "/{tail:.*}",
+///                 leptos_actix::render_app_to_stream(
+///                     leptos_options.to_owned(),
+///                     |cx| view! { cx, <MyApp/> },
+///                 ),
+///             )
+///     })
+///     .bind(&addr)?
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [ResponseOptions]
+/// - [HttpRequest](actix_web::HttpRequest)
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
+pub fn render_app_to_stream_in_order<IV>(
+    options: LeptosOptions,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+) -> Route
+where
+    IV: IntoView,
+{
+    render_app_to_stream_in_order_with_context(options, |_cx| {}, app_fn)
+}
+
+/// Returns an Actix [Route](actix_web::Route) that listens for a `GET` request and tries
+/// to route it using [leptos_router], serving an HTML stream of your application in
+/// document order.
+///
+/// This function allows you to provide additional information to Leptos for your route,
+/// like [render_app_to_stream_with_context]. Otherwise, it is identical to
+/// [render_app_to_stream_in_order].
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [ResponseOptions]
+/// - [HttpRequest](actix_web::HttpRequest)
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
+pub fn render_app_to_stream_in_order_with_context<IV>(
+    options: LeptosOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+) -> Route
+where
+    IV: IntoView,
+{
+    render_app_to_stream_with_mode(
+        options,
+        additional_context,
+        app_fn,
+        SsrMode::InOrder,
+    )
+}
+
+fn render_app_to_stream_with_mode<IV>(
+    options: LeptosOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+    mode: SsrMode,
+) -> Route
+where
+    IV: IntoView,
+{
+    let manifest = load_asset_manifest(&options);
+    let shell = load_html_shell(&options);
+
     web::get().to(move |req: HttpRequest| {
         let options = options.clone();
         let app_fn = app_fn.clone();
         let additional_context = additional_context.clone();
+        let manifest = manifest.clone();
+        let shell = shell.clone();
         let res_options = ResponseOptions::default();
+        let span = request_span(req.method(), req.path());
 
         async move {
+            let encoding = ContentEncoding::for_options(
+                &options,
+                req.headers().get(header::ACCEPT_ENCODING),
+            );
+            let bundle_urls = bundle_paths(&options, manifest.as_ref());
             let app = {
                 let app_fn = app_fn.clone();
                 let res_options = res_options.clone();
                 move |cx| {
-                    provide_contexts(cx, &req, res_options);
+                    provide_contexts(cx, &req, res_options, manifest);
                     (app_fn)(cx).into_view(cx)
                 }
             };
 
-            stream_app(&options, app, res_options, additional_context).await
+            stream_app(
+                &options,
+                shell,
+                bundle_urls,
+                app,
+                res_options,
+                additional_context,
+                mode,
+                encoding,
+            )
+            .await
         }
+        .instrument(span)
     })
 }
 
+/// Returns an Actix [Route](actix_web::Route) that serves your application like
+/// [render_app_to_stream], but keeps the rendered HTML of each page in a [PageCache] and
+/// serves it from there until it expires.
+///
+/// A page is cached by its path, and by any other parts of the request the cache is
+/// configured to vary by. Only successful responses that don’t set cookies are cached.
+/// Once a cached page is stale, it is still served, while the page is rendered again in
+/// the background to replace it.
+///
+/// Responses are sent once the whole page has rendered, rather than streamed. A cached
+/// page is the same for everyone who receives it, so avoid caching pages that depend on
+/// who is asking for them. The one exception is the CSP nonce, which is replaced by a new
+/// one each time the page is served. (See [CachedPage::replace_nonce].) Cached pages aren’t compressed,
+/// even if [LeptosOptions::compression] is set.
+///
+/// To let server functions invalidate cached pages, provide the same cache to them:
+/// ```
+/// use actix_web::{App, HttpServer};
+/// use leptos::{leptos_server::PageCache, *};
+/// use std::time::Duration;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> impl IntoView {
+///     view! { cx, <main>"Hello, world!"</main> }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[actix_web::main]
+/// async fn main() -> std::io::Result<()> {
+///     let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+///     let addr = conf.leptos_options.site_addr.clone();
+///     let cache = PageCache::new(Duration::from_secs(60));
+///     HttpServer::new(move || {
+///         let leptos_options = &conf.leptos_options;
+///         let server_fn_cache = cache.clone();
+///
+///         App::new()
+///             .route(
+///                 "/api/{tail:.*}",
+///                 leptos_actix::handle_server_fns_with_context(move |cx| {
+///                     provide_context(cx, server_fn_cache.clone())
+///                 }),
+///             )
+///             .route(
+///                 "/{tail:.*}",
+///                 leptos_actix::render_app_to_stream_cached(
+///                     leptos_options.to_owned(),
+///                     cache.clone(),
+///                     |cx| view! { cx, <MyApp/> },
+///                 ),
+///             )
+///     })
+///     .bind(&addr)?
+///     .run()
+///     .await
+/// }
+/// # }
+/// ```
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [ResponseOptions]
+/// - [HttpRequest](actix_web::HttpRequest)
+/// - [PageCache]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+///
+/// Because a cached page is shared by every visitor, it is rendered without the [Session]
+/// from [handle_sessions]. Cached pages don’t include a CSRF token, so pages that call
+/// server functions shouldn’t be cached if you use sessions.
+pub fn render_app_to_stream_cached<IV>(
+    options: LeptosOptions,
+    cache: PageCache,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+) -> Route
+where
+    IV: IntoView,
+{
+    render_app_to_stream_cached_with_context(options, cache, |_cx| {}, app_fn)
+}
+
+/// Returns an Actix [Route](actix_web::Route) that serves your application from a
+/// [PageCache], like [render_app_to_stream_cached].
+///
+/// This function allows you to provide additional information to Leptos for your route,
+/// like [render_app_to_stream_with_context]. Otherwise, it is identical to
+/// [render_app_to_stream_cached].
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [ResponseOptions]
+/// - [HttpRequest](actix_web::HttpRequest)
+/// - [PageCache]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+pub fn render_app_to_stream_cached_with_context<IV>(
+    options: LeptosOptions,
+    cache: PageCache,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+) -> Route
+where
+    IV: IntoView,
+{
+    let manifest = load_asset_manifest(&options);
+    let shell = load_html_shell(&options);
+
+    web::get().to(move |req: HttpRequest| {
+        let options = options.clone();
+        let app_fn = app_fn.clone();
+        let additional_context = additional_context.clone();
+        let manifest = manifest.clone();
+        let shell = shell.clone();
+        let cache = cache.clone();
+        let span = request_span(req.method(), req.path());
+
+        async move {
+            // a cached page is shared by every visitor, so it's rendered without a session
+            req.extensions_mut().remove::<Session>();
+
+            let key = cache.key(req.path(), Some(req.query_string()), |name| {
+                req.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from)
+            });
+
+            let render = move |req: HttpRequest, cache: PageCache| async move {
+                let res_options = ResponseOptions::default();
+                let bundle_urls = bundle_paths(&options, manifest.as_ref());
+                let app = {
+                    let res_options = res_options.clone();
+                    let cache = cache.clone();
+                    move |cx| {
+                        provide_contexts(cx, &req, res_options, manifest);
+                        provide_context(cx, cache);
+                        (app_fn)(cx).into_view(cx)
+                    }
+                };
+                let res = stream_app(
+                    &options,
+                    shell,
+                    bundle_urls,
+                    app,
+                    res_options,
+                    additional_context,
+                    SsrMode::OutOfOrder,
+                    // the page is stored as text, and may be sent to any browser
+                    None,
+                )
+                .await;
+                render_page(res).await
+            };
+
+            if let Some(page) = cache.get(&key) {
+                if cache.is_stale(&page) && cache.start_refresh(&key) {
+                    let req = req.clone();
+                    actix_web::rt::spawn(async move {
+                        let generation = cache.generation();
+                        let page = render(req, cache.clone()).await;
+                        if is_cacheable(&page) {
+                            cache.insert(key.clone(), page, generation);
+                        }
+                        cache.finish_refresh(&key);
+                    });
+                }
+                return cached_response(page);
+            }
+
+            let generation = cache.generation();
+            let page = render(req, cache.clone()).await;
+            if is_cacheable(&page) {
+                cache.insert(key, page.clone(), generation);
+            }
+            cached_response(page)
+        }
+        .instrument(span)
+    })
+}
+
+/// Waits for the whole HTML stream of a rendered page.
+async fn render_page(res: HttpResponse<BoxBody>) -> CachedPage {
+    let status = res.status().as_u16();
+    let headers = res
+        .headers()
+        .iter()
+        // the timings of this render don't apply to later responses
+        .filter(|(name, _)| **name != SERVER_TIMING)
+        .filter_map(|(name, value)| {
+            Some((name.to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect();
+    let body = body::to_bytes(res.into_body()).await.unwrap_or_default();
+    CachedPage {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        rendered_at: std::time::SystemTime::now(),
+    }
+}
+
+fn is_cacheable(page: &CachedPage) -> bool {
+    page.status == StatusCode::OK.as_u16()
+        && !page
+            .headers
+            .iter()
+            .any(|(name, _)| name == header::SET_COOKIE.as_str())
+}
+
+fn cached_response(mut page: CachedPage) -> HttpResponse<BoxBody> {
+    page.replace_nonce(&Nonce::new().to_string());
+    let age = page
+        .rendered_at
+        .elapsed()
+        .map(|age| age.as_secs())
+        .unwrap_or_default();
+    let mut res = HttpResponse::build(
+        StatusCode::from_u16(page.status).unwrap_or_default(),
+    );
+    for (name, value) in page.headers {
+        res.append_header((name, value));
+    }
+    res.insert_header((header::AGE, age)).body(page.body)
+}
+
 /// Returns an Actix [Route](actix_web::Route) that listens for a `GET` request and tries
 /// to route it using [leptos_router], serving an HTML stream of your application.
 ///
@@ -434,6 +934,8 @@ where
 /// - [HttpRequest](actix_web::HttpRequest)
 /// - [MetaContext](leptos_meta::MetaContext)
 /// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
 pub fn render_preloaded_data_app<Data, Fut, IV>(
     options: LeptosOptions,
     data_fn: impl Fn(HttpRequest) -> Fut + Clone + 'static,
@@ -444,11 +946,17 @@ where
     Fut: Future<Output = Result<DataResponse<Data>, actix_web::Error>>,
     IV: IntoView + 'static,
 {
+    let manifest = load_asset_manifest(&options);
+    let shell = load_html_shell(&options);
+
     web::get().to(move |req: HttpRequest| {
         let options = options.clone();
         let app_fn = app_fn.clone();
         let data_fn = data_fn.clone();
+        let manifest = manifest.clone();
+        let shell = shell.clone();
         let res_options = ResponseOptions::default();
+        let span = request_span(req.method(), req.path());
 
         async move {
             let data = match data_fn(req.clone()).await {
@@ -457,33 +965,74 @@ where
                 Ok(DataResponse::Data(d)) => d,
             };
 
+            let encoding = ContentEncoding::for_options(
+                &options,
+                req.headers().get(header::ACCEPT_ENCODING),
+            );
+            let bundle_urls = bundle_paths(&options, manifest.as_ref());
             let app = {
                 let app_fn = app_fn.clone();
                 let res_options = res_options.clone();
                 move |cx| {
-                    provide_contexts(cx, &req, res_options);
+                    provide_contexts(cx, &req, res_options, manifest);
                     (app_fn)(cx, data).into_view(cx)
                 }
             };
 
-            stream_app(&options, app, res_options, |_cx| {}).await
+            stream_app(
+                &options,
+                shell,
+                bundle_urls,
+                app,
+                res_options,
+                |_cx| {},
+                SsrMode::OutOfOrder,
+                encoding,
+            )
+            .await
         }
+        .instrument(span)
     })
 }
 
+/// Loads the [AssetManifest] given in the [LeptosOptions], once, when a handler is created.
+fn load_asset_manifest(options: &LeptosOptions) -> Option<AssetManifest> {
+    // `get_configuration` has already reported a manifest that can't be loaded
+    options.load_asset_manifest().unwrap_or_else(|e| {
+        leptos::error!("{e}");
+        None
+    })
+}
+
+/// Loads the [HtmlShell] given in the [LeptosOptions], once, when a handler is created.
+fn load_html_shell(options: &LeptosOptions) -> HtmlShell {
+    options
+        .load_html_shell()
+        .expect("couldn't load the HTML shell")
+}
+
 fn provide_contexts(
     cx: leptos::Scope,
     req: &HttpRequest,
     res_options: ResponseOptions,
+    manifest: Option<AssetManifest>,
 ) {
     let path = leptos_corrected_path(req);
 
     let integration = ServerIntegration { path };
     provide_context(cx, RouterIntegrationContext::new(integration));
-    provide_context(cx, MetaContext::new());
+    provide_context(cx, Nonce::new());
+    provide_meta_context(cx);
     provide_context(cx, res_options);
     provide_context(cx, req.clone());
+    if let Some(manifest) = manifest {
+        provide_context(cx, manifest);
+    }
+    if let Some(session) = req.extensions().get::<Session>().cloned() {
+        provide_context(cx, session);
+    }
     provide_server_redirect(cx, move |path| redirect(cx, path));
+    provide_server_status(cx, move |status| set_status(cx, status));
 }
 
 fn leptos_corrected_path(req: &HttpRequest) -> String {
@@ -496,136 +1045,47 @@ fn leptos_corrected_path(req: &HttpRequest) -> String {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn stream_app(
     options: &LeptosOptions,
+    shell: HtmlShell,
+    bundle_urls: (String, String),
     app: impl FnOnce(leptos::Scope) -> View + 'static,
     res_options: ResponseOptions,
     additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    mode: SsrMode,
+    encoding: Option<ContentEncoding>,
 ) -> HttpResponse<BoxBody> {
-    let (stream, runtime, scope) =
-        render_to_stream_with_prefix_undisposed_with_context(
-            app,
-            move |cx| {
-                let meta = use_context::<MetaContext>(cx);
-                let head = meta
-                    .as_ref()
-                    .map(|meta| meta.dehydrate())
-                    .unwrap_or_default();
-                let body_meta = meta
-                    .as_ref()
-                    .and_then(|meta| meta.body.as_string())
-                    .unwrap_or_default();
-                format!("{head}</head><body{body_meta}>").into()
-            },
-            additional_context,
-        );
-
-    let cx = leptos::Scope { runtime, id: scope };
-    let (head, tail) =
-        html_parts(options, use_context::<MetaContext>(cx).as_ref());
-
-    let mut stream = Box::pin(
-        futures::stream::once(async move { head.clone() })
-            .chain(stream)
-            .chain(futures::stream::once(async move {
-                runtime.dispose();
-                tail.to_string()
-            }))
-            .map(|html| Ok(web::Bytes::from(html)) as Result<web::Bytes>),
+    let started = Instant::now();
+    let page = stream_page(
+        options,
+        shell,
+        bundle_urls,
+        app,
+        &res_options,
+        additional_context,
+        mode,
     );
-
-    // Get the first and second in the stream, which renders the app shell, and thus allows Resources to run
-    let first_chunk = stream.next().await;
-    let second_chunk = stream.next().await;
-
-    let res_options = res_options.0.read();
-
-    let (status, mut headers) =
-        (res_options.status, res_options.headers.clone());
-    let status = status.unwrap_or_default();
-
-    let complete_stream =
-        futures::stream::iter([first_chunk.unwrap(), second_chunk.unwrap()])
-            .chain(stream);
-    let mut res = HttpResponse::Ok()
-        .content_type("text/html")
-        .streaming(complete_stream);
-    // Add headers manipulated in the response
-    for (key, value) in headers.drain() {
-        if let Some(key) = key {
-            res.headers_mut().append(key, value);
-        }
-    }
-    // Set status to what is returned in the function
-    let res_status = res.status_mut();
-    *res_status = status;
-    // Return the response
-    res
+    let res = page_response(
+        page,
+        &res_options,
+        options.compression,
+        encoding,
+        started,
+    )
+    .await;
+    let (parts, body) = res.into_parts();
+    response_builder(&parts).streaming(body)
 }
 
-fn html_parts(
-    options: &LeptosOptions,
-    meta_context: Option<&MetaContext>,
-) -> (String, String) {
-    // Because wasm-pack adds _bg to the end of the WASM filename, and we want to mantain compatibility with it's default options
-    // we add _bg to the wasm files if cargo-leptos doesn't set the env var LEPTOS_OUTPUT_NAME
-    // Otherwise we need to add _bg because wasm_pack always does. This is not the same as options.output_name, which is set regardless
-    let output_name = &options.output_name;
-    let mut wasm_output_name = output_name.clone();
-    if std::env::var("LEPTOS_OUTPUT_NAME").is_err() {
-        wasm_output_name.push_str("_bg");
+/// Starts an Actix response with the status and headers of a response built by
+/// [leptos_tower].
+fn response_builder(parts: &::http::response::Parts) -> HttpResponseBuilder {
+    let mut res = HttpResponse::build(parts.status);
+    for (name, value) in &parts.headers {
+        res.append_header((name.clone(), value.clone()));
     }
-
-    let site_ip = &options.site_addr.ip().to_string();
-    let reload_port = options.reload_port;
-    let pkg_path = &options.site_pkg_dir;
-
-    let leptos_autoreload = match std::env::var("LEPTOS_WATCH").is_ok() {
-        true => format!(
-            r#"
-            <script crossorigin="">(function () {{
-                var ws = new WebSocket('ws://{site_ip}:{reload_port}/live_reload');
-                ws.onmessage = (ev) => {{
-                    let msg = JSON.parse(ev.data);
-                    if (msg.all) window.location.reload();
-                    if (msg.css) {{
-                        let found = false;
-                        document.querySelectorAll("link").forEach((link) => {{
-                            if (link.getAttribute('href').includes(msg.css)) {{
-                                let newHref = '/' + msg.css + '?version=' + new Date().getMilliseconds();
-                                link.setAttribute('href', newHref);
-                                found = true;
-                            }}
-                        }});
-                        if (!found) console.warn(`CSS hot-reload: Could not find a <link href=/\"${{msg.css}}\"> element`);
-                    }};
-                }};
-                ws.onclose = () => console.warn('Live-reload stopped. Manual reload necessary.');
-            }})()
-            </script>
-            "#
-        ),
-        false => "".to_string(),
-    };
-
-    let html_metadata = meta_context
-        .and_then(|mc| mc.html.as_string())
-        .unwrap_or_default();
-    let head = format!(
-        r#"<!DOCTYPE html>
-        <html{html_metadata}>
-            <head>
-                <meta charset="utf-8"/>
-                <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                <link rel="modulepreload" href="/{pkg_path}/{output_name}.js">
-                <link rel="preload" href="/{pkg_path}/{wasm_output_name}.wasm" as="fetch" type="application/wasm" crossorigin="">
-                <script type="module">import init, {{ hydrate }} from '/{pkg_path}/{output_name}.js'; init('/{pkg_path}/{wasm_output_name}.wasm').then(hydrate);</script>
-                {leptos_autoreload}
-                "#
-    );
-    let tail = "</body></html>".to_string();
-
-    (head, tail)
+    res
 }
 
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
@@ -637,7 +1097,22 @@ pub fn generate_route_list<IV>(
 where
     IV: IntoView + 'static,
 {
-    let mut routes = leptos_router::generate_route_list_inner(app_fn);
+    generate_route_list_with_fallback(app_fn).0
+}
+
+/// Like [generate_route_list], but also returns whether your app defines a fallback view
+/// for paths that don't match any route. If it does, you can render the app for every
+/// other `GET` request, for example with
+/// `.default_service(leptos_actix::render_app_to_stream(...))`, and unmatched paths will
+/// show the fallback with a `404 Not Found` status.
+pub fn generate_route_list_with_fallback<IV>(
+    app_fn: impl FnOnce(leptos::Scope) -> IV + 'static,
+) -> (Vec<String>, bool)
+where
+    IV: IntoView + 'static,
+{
+    let (mut routes, has_fallback) =
+        leptos_router::generate_route_list_with_fallback_inner(app_fn);
 
     // Empty strings screw with Actix pathing, they need to be "/"
     routes = routes
@@ -662,11 +1137,71 @@ where
         .map(|s| capture_re.replace_all(&s, "{$1}").to_string())
         .collect();
 
-    if routes.is_empty() {
+    let routes = if routes.is_empty() {
         vec!["/".to_string()]
     } else {
         routes
-    }
+    };
+    (routes, has_fallback)
+}
+
+/// Renders a page for each of your app’s routes ahead of time, and writes it to an
+/// `index.html` file in the matching directory under [LeptosOptions::site_root], so
+/// that the site can be served as static files. Dynamic segments are filled in from
+/// `params`, and the pages hydrate if `hydrate` is `true`: see
+/// [leptos_tower::generate_static_site] for the details.
+///
+/// Returns the paths of the files that were written.
+/// ```
+/// use leptos::*;
+/// use leptos_router::*;
+/// use std::collections::HashMap;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> impl IntoView {
+///     view! { cx,
+///         <Router>
+///             <Routes>
+///                 <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+///                 <Route path="post/:id" view=|cx| view! { cx, <p>"Post"</p> }/>
+///             </Routes>
+///         </Router>
+///     }
+/// }
+///
+/// # if false { // don't actually try to write files in a doctest...
+/// #[actix_web::main]
+/// async fn main() {
+///     let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+///     let posts = (1..=10)
+///         .map(|id| {
+///             let mut params = ParamsMap::new();
+///             params.insert("id".to_string(), id.to_string());
+///             params
+///         })
+///         .collect();
+///     let params = HashMap::from([("/post/:id".to_string(), posts)]);
+///     leptos_actix::generate_static_site(
+///         &conf.leptos_options,
+///         |cx| view! { cx, <MyApp/> },
+///         params,
+///         true,
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// # }
+/// ```
+pub async fn generate_static_site<IV>(
+    options: &LeptosOptions,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+    params: HashMap<String, Vec<ParamsMap>>,
+    hydrate: bool,
+) -> io::Result<Vec<PathBuf>>
+where
+    IV: IntoView + 'static,
+{
+    leptos_tower::generate_static_site(options, app_fn, params, hydrate).await
 }
 
 pub enum DataResponse<T> {
@@ -0,0 +1,51 @@
+use actix_web::{http::header, middleware, test, web, App, HttpResponse};
+use leptos::leptos_config::{AssetManifest, IMMUTABLE_CACHE_CONTROL};
+
+fn manifest() -> AssetManifest {
+    AssetManifest::from_json(r#"{ "pkg/app.js": "pkg/app.3f9a2c.js" }"#)
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn static_files_get_cache_control_from_the_manifest() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(manifest()))
+            .wrap(middleware::from_fn(leptos_actix::asset_cache_control))
+            .route("/pkg/{file}", web::get().to(HttpResponse::Ok))
+            .route(
+                "/private.txt",
+                web::get().to(|| async {
+                    HttpResponse::Ok()
+                        .insert_header((header::CACHE_CONTROL, "private"))
+                        .finish()
+                }),
+            ),
+    )
+    .await;
+
+    let cache_control = |path: &'static str| {
+        let req = test::TestRequest::get().uri(path).to_request();
+        let res = test::call_service(&app, req);
+        async move {
+            res.await
+                .headers()
+                .get(header::CACHE_CONTROL)
+                .map(|value| value.to_str().unwrap().to_string())
+        }
+    };
+
+    assert_eq!(
+        cache_control("/pkg/app.3f9a2c.js").await.as_deref(),
+        Some(IMMUTABLE_CACHE_CONTROL)
+    );
+    assert_eq!(
+        cache_control("/pkg/app.js").await.as_deref(),
+        Some("no-cache")
+    );
+    assert_eq!(
+        cache_control("/private.txt").await.as_deref(),
+        Some("private")
+    );
+    assert_eq!(cache_control("/missing").await, None);
+}
@@ -0,0 +1,69 @@
+use actix_web::{http::header, test, web, HttpRequest};
+use leptos::*;
+use std::collections::HashMap;
+
+/// Runs `extract` in a scope that has `req`, the way a server function does.
+async fn with_request<T>(
+    req: HttpRequest,
+    extract: impl FnOnce(Scope) -> T,
+) -> T::Output
+where
+    T: std::future::Future,
+{
+    let runtime = create_runtime();
+    let (cx, disposer) = raw_scope_and_disposer(runtime);
+    provide_context(cx, req);
+    let output = extract(cx).await;
+    disposer.dispose();
+    runtime.dispose();
+    output
+}
+
+fn request() -> HttpRequest {
+    test::TestRequest::get()
+        .uri("/search?q=leptos")
+        .insert_header(header::ContentType::json())
+        .to_http_request()
+}
+
+#[actix_web::test]
+async fn extractors_run_against_the_request() {
+    let (query, content_type) = with_request(request(), |cx| async move {
+        let query: web::Query<HashMap<String, String>> =
+            leptos_actix::extract(cx).await.unwrap();
+        let content_type: web::Header<header::ContentType> =
+            leptos_actix::extract(cx).await.unwrap();
+        (query, content_type)
+    })
+    .await;
+
+    assert_eq!(query["q"], "leptos");
+    assert_eq!(content_type.0, header::ContentType::json());
+}
+
+#[actix_web::test]
+async fn extractor_errors_are_server_errors() {
+    let result = with_request(request(), |cx| {
+        leptos_actix::extract::<web::Query<HashMap<String, u32>>>(cx)
+    })
+    .await;
+
+    match result {
+        Err(ServerFnError::ServerError(message)) => {
+            assert!(message.starts_with("Query deserialize error"), "{message}")
+        }
+        Err(e) => panic!("expected a server error, got {e:?}"),
+        Ok(_) => panic!("expected the extractor to fail"),
+    }
+}
+
+#[actix_web::test]
+async fn extract_needs_a_request() {
+    let runtime = create_runtime();
+    let (cx, disposer) = raw_scope_and_disposer(runtime);
+    let result = leptos_actix::extract::<HttpRequest>(cx).await;
+    disposer.dispose();
+    runtime.dispose();
+
+    assert!(matches!(result, Err(ServerFnError::ServerError(_))));
+}
@@ -0,0 +1,40 @@
+use leptos::*;
+use leptos_router::*;
+use std::collections::HashMap;
+
+#[actix_web::test]
+async fn static_sites_render_on_the_actix_runtime() {
+    let site_root = std::env::temp_dir()
+        .join(format!("leptos-actix-static-site-{}", std::process::id()));
+    let options = LeptosOptions::builder()
+        .output_name("app")
+        .site_root(site_root.to_string_lossy().to_string())
+        .build();
+
+    let files = leptos_actix::generate_static_site(
+        &options,
+        |cx| {
+            let text = create_resource(cx, || (), |_| async { "Home".to_string() });
+            view! { cx,
+                <Router>
+                    <Routes>
+                        <Route path="" view=move |cx| view! { cx,
+                            <Suspense fallback=|| ()>{move || text.read()}</Suspense>
+                        }/>
+                    </Routes>
+                </Router>
+            }
+        },
+        HashMap::new(),
+        true,
+    )
+    .await
+    .unwrap();
+    let home = std::fs::read_to_string(site_root.join("index.html"));
+    std::fs::remove_dir_all(&site_root).unwrap();
+
+    assert_eq!(files, vec![site_root.join("index.html")]);
+    let home = home.unwrap();
+    assert!(home.contains("Home"), "{home}");
+    assert!(home.contains("/pkg/app.js"), "{home}");
+}
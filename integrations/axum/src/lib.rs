@@ -5,118 +5,229 @@
 //! For more details on how to use the integrations, see the
 //! [`examples`](https://github.com/leptos-rs/leptos/tree/main/examples)
 //! directory in the Leptos repository.
+//!
+//! The handlers that render your app and run server functions are built on the services in
+//! [leptos_tower], which can also be used with hyper, or any other server built on tower.
 
 use axum::{
     body::{Body, Bytes, Full, StreamBody},
-    extract::Path,
+    extract::{FromRequestParts, OriginalUri, Path, State},
     http::{
         header::{HeaderName, HeaderValue},
-        HeaderMap, Request, StatusCode,
+        Request, StatusCode,
+    },
+    middleware::Next,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
     },
-    response::IntoResponse,
     routing::get,
 };
-use futures::{Future, SinkExt, Stream, StreamExt};
-use http::{header, method::Method, uri::Uri, version::Version, Response};
+use futures::{Future, Stream, StreamExt, TryStreamExt};
+use http::{header, method::Method, Response};
 use hyper::body;
 use leptos::{
-    leptos_server::{server_fn_by_path, Payload},
+    leptos_server::{
+        check_server_fn_urls, openapi_document, subscribe_to_channel,
+        CachedPage, PageCache, Session, SessionOptions,
+    },
+    ssr::{Nonce, SsrMode},
     *,
 };
-use leptos_meta::MetaContext;
 use leptos_router::*;
+use leptos_tower::{authorize_channel, ExtractorParts, SERVER_TIMING};
+pub use leptos_tower::{
+    generate_request_parts, redirect, AssetCacheControl, RequestParts,
+    ResponseOptions, ResponseParts,
+};
 use parking_lot::RwLock;
-use std::{io, pin::Pin, sync::Arc};
-use tokio::task::{spawn_blocking, LocalSet};
-
-/// A struct to hold the parts of the incoming Request. Since `http::Request` isn't cloneable, we're forced
-/// to construct this for Leptos to use in Axum
-#[derive(Debug, Clone)]
-pub struct RequestParts {
-    pub version: Version,
-    pub method: Method,
-    pub uri: Uri,
-    pub headers: HeaderMap<HeaderValue>,
-    pub body: Bytes,
-}
-/// This struct lets you define headers and override the status of the Response from an Element or a Server Function
-/// Typically contained inside of a ResponseOptions. Setting this is useful for cookies and custom responses.
-#[derive(Debug, Clone, Default)]
-pub struct ResponseParts {
-    pub status: Option<StatusCode>,
-    pub headers: HeaderMap,
+use std::{
+    collections::HashMap, convert::Infallible, io, path::PathBuf, pin::Pin,
+    sync::Arc,
+};
+use tokio::task::LocalSet;
+
+/// Runs an Axum extractor against the current request, from inside a server function or
+/// a component being rendered on the server. This works for any extractor that implements
+/// [FromRequestParts] without needing state, like [TypedHeader](axum::TypedHeader),
+/// [Query](axum::extract::Query), or an [Extension](axum::Extension) added by a middleware
+/// layer, for example the authenticated user.
+///
+/// If the extractor rejects the request, the text of the response its rejection would have
+/// sent, like `Failed to deserialize query string`, is returned as a
+/// [ServerFnError::ServerError].
+/// ```ignore
+/// #[server(WhoAmI, "/api")]
+/// pub async fn who_am_i(cx: Scope) -> Result<String, ServerFnError> {
+///     let Extension(user): Extension<User> = leptos_axum::extract(cx).await?;
+///     Ok(user.name)
+/// }
+/// ```
+pub async fn extract<T>(cx: leptos::Scope) -> Result<T, ServerFnError>
+where
+    T: FromRequestParts<()>,
+{
+    extract_with_state(cx, &()).await
 }
 
-impl ResponseParts {
-    /// Insert a header, overwriting any previous value with the same key
-    pub fn insert_header(&mut self, key: HeaderName, value: HeaderValue) {
-        self.headers.insert(key, value);
-    }
-    /// Append a header, leaving any header with the same key intact
-    pub fn append_header(&mut self, key: HeaderName, value: HeaderValue) {
-        self.headers.append(key, value);
+/// Runs an Axum extractor that needs your app’s state against the current request, like
+/// [State](axum::extract::State). Otherwise, this is the same as [extract].
+/// ```ignore
+/// #[server(CountPosts, "/api")]
+/// pub async fn count_posts(cx: Scope) -> Result<usize, ServerFnError> {
+///     let state = use_context::<AppState>(cx).unwrap();
+///     let State(pool): State<PgPool> =
+///         leptos_axum::extract_with_state(cx, &state).await?;
+///     Ok(count(&pool).await?)
+/// }
+/// ```
+pub async fn extract_with_state<T, S>(
+    cx: leptos::Scope,
+    state: &S,
+) -> Result<T, ServerFnError>
+where
+    T: FromRequestParts<S>,
+{
+    let parts = use_context::<ExtractorParts>(cx).ok_or_else(|| {
+        ServerFnError::ServerError(
+            "couldn't find the request to extract from; `extract` only works \
+             in server functions and components run by leptos_axum"
+                .to_string(),
+        )
+    })?;
+    let mut parts = parts.0.lock().await;
+    match T::from_request_parts(&mut parts, state).await {
+        Ok(value) => Ok(value),
+        Err(rejection) => {
+            let body = body::to_bytes(rejection.into_response().into_body())
+                .await
+                .unwrap_or_default();
+            Err(ServerFnError::ServerError(
+                String::from_utf8_lossy(&body).into_owned(),
+            ))
+        }
     }
 }
 
-/// Adding this Struct to your Scope inside of a Server Fn or Element will allow you to override details of the Response
-/// like status and add Headers/Cookies. Because Elements and Server Fns are lower in the tree than the Response generation
-/// code, it needs to be wrapped in an `Arc<RwLock<>>` so that it can be surfaced.
-#[derive(Debug, Clone, Default)]
-pub struct ResponseOptions(pub Arc<RwLock<ResponseParts>>);
-
-impl ResponseOptions {
-    /// A less boilerplatey way to overwrite the contents of `ResponseOptions` with a new `ResponseParts`
-    pub fn overwrite(&self, parts: ResponseParts) {
-        let mut writable = self.0.write();
-        *writable = parts
-    }
-    /// Set the status of the returned Response
-    pub fn set_status(&self, status: StatusCode) {
-        let mut writeable = self.0.write();
-        let res_parts = &mut *writeable;
-        res_parts.status = Some(status);
-    }
-    /// Insert a header, overwriting any previous value with the same key
-    pub fn insert_header(&self, key: HeaderName, value: HeaderValue) {
-        let mut writeable = self.0.write();
-        let res_parts = &mut *writeable;
-        res_parts.headers.insert(key, value);
-    }
-    /// Append a header, leaving any header with the same key intact
-    pub fn append_header(&self, key: HeaderName, value: HeaderValue) {
-        let mut writeable = self.0.write();
-        let res_parts = &mut *writeable;
-        res_parts.headers.append(key, value);
+/// An Axum middleware that gives each visitor a [Session], identified by a signed cookie.
+///
+/// Pages rendered by the integration, and server functions, can read the session from
+/// context with `use_context::<Session>(cx)`. The page also gets a
+/// `<meta name="csrf-token">` tag with the session’s CSRF token, which `<ActionForm/>`,
+/// `<MultiActionForm/>` and server function calls send back, and [handle_server_fns]
+/// rejects server function calls without it with `403 Forbidden`.
+///
+/// ```
+/// use axum::{middleware, routing::post, Router};
+/// use leptos::leptos_server::SessionOptions;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// let sessions = SessionOptions::new(std::env::var("SESSION_KEY").unwrap());
+/// let app: Router = Router::new()
+///     .route("/api/*fn_name", post(leptos_axum::handle_server_fns))
+///     .layer(middleware::from_fn_with_state(
+///         sessions,
+///         leptos_axum::handle_sessions,
+///     ));
+/// # }
+/// ```
+pub async fn handle_sessions<B>(
+    State(options): State<SessionOptions>,
+    mut req: Request<B>,
+    next: Next<B>,
+) -> axum::response::Response {
+    let cookies = req
+        .headers()
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let session = options.load(Some(&cookies));
+    req.extensions_mut().insert(session.clone());
+
+    let mut res = next.run(req).await;
+    if let Some(cookie) = options
+        .save(&session)
+        .and_then(|cookie| HeaderValue::from_str(&cookie).ok())
+    {
+        res.headers_mut().append(header::SET_COOKIE, cookie);
     }
+    res
 }
 
-/// Provides an easy way to redirect the user from within a server function. Mimicing the Remix `redirect()`,
-/// it sets a StatusCode of 302 and a LOCATION header with the provided value.
-/// If looking to redirect from the client, `leptos_router::use_navigate()` should be used instead
-pub fn redirect(cx: leptos::Scope, path: &str) {
-    if let Some(response_options) = use_context::<ResponseOptions>(cx) {
-        response_options.set_status(StatusCode::FOUND);
-        response_options.insert_header(
-            header::LOCATION,
-            header::HeaderValue::from_str(path)
-                .expect("Failed to create HeaderValue"),
-        );
-    }
+/// An Axum handler that streams the messages broadcast on a [Channel](leptos::Channel) to
+/// the browser, as [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events),
+/// for [create_channel_signal](leptos::create_channel_signal). The stream ends when the
+/// browser disconnects. Anyone can subscribe to any channel; to decide who can, use
+/// [handle_channel_with_authorization] instead.
+///
+/// Mount it at `/channels/:name`, or at another prefix set with
+/// [Channel::with_prefix](leptos::Channel::with_prefix):
+/// ```
+/// use axum::{routing::get, Router};
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// let app: Router = Router::new()
+///     .route("/channels/:name", get(leptos_axum::handle_channel));
+/// # }
+/// ```
+pub async fn handle_channel(
+    uri: OriginalUri,
+    req: Request<Body>,
+) -> axum::response::Response {
+    handle_channel_with_authorization(uri, req, |_, _| true).await
 }
 
-/// Decomposes an HTTP request into its parts, allowing you to read its headers
-/// and other data without consuming the body.
-pub async fn generate_request_parts(req: Request<Body>) -> RequestParts {
-    // provide request headers as context in server scope
-    let (parts, body) = req.into_parts();
-    let body = body::to_bytes(body).await.unwrap_or_default();
-    RequestParts {
-        method: parts.method,
-        uri: parts.uri,
-        headers: parts.headers,
-        version: parts.version,
-        body,
+/// Like [handle_channel], but only lets the browser subscribe if `authorize` returns `true`,
+/// and responds with `403 Forbidden` otherwise.
+///
+/// `authorize` is called with the URL of the channel, in a [Scope](leptos::Scope) that has
+/// the [RequestParts], and the [Session] if the request went through [handle_sessions].
+/// ```
+/// use axum::{routing::get, Router};
+/// use leptos::{leptos_server::Session, *};
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// let app: Router = Router::new().route(
+///     "/channels/:name",
+///     get(|uri, req| {
+///         leptos_axum::handle_channel_with_authorization(uri, req, |cx, _| {
+///             use_context::<Session>(cx)
+///                 .and_then(|session| session.get::<String>("user"))
+///                 .is_some()
+///         })
+///     }),
+/// );
+/// # }
+/// ```
+pub async fn handle_channel_with_authorization(
+    OriginalUri(uri): OriginalUri,
+    req: Request<Body>,
+    authorize: impl FnOnce(leptos::Scope, &str) -> bool + Send,
+) -> axum::response::Response {
+    let url = uri.path().to_string();
+    let session = req.extensions().get::<Session>().cloned();
+    let req_parts = generate_request_parts(req).await;
+    let authorized = authorize_channel(
+        &url,
+        session,
+        move |cx| provide_context(cx, req_parts),
+        authorize,
+    );
+    if !authorized {
+        return (
+            StatusCode::FORBIDDEN,
+            "not allowed to subscribe to this channel",
+        )
+            .into_response();
     }
+
+    let messages = subscribe_to_channel(&url)
+        .map(|message| Ok::<_, Infallible>(Event::default().data(message)));
+    Sse::new(messages)
+        .keep_alive(KeepAlive::default())
+        .into_response()
 }
 
 /// An Axum handlers to listens for a request with Leptos server function arguments in the body,
@@ -134,6 +245,11 @@ pub async fn generate_request_parts(req: Request<Body>) -> RequestParts {
 /// async fn main() {
 ///     let addr = SocketAddr::from(([127, 0, 0, 1], 8082));
 ///
+///     // server functions are registered automatically; this warns about any
+///     // two of them that share a URL now, rather than on the first request.
+///     // (`leptos_routes` does this for you.)
+///     leptos::leptos_server::check_server_fn_urls();
+///
 ///     // build our application with a route
 ///     let app = Router::new()
 ///         .route("/api/*fn_name", post(leptos_axum::handle_server_fns));
@@ -154,12 +270,14 @@ pub async fn generate_request_parts(req: Request<Body>) -> RequestParts {
 /// This function always provides context values including the following types:
 /// - [RequestParts]
 /// - [ResponseOptions]
+///
+/// It also provides the [Session], if the request went through [handle_sessions], and
+/// responds with `403 Forbidden` if the request doesn’t carry the session’s CSRF token.
 pub async fn handle_server_fns(
     Path(fn_name): Path<String>,
-    headers: HeaderMap,
     req: Request<Body>,
 ) -> impl IntoResponse {
-    handle_server_fns_inner(fn_name, headers, |_| {}, req).await
+    handle_server_fns_inner(fn_name, |_| {}, req).await
 }
 
 /// An Axum handlers to listens for a request with Leptos server function arguments in the body,
@@ -176,156 +294,70 @@ pub async fn handle_server_fns(
 /// This function always provides context values including the following types:
 /// - [RequestParts]
 /// - [ResponseOptions]
+///
+/// It also provides the [Session], if the request went through [handle_sessions], and
+/// responds with `403 Forbidden` if the request doesn’t carry the session’s CSRF token.
 pub async fn handle_server_fns_with_context(
     Path(fn_name): Path<String>,
-    headers: HeaderMap,
     additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
     req: Request<Body>,
 ) -> impl IntoResponse {
-    handle_server_fns_inner(fn_name, headers, additional_context, req).await
+    handle_server_fns_inner(fn_name, additional_context, req).await
 }
 
 async fn handle_server_fns_inner(
     fn_name: String,
-    headers: HeaderMap,
     additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
     req: Request<Body>,
 ) -> impl IntoResponse {
-    // Axum Path extractor doesn't remove the first slash from the path, while Actix does
-    let fn_name = fn_name
-        .strip_prefix('/')
-        .map(|fn_name| fn_name.to_string())
-        .unwrap_or(fn_name);
-
-    let (tx, rx) = futures::channel::oneshot::channel();
-    spawn_blocking({
-        move || {
-            tokio::runtime::Runtime::new()
-                .expect("couldn't spawn runtime")
-                .block_on({
-                    async move {
-                        let res = if let Some(server_fn) =
-                            server_fn_by_path(fn_name.as_str())
-                        {
-                            let runtime = create_runtime();
-                            let (cx, disposer) =
-                                raw_scope_and_disposer(runtime);
-
-                            additional_context(cx);
-
-                            let req_parts = generate_request_parts(req).await;
-                            // Add this so we can get details about the Request
-                            provide_context(cx, req_parts.clone());
-                            // Add this so that we can set headers and status of the response
-                            provide_context(cx, ResponseOptions::default());
-
-                            match server_fn(cx, &req_parts.body).await {
-                                Ok(serialized) => {
-                                    // If ResponseOptions are set, add the headers and status to the request
-                                    let res_options =
-                                        use_context::<ResponseOptions>(cx);
-
-                                    // clean up the scope, which we only needed to run the server fn
-                                    disposer.dispose();
-                                    runtime.dispose();
-
-                                    // if this is Accept: application/json then send a serialized JSON response
-                                    let accept_header = headers
-                                        .get("Accept")
-                                        .and_then(|value| value.to_str().ok());
-                                    let mut res = Response::builder();
-
-                                    // Add headers from ResponseParts if they exist. These should be added as long
-                                    // as the server function returns an OK response
-                                    let res_options_outer =
-                                        res_options.unwrap().0;
-                                    let res_options_inner =
-                                        res_options_outer.read();
-                                    let (status, mut res_headers) = (
-                                        res_options_inner.status,
-                                        res_options_inner.headers.clone(),
-                                    );
-
-                                    if let Some(header_ref) = res.headers_mut()
-                                    {
-                                        header_ref.extend(res_headers.drain());
-                                    };
-
-                                    if accept_header == Some("application/json")
-                                        || accept_header
-                                            == Some(
-                                                "application/\
-                                                 x-www-form-urlencoded",
-                                            )
-                                        || accept_header
-                                            == Some("application/cbor")
-                                    {
-                                        res = res.status(StatusCode::OK);
-                                    }
-                                    // otherwise, it's probably a <form> submit or something: redirect back to the referrer
-                                    else {
-                                        let referer = headers
-                                            .get("Referer")
-                                            .and_then(|value| {
-                                                value.to_str().ok()
-                                            })
-                                            .unwrap_or("/");
-
-                                        res = res
-                                            .status(StatusCode::SEE_OTHER)
-                                            .header("Location", referer);
-                                    }
-                                    // Override StatusCode if it was set in a Resource or Element
-                                    res = match status {
-                                        Some(status) => res.status(status),
-                                        None => res,
-                                    };
-                                    match serialized {
-                                        Payload::Binary(data) => res
-                                            .header(
-                                                "Content-Type",
-                                                "application/cbor",
-                                            )
-                                            .body(Full::from(data)),
-                                        Payload::Url(data) => res
-                                            .header(
-                                                "Content-Type",
-                                                "application/\
-                                                 x-www-form-urlencoded",
-                                            )
-                                            .body(Full::from(data)),
-                                        Payload::Json(data) => res
-                                            .header(
-                                                "Content-Type",
-                                                "application/json",
-                                            )
-                                            .body(Full::from(data)),
-                                    }
-                                }
-                                Err(e) => Response::builder()
-                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                                    .body(Full::from(e.to_string())),
-                            }
-                        } else {
-                            Response::builder()
-                                .status(StatusCode::BAD_REQUEST)
-                                .body(Full::from(format!(
-                                    "Could not find a server function at the \
-                                     route {fn_name}. \n\nIt's likely that \
-                                     you need to call ServerFn::register() on \
-                                     the server function type, somewhere in \
-                                     your `main` function."
-                                )))
-                        }
-                        .expect("could not build Response");
-
-                        _ = tx.send(res);
-                    }
-                })
-        }
-    });
+    leptos_tower::handle_server_fn(&fn_name, req, additional_context).await
+}
 
-    rx.await.unwrap()
+/// Returns an Axum [Handler](axum::handler::Handler) that responds with an
+/// [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document describing all of your server
+/// functions, which can be used to generate API clients or documentation. (See
+/// [openapi_document](leptos::leptos_server::openapi_document).)
+///
+/// ```
+/// use axum::{
+///     routing::{get, post},
+///     Router,
+/// };
+/// use std::net::SocketAddr;
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = SocketAddr::from(([127, 0, 0, 1], 8082));
+///
+///     let app = Router::new()
+///         .route(
+///             "/api/openapi.json",
+///             get(leptos_axum::handle_openapi("My App", "1.0.0")),
+///         )
+///         .route("/api/*fn_name", post(leptos_axum::handle_server_fns));
+///
+///     axum::Server::bind(&addr)
+///         .serve(app.into_make_service())
+///         .await
+///         .unwrap();
+/// }
+/// # }
+/// ```
+pub fn handle_openapi(
+    title: &str,
+    version: &str,
+) -> impl Fn() -> std::future::Ready<Response<Full<Bytes>>> + Clone + Send + 'static
+{
+    let document = Bytes::from(openapi_document(title, version).to_string());
+    move || {
+        std::future::ready(
+            Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Full::from(document.clone()))
+                .expect("could not build Response"),
+        )
+    }
 }
 
 pub type PinnedHtmlStream =
@@ -381,6 +413,8 @@ pub type PinnedHtmlStream =
 /// - [ResponseOptions]
 /// - [MetaContext](leptos_meta::MetaContext)
 /// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
 pub fn render_app_to_stream<IV>(
     options: LeptosOptions,
     app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
@@ -426,6 +460,8 @@ where
 /// - [ResponseOptions]
 /// - [MetaContext](leptos_meta::MetaContext)
 /// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
 pub fn render_app_to_stream_with_context<IV>(
     options: LeptosOptions,
     additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
@@ -444,191 +480,418 @@ pub fn render_app_to_stream_with_context<IV>(
 where
     IV: IntoView,
 {
+    render_app_to_stream_with_mode(
+        options,
+        additional_context,
+        app_fn,
+        SsrMode::OutOfOrder,
+    )
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that listens for a `GET` request and tries
+/// to route it using [leptos_router], serving an HTML stream of your application in document order.
+///
+/// The stream is held at each `<Suspense/>` until the resources read under it have resolved,
+/// and its HTML is written in place, so clients that don't run JavaScript, like search
+/// engine crawlers, receive the complete page. Everything before the first pending
+/// `<Suspense/>` is still sent immediately. (See [render_to_stream_in_order](leptos::ssr::render_to_stream_in_order).)
+///
+/// This can be used for individual routes, alongside [render_app_to_stream] for the rest:
+/// ```
+/// use axum::{routing::get, Router};
+/// use leptos::*;
+/// use leptos_config::get_configuration;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> impl IntoView {
+///     view! { cx, <main>"Hello, world!"</main> }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[tokio::main]
+/// async fn main() {
+///     let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+///     let leptos_options = conf.leptos_options;
+///     let addr = leptos_options.site_addr.clone();
+///
+///     let app = Router::new()
+///         .route(
+///             "/blog/:slug",
+///             get(leptos_axum::render_app_to_stream_in_order(
+///                 leptos_options.clone(),
+///                 |cx| view! { cx, <MyApp/> },
+///             )),
+///         )
+///         .fallback(leptos_axum::render_app_to_stream(
+///             leptos_options,
+///             |cx| view! { cx, <MyApp/> },
+///         ));
+///
+///     axum::Server::bind(&addr)
+///         .serve(app.into_make_service())
+///         .await
+///         .unwrap();
+/// }
+/// # }
+/// ```
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [RequestParts]
+/// - [ResponseOptions]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
+pub fn render_app_to_stream_in_order<IV>(
+    options: LeptosOptions,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
+) -> impl Fn(
+    Request<Body>,
+) -> Pin<
+    Box<
+        dyn Future<Output = Response<StreamBody<PinnedHtmlStream>>>
+            + Send
+            + 'static,
+    >,
+> + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView,
+{
+    render_app_to_stream_in_order_with_context(options, |_| {}, app_fn)
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that listens for a `GET` request and tries
+/// to route it using [leptos_router], serving an HTML stream of your application in document order.
+///
+/// This version allows us to pass Axum State/Extension/Extractor or other infro from Axum or network
+/// layers above Leptos itself, like [render_app_to_stream_with_context]. Otherwise, this function
+/// is identical to [render_app_to_stream_in_order].
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [RequestParts]
+/// - [ResponseOptions]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request went through [handle_sessions]
+pub fn render_app_to_stream_in_order_with_context<IV>(
+    options: LeptosOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
+) -> impl Fn(
+    Request<Body>,
+) -> Pin<
+    Box<
+        dyn Future<Output = Response<StreamBody<PinnedHtmlStream>>>
+            + Send
+            + 'static,
+    >,
+> + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView,
+{
+    render_app_to_stream_with_mode(
+        options,
+        additional_context,
+        app_fn,
+        SsrMode::InOrder,
+    )
+}
+
+fn render_app_to_stream_with_mode<IV>(
+    options: LeptosOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
+    mode: SsrMode,
+) -> impl Fn(
+    Request<Body>,
+) -> Pin<
+    Box<
+        dyn Future<Output = Response<StreamBody<PinnedHtmlStream>>>
+            + Send
+            + 'static,
+    >,
+> + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView,
+{
+    let render = leptos_tower::render_app_to_stream_with_mode(
+        options,
+        additional_context,
+        app_fn,
+        mode,
+    );
+
     move |req: Request<Body>| {
-        Box::pin({
-            let options = options.clone();
-            let app_fn = app_fn.clone();
-            let add_context = additional_context.clone();
-            let default_res_options = ResponseOptions::default();
-            let res_options2 = default_res_options.clone();
-            let res_options3 = default_res_options.clone();
-
-            async move {
-                // Need to get the path and query string of the Request
-                // For reasons that escape me, if the incoming URI protocol is https, it provides the absolute URI
-                // if http, it returns a relative path. Adding .path() seems to make it explicitly return the relative uri
-                let path = req.uri().path_and_query().unwrap().as_str();
-
-                let full_path = format!("http://leptos.dev{path}");
-
-                let (mut tx, rx) = futures::channel::mpsc::channel(8);
-
-                spawn_blocking({
-                    let app_fn = app_fn.clone();
-                    let add_context = add_context.clone();
-                    move || {
-                        tokio::runtime::Runtime::new()
-                            .expect("couldn't spawn runtime")
-                            .block_on({
-                                let app_fn = app_fn.clone();
-                                let add_context = add_context.clone();
-                                async move {
-                                    tokio::task::LocalSet::new()
-                                        .run_until(async {
-                                            let app = {
-                                                let full_path = full_path.clone();
-                                                let req_parts = generate_request_parts(req).await;
-                                                move |cx| {
-                                                    let integration = ServerIntegration {
-                                                        path: full_path.clone(),
-                                                    };
-                                                    provide_context(
-                                                        cx,
-                                                        RouterIntegrationContext::new(integration),
-                                                    );
-                                                    provide_context(cx, MetaContext::new());
-                                                    provide_context(cx, req_parts);
-                                                    provide_context(cx, default_res_options);
-                                                    provide_server_redirect(cx, move |path| redirect(cx, path));
-                                                    app_fn(cx).into_view(cx)
-                                                }
-                                            };
-
-                                            let (bundle, runtime, scope) =
-                                                leptos::leptos_dom::ssr::render_to_stream_with_prefix_undisposed_with_context(
-                                                    app,
-                                                    |cx| {
-                                                        let head = use_context::<MetaContext>(cx)
-                                                            .map(|meta| meta.dehydrate())
-                                                            .unwrap_or_default();
-                                                        format!("{head}</head><body>").into()
-                                                    },
-                                                    add_context,
-                                                );
-
-                                            let cx = Scope { runtime, id: scope };
-                                            let (head, tail) = html_parts(&options, use_context::<MetaContext>(cx).as_ref());
-
-                                            _ = tx.send(head).await;
-                                            let mut shell = Box::pin(bundle);
-                                            while let Some(fragment) = shell.next().await {
-                                                _ = tx.send(fragment).await;
-                                            }
-                                            _ = tx.send(tail.to_string()).await;
-
-                                            // Extract the value of ResponseOptions from here
-                                            let res_options =
-                                                use_context::<ResponseOptions>(cx).unwrap();
-
-                                            let new_res_parts = res_options.0.read().clone();
-
-                                            let mut writable = res_options2.0.write();
-                                            *writable = new_res_parts;
-
-                                            runtime.dispose();
-
-                                            tx.close_channel();
-                                        })
-                                        .await;
-                                }
-                            });
-                    }
-                });
-
-                let mut stream = Box::pin(rx.map(|html| Ok(Bytes::from(html))));
-
-                // Get the first and second chunks in the stream, which renders the app shell, and thus allows Resources to run
-                let first_chunk = stream.next().await;
-                let second_chunk = stream.next().await;
-
-                // Extract the resources now that they've been rendered
-                let res_options = res_options3.0.read();
-
-                let complete_stream = futures::stream::iter([
-                    first_chunk.unwrap(),
-                    second_chunk.unwrap(),
-                ])
-                .chain(stream);
-
-                let mut res = Response::new(StreamBody::new(Box::pin(
-                    complete_stream,
-                )
-                    as PinnedHtmlStream));
-
-                if let Some(status) = res_options.status {
-                    *res.status_mut() = status
+        let res = render(req);
+        Box::pin(async move {
+            res.await.map(|body| {
+                let body = body.map_err(io::Error::other);
+                StreamBody::new(Box::pin(body) as PinnedHtmlStream)
+            })
+        })
+    }
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves your application like
+/// [render_app_to_stream], but keeps the rendered HTML of each page in a [PageCache] and
+/// serves it from there until it expires.
+///
+/// A page is cached by its path, and by any other parts of the request the cache is
+/// configured to vary by. Only successful responses to `GET` requests that don’t set
+/// cookies are cached. Once a cached page is stale, it is still served, while the page
+/// is rendered again in the background to replace it.
+///
+/// Responses are sent once the whole page has rendered, rather than streamed. A cached
+/// page is the same for everyone who receives it, so avoid caching pages that depend on
+/// who is asking for them. The one exception is the CSP nonce, which is replaced by a new
+/// one each time the page is served. (See [CachedPage::replace_nonce].) Cached pages aren’t compressed,
+/// even if [LeptosOptions::compression] is set.
+///
+/// To let server functions invalidate cached pages, provide the same cache to them:
+/// ```
+/// use axum::{routing::post, Router};
+/// use leptos::{leptos_server::PageCache, *};
+/// use leptos_config::get_configuration;
+/// use std::time::Duration;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> impl IntoView {
+///     view! { cx, <main>"Hello, world!"</main> }
+/// }
+///
+/// # if false { // don't actually try to run a server in a doctest...
+/// #[tokio::main]
+/// async fn main() {
+///     let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+///     let leptos_options = conf.leptos_options;
+///     let addr = leptos_options.site_addr.clone();
+///     let cache = PageCache::new(Duration::from_secs(60));
+///
+///     let app = Router::new()
+///         .route(
+///             "/api/*fn_name",
+///             post({
+///                 let cache = cache.clone();
+///                 move |path, req| {
+///                     leptos_axum::handle_server_fns_with_context(
+///                         path,
+///                         move |cx| provide_context(cx, cache.clone()),
+///                         req,
+///                     )
+///                 }
+///             }),
+///         )
+///         .fallback(leptos_axum::render_app_to_stream_cached(
+///             leptos_options,
+///             cache,
+///             |cx| view! { cx, <MyApp/> },
+///         ));
+///
+///     axum::Server::bind(&addr)
+///         .serve(app.into_make_service())
+///         .await
+///         .unwrap();
+/// }
+/// # }
+/// ```
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [RequestParts]
+/// - [ResponseOptions]
+/// - [PageCache]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+///
+/// Because a cached page is shared by every visitor, `GET` requests are rendered without
+/// the [Session] from [handle_sessions]. Cached pages don’t include a CSRF token, so pages
+/// that call server functions shouldn’t be cached if you use sessions.
+pub fn render_app_to_stream_cached<IV>(
+    options: LeptosOptions,
+    cache: PageCache,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
+) -> impl Fn(
+    Request<Body>,
+) -> Pin<
+    Box<
+        dyn Future<Output = Response<StreamBody<PinnedHtmlStream>>>
+            + Send
+            + 'static,
+    >,
+> + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView,
+{
+    render_app_to_stream_cached_with_context(options, cache, |_| {}, app_fn)
+}
+
+/// Returns an Axum [Handler](axum::handler::Handler) that serves your application from a
+/// [PageCache], like [render_app_to_stream_cached].
+///
+/// This version allows us to pass Axum State/Extension/Extractor or other infro from Axum or network
+/// layers above Leptos itself, like [render_app_to_stream_with_context]. Otherwise, this function
+/// is identical to [render_app_to_stream_cached].
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [RequestParts]
+/// - [ResponseOptions]
+/// - [PageCache]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+pub fn render_app_to_stream_cached_with_context<IV>(
+    options: LeptosOptions,
+    cache: PageCache,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
+) -> impl Fn(
+    Request<Body>,
+) -> Pin<
+    Box<
+        dyn Future<Output = Response<StreamBody<PinnedHtmlStream>>>
+            + Send
+            + 'static,
+    >,
+> + Clone
+       + Send
+       + 'static
+where
+    IV: IntoView,
+{
+    let render = render_app_to_stream_with_context(
+        options,
+        {
+            let cache = cache.clone();
+            move |cx| {
+                provide_context(cx, cache.clone());
+                additional_context(cx);
+            }
+        },
+        app_fn,
+    );
+
+    move |req: Request<Body>| {
+        let render = render.clone();
+        let cache = cache.clone();
+        Box::pin(async move {
+            if req.method() != Method::GET {
+                return render(req).await;
+            }
+
+            // a cached page is shared by every visitor, so it's rendered without a session
+            let mut req = req;
+            req.extensions_mut().remove::<Session>();
+
+            let key = cache.key(req.uri().path(), req.uri().query(), |name| {
+                req.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from)
+            });
+            // the page is stored as text, and may be sent to any browser
+            req.headers_mut().remove(header::ACCEPT_ENCODING);
+
+            if let Some(page) = cache.get(&key) {
+                if cache.is_stale(&page) && cache.start_refresh(&key) {
+                    let req = clone_request(&req);
+                    tokio::spawn(async move {
+                        let generation = cache.generation();
+                        let page = render_page(render(req).await).await;
+                        if is_cacheable(&page) {
+                            cache.insert(key.clone(), page, generation);
+                        }
+                        cache.finish_refresh(&key);
+                    });
                 }
-                let mut res_headers = res_options.headers.clone();
-                res.headers_mut().extend(res_headers.drain());
+                return cached_response(page);
+            }
 
-                res
+            let generation = cache.generation();
+            let page = render_page(render(req).await).await;
+            if is_cacheable(&page) {
+                cache.insert(key, page.clone(), generation);
             }
+            cached_response(page)
         })
     }
 }
 
-fn html_parts(
-    options: &LeptosOptions,
-    meta: Option<&MetaContext>,
-) -> (String, &'static str) {
-    let pkg_path = &options.site_pkg_dir;
-    let output_name = &options.output_name;
-
-    // Because wasm-pack adds _bg to the end of the WASM filename, and we want to mantain compatibility with it's default options
-    // we add _bg to the wasm files if cargo-leptos doesn't set the env var LEPTOS_OUTPUT_NAME
-    // Otherwise we need to add _bg because wasm_pack always does. This is not the same as options.output_name, which is set regardless
-    let mut wasm_output_name = output_name.clone();
-    if std::env::var("LEPTOS_OUTPUT_NAME").is_err() {
-        wasm_output_name.push_str("_bg");
+/// Copies everything but the body of a `GET` request, to render the page again later.
+fn clone_request(req: &Request<Body>) -> Request<Body> {
+    let mut new_req = Request::new(Body::empty());
+    *new_req.method_mut() = req.method().clone();
+    *new_req.uri_mut() = req.uri().clone();
+    *new_req.version_mut() = req.version();
+    *new_req.headers_mut() = req.headers().clone();
+    new_req
+}
+
+/// Waits for the whole HTML stream of a rendered page.
+async fn render_page(
+    res: Response<StreamBody<PinnedHtmlStream>>,
+) -> CachedPage {
+    let (parts, body) = res.into_parts();
+    let body = body::to_bytes(body).await.unwrap_or_default();
+    CachedPage {
+        status: parts.status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            // the timings of this render don't apply to later responses
+            .filter(|(name, _)| **name != SERVER_TIMING)
+            .filter_map(|(name, value)| {
+                Some((name.to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+        rendered_at: std::time::SystemTime::now(),
     }
+}
 
-    let site_ip = &options.site_addr.ip().to_string();
-    let reload_port = options.reload_port;
-
-    let leptos_autoreload = match std::env::var("LEPTOS_WATCH").is_ok() {
-        true => format!(
-            r#"
-                <script crossorigin="">(function () {{
-                    var ws = new WebSocket('ws://{site_ip}:{reload_port}/live_reload');
-                    ws.onmessage = (ev) => {{
-                        let msg = JSON.parse(ev.data);
-                        if (msg.all) window.location.reload();
-                        if (msg.css) {{
-                            let found = false;
-                            document.querySelectorAll("link").forEach((link) => {{
-                                if (link.getAttribute('href').includes(msg.css)) {{
-                                    let newHref = '/' + href + '?version=' + new Date().getMilliseconds();
-                                    link.setAttribute('href', newHref);
-                                    found = true;
-                                }}
-                            }});
-                            if (!found) console.warn(`CSS hot-reload: Could not find a <link href=/\"${{msg.css}}\"> element`);
-                        }};
-                    }};
-                    ws.onclose = () => console.warn('Live-reload stopped. Manual reload necessary.');
-                }})()
-                </script>
-                "#
-        ),
-        false => "".to_string(),
-    };
+fn is_cacheable(page: &CachedPage) -> bool {
+    page.status == StatusCode::OK.as_u16()
+        && !page
+            .headers
+            .iter()
+            .any(|(name, _)| name == header::SET_COOKIE.as_str())
+}
 
-    let html_metadata =
-        meta.and_then(|mc| mc.html.as_string()).unwrap_or_default();
-    let head = format!(
-        r#"<!DOCTYPE html>
-            <html{html_metadata}>
-                <head>
-                    <meta charset="utf-8"/>
-                    <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                    <link rel="modulepreload" href="/{pkg_path}/{output_name}.js">
-                    <link rel="preload" href="/{pkg_path}/{wasm_output_name}.wasm" as="fetch" type="application/wasm" crossorigin="">
-                    <script type="module">import init, {{ hydrate }} from '/{pkg_path}/{output_name}.js'; init('/{pkg_path}/{wasm_output_name}.wasm').then(hydrate);</script>
-                    {leptos_autoreload}
-                    "#
-    );
-    let tail = "</body></html>";
-    (head, tail)
+fn cached_response(
+    mut page: CachedPage,
+) -> Response<StreamBody<PinnedHtmlStream>> {
+    page.replace_nonce(&Nonce::new().to_string());
+    let age = page
+        .rendered_at
+        .elapsed()
+        .map(|age| age.as_secs())
+        .unwrap_or_default();
+    let body = futures::stream::once(async move { Ok(Bytes::from(page.body)) });
+    let mut res =
+        Response::new(StreamBody::new(Box::pin(body) as PinnedHtmlStream));
+    *res.status_mut() = StatusCode::from_u16(page.status).unwrap_or_default();
+    for (name, value) in page.headers {
+        if let (Ok(name), Ok(value)) =
+            (HeaderName::try_from(name), HeaderValue::try_from(value))
+        {
+            res.headers_mut().append(name, value);
+        }
+    }
+    res.headers_mut()
+        .insert(header::AGE, HeaderValue::from(age));
+    res
 }
 
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
@@ -637,11 +900,24 @@ fn html_parts(
 pub async fn generate_route_list<IV>(
     app_fn: impl FnOnce(Scope) -> IV + 'static,
 ) -> Vec<String>
+where
+    IV: IntoView + 'static,
+{
+    generate_route_list_with_fallback(app_fn).await.0
+}
+
+/// Like [generate_route_list], but also returns whether your app defines a fallback view
+/// for paths that don't match any route. If it does, you can render the app for every
+/// other `GET` request, for example with `.fallback(leptos_axum::render_app_to_stream(...))`,
+/// and unmatched paths will show the fallback with a `404 Not Found` status.
+pub async fn generate_route_list_with_fallback<IV>(
+    app_fn: impl FnOnce(Scope) -> IV + 'static,
+) -> (Vec<String>, bool)
 where
     IV: IntoView + 'static,
 {
     #[derive(Default, Clone, Debug)]
-    pub struct Routes(pub Arc<RwLock<Vec<String>>>);
+    pub struct Routes(pub Arc<RwLock<(Vec<String>, bool)>>);
 
     let routes = Routes::default();
     let routes_inner = routes.clone();
@@ -652,7 +928,10 @@ where
     local
         .run_until(async move {
             tokio::task::spawn_local(async move {
-                let routes = leptos_router::generate_route_list_inner(app_fn);
+                let routes =
+                    leptos_router::generate_route_list_with_fallback_inner(
+                        app_fn,
+                    );
                 let mut writable = routes_inner.0.write();
                 *writable = routes;
             })
@@ -661,18 +940,78 @@ where
         })
         .await;
 
-    let routes = routes.0.read().to_owned();
+    let (routes, has_fallback) = routes.0.read().to_owned();
     // Axum's Router defines Root routes as "/" not ""
     let routes: Vec<String> = routes
         .into_iter()
         .map(|s| if s.is_empty() { "/".to_string() } else { s })
         .collect();
 
-    if routes.is_empty() {
+    let routes = if routes.is_empty() {
         vec!["/".to_string()]
     } else {
         routes
-    }
+    };
+    (routes, has_fallback)
+}
+
+/// Renders a page for each of your app’s routes ahead of time, and writes it to an
+/// `index.html` file in the matching directory under [LeptosOptions::site_root], so
+/// that the site can be served as static files. Dynamic segments are filled in from
+/// `params`, and the pages hydrate if `hydrate` is `true`: see
+/// [leptos_tower::generate_static_site] for the details.
+///
+/// Returns the paths of the files that were written.
+/// ```
+/// use leptos::*;
+/// use leptos_router::*;
+/// use std::collections::HashMap;
+///
+/// #[component]
+/// fn MyApp(cx: Scope) -> impl IntoView {
+///     view! { cx,
+///         <Router>
+///             <Routes>
+///                 <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+///                 <Route path="post/:id" view=|cx| view! { cx, <p>"Post"</p> }/>
+///             </Routes>
+///         </Router>
+///     }
+/// }
+///
+/// # if false { // don't actually try to write files in a doctest...
+/// #[tokio::main]
+/// async fn main() {
+///     let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+///     let posts = (1..=10)
+///         .map(|id| {
+///             let mut params = ParamsMap::new();
+///             params.insert("id".to_string(), id.to_string());
+///             params
+///         })
+///         .collect();
+///     let params = HashMap::from([("/post/:id".to_string(), posts)]);
+///     leptos_axum::generate_static_site(
+///         &conf.leptos_options,
+///         |cx| view! { cx, <MyApp/> },
+///         params,
+///         true,
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// # }
+/// ```
+pub async fn generate_static_site<IV>(
+    options: &LeptosOptions,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+    params: HashMap<String, Vec<ParamsMap>>,
+    hydrate: bool,
+) -> io::Result<Vec<PathBuf>>
+where
+    IV: IntoView + 'static,
+{
+    leptos_tower::generate_static_site(options, app_fn, params, hydrate).await
 }
 
 /// This trait allows one to pass a list of routes and a render function to Axum's router, letting us avoid
@@ -708,6 +1047,9 @@ pub trait LeptosRoutes {
 }
 /// The default implementation of `LeptosRoutes` which takes in a list of paths, and dispatches GET requests
 /// to those paths to Leptos's renderer.
+///
+/// Server functions are registered automatically, and each of these methods logs a warning
+/// if two of them share a URL. (See [check_server_fn_urls].)
 impl LeptosRoutes for axum::Router {
     fn leptos_routes<IV>(
         self,
@@ -718,6 +1060,7 @@ impl LeptosRoutes for axum::Router {
     where
         IV: IntoView + 'static,
     {
+        check_server_fn_urls();
         let mut router = self;
         for path in paths.iter() {
             router = router.route(
@@ -738,6 +1081,7 @@ impl LeptosRoutes for axum::Router {
     where
         IV: IntoView + 'static,
     {
+        check_server_fn_urls();
         let mut router = self;
         for path in paths.iter() {
             router = router.route(
@@ -761,6 +1105,7 @@ impl LeptosRoutes for axum::Router {
         H: axum::handler::Handler<T, (), axum::body::Body>,
         T: 'static,
     {
+        check_server_fn_urls();
         let mut router = self;
         for path in paths.iter() {
             router = router.route(path, get(handler.clone()));
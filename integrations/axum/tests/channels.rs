@@ -0,0 +1,52 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use leptos::*;
+use leptos_axum::RequestParts;
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new().route(
+        "/channels/:name",
+        get(|uri, req| {
+            leptos_axum::handle_channel_with_authorization(
+                uri,
+                req,
+                |cx, url| {
+                    let req = use_context::<RequestParts>(cx).unwrap();
+                    url == "/channels/news"
+                        && req.headers.get("x-user").is_some()
+                },
+            )
+        }),
+    )
+}
+
+async fn subscribe(user: Option<&str>, name: &str) -> StatusCode {
+    let mut req = Request::get(format!("/channels/{name}"));
+    if let Some(user) = user {
+        req = req.header("x-user", user);
+    }
+    let res = app()
+        .oneshot(req.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    if res.status() == StatusCode::OK {
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "text/event-stream");
+    }
+    res.status()
+}
+
+#[tokio::test]
+async fn authorized_requests_subscribe() {
+    assert_eq!(subscribe(Some("ada"), "news").await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn unauthorized_requests_are_forbidden() {
+    assert_eq!(subscribe(None, "news").await, StatusCode::FORBIDDEN);
+    assert_eq!(subscribe(Some("ada"), "admin").await, StatusCode::FORBIDDEN);
+}
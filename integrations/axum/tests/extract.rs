@@ -0,0 +1,100 @@
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{HeaderMap, Request},
+    Extension,
+};
+use leptos::*;
+use leptos_tower::generate_extractor_parts;
+use std::collections::HashMap;
+
+/// Runs `extract` in a scope that has the parts of `req`, the way a server function does.
+async fn with_request<T>(
+    req: Request<Body>,
+    extract: impl FnOnce(Scope) -> T,
+) -> T::Output
+where
+    T: std::future::Future,
+{
+    let (_, parts) = generate_extractor_parts(req).await.unwrap();
+    let runtime = create_runtime();
+    let (cx, disposer) = raw_scope_and_disposer(runtime);
+    provide_context(cx, parts);
+    let output = extract(cx).await;
+    disposer.dispose();
+    runtime.dispose();
+    output
+}
+
+fn request() -> Request<Body> {
+    Request::get("/search?q=leptos")
+        .header("x-request-id", "42")
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn extractors_run_against_the_request() {
+    let (Query(query), headers) = with_request(request(), |cx| async move {
+        let query: Query<HashMap<String, String>> =
+            leptos_axum::extract(cx).await.unwrap();
+        let headers: HeaderMap = leptos_axum::extract(cx).await.unwrap();
+        (query, headers)
+    })
+    .await;
+
+    assert_eq!(query["q"], "leptos");
+    assert_eq!(headers["x-request-id"], "42");
+}
+
+#[tokio::test]
+async fn extractors_can_use_state() {
+    let query = with_request(request(), |cx| async move {
+        let Query(query): Query<HashMap<String, String>> =
+            leptos_axum::extract_with_state(cx, &"state").await.unwrap();
+        query
+    })
+    .await;
+
+    assert_eq!(query["q"], "leptos");
+}
+
+#[tokio::test]
+async fn rejections_are_server_errors_with_their_text() {
+    let (query, extension) = with_request(request(), |cx| async move {
+        let query = leptos_axum::extract::<Query<HashMap<String, u32>>>(cx)
+            .await
+            .unwrap_err();
+        let extension =
+            leptos_axum::extract::<Extension<u8>>(cx).await.unwrap_err();
+        (query, extension)
+    })
+    .await;
+
+    match query {
+        ServerFnError::ServerError(message) => assert!(
+            message.starts_with("Failed to deserialize query string"),
+            "{message}"
+        ),
+        e => panic!("expected a server error, got {e:?}"),
+    }
+    match extension {
+        ServerFnError::ServerError(message) => assert!(
+            message.starts_with("Missing request extension"),
+            "{message}"
+        ),
+        e => panic!("expected a server error, got {e:?}"),
+    }
+}
+
+#[test]
+fn extract_needs_a_request() {
+    let runtime = create_runtime();
+    let (cx, disposer) = raw_scope_and_disposer(runtime);
+    let result =
+        futures::executor::block_on(leptos_axum::extract::<HeaderMap>(cx));
+    disposer.dispose();
+    runtime.dispose();
+
+    assert!(matches!(result, Err(ServerFnError::ServerError(_))));
+}
@@ -0,0 +1,34 @@
+use axum::{body::Body, http::Request};
+use leptos::{leptos_server::PageCache, *};
+use std::time::Duration;
+
+#[tokio::test]
+async fn cache_hits_get_a_fresh_nonce() {
+    let options = LeptosOptions::builder()
+        .output_name("app")
+        .csp_header(true)
+        .build();
+    let handler = leptos_axum::render_app_to_stream_cached(
+        options,
+        PageCache::new(Duration::from_secs(60)),
+        |cx| view! { cx, <p>"Hello"</p> },
+    );
+
+    let mut nonces = Vec::new();
+    for _ in 0..3 {
+        let res = handler(Request::get("/").body(Body::empty()).unwrap()).await;
+        let csp = res.headers()["content-security-policy"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let start = csp.find("'nonce-").unwrap() + "'nonce-".len();
+        let nonce =
+            csp[start..start + csp[start..].find('\'').unwrap()].to_string();
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains(&format!("nonce=\"{nonce}\"")), "{body}");
+        assert!(!nonces.contains(&nonce));
+        nonces.push(nonce);
+    }
+}
@@ -0,0 +1,140 @@
+use axum::{
+    body::Body,
+    http::{header, Request, Response, StatusCode},
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use futures::Future;
+use hyper::body;
+use leptos::{
+    leptos_server::{Encoding, ServerFn, SessionOptions, CSRF_HEADER},
+    *,
+};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use tower::ServiceExt;
+
+/// A server function implemented by hand, because the `#[server]` macro only registers
+/// functions in crates built with an `ssr` feature.
+#[derive(Clone, Serialize, Deserialize)]
+struct AddOne {
+    value: u32,
+}
+
+impl ServerFn for AddOne {
+    type Output = u32;
+
+    fn prefix() -> &'static str {
+        "/api"
+    }
+
+    fn url() -> &'static str {
+        "add_one"
+    }
+
+    fn encoding() -> Encoding {
+        Encoding::Url
+    }
+
+    fn call_fn(
+        self,
+        _cx: Scope,
+    ) -> Pin<Box<dyn Future<Output = Result<u32, ServerFnError>>>> {
+        Box::pin(async move { Ok(self.value + 1) })
+    }
+}
+
+fn app() -> Router {
+    _ = AddOne::register();
+    let options = LeptosOptions::builder().output_name("app").build();
+    let sessions = SessionOptions::new(b"a secret key of at least 32 bytes!")
+        .secure(false);
+    Router::new()
+        .route("/api/*fn_name", post(leptos_axum::handle_server_fns))
+        .route(
+            "/",
+            get(leptos_axum::render_app_to_stream(options, |cx| {
+                view! { cx, <p>"Hello"</p> }
+            })),
+        )
+        .layer(middleware::from_fn_with_state(
+            sessions,
+            leptos_axum::handle_sessions,
+        ))
+}
+
+async fn text(res: Response<axum::body::BoxBody>) -> String {
+    let body = body::to_bytes(res.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+/// Loads the page, and returns the session cookie and the CSRF token it was given.
+async fn visit() -> (String, String) {
+    let res = app()
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let cookie = res.headers()[header::SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .split(';')
+        .next()
+        .unwrap()
+        .to_string();
+    let html = text(res).await;
+    let token = html
+        .split(r#"<meta name="csrf-token" content=""#)
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap()
+        .to_string();
+    (cookie, token)
+}
+
+async fn add_one(
+    cookie: &str,
+    token: Option<&str>,
+    body: String,
+) -> Response<axum::body::BoxBody> {
+    let mut req = Request::post("/api/add_one")
+        .header(header::COOKIE, cookie)
+        .header(header::ACCEPT, "application/json")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+    if let Some(token) = token {
+        req = req.header(CSRF_HEADER, token);
+    }
+    app()
+        .oneshot(req.body(Body::from(body)).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn server_fns_need_the_csrf_token() {
+    let (cookie, _) = visit().await;
+
+    let missing = add_one(&cookie, None, "value=1".into()).await;
+    assert_eq!(missing.status(), StatusCode::FORBIDDEN);
+
+    let wrong = add_one(&cookie, Some("wrong"), "value=1".into()).await;
+    assert_eq!(wrong.status(), StatusCode::FORBIDDEN);
+
+    let (other_cookie, other_token) = visit().await;
+    assert_ne!(other_cookie, cookie);
+    let other = add_one(&cookie, Some(&other_token), "value=1".into()).await;
+    assert_eq!(other.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn server_fns_accept_the_csrf_token() {
+    let (cookie, token) = visit().await;
+
+    let header = add_one(&cookie, Some(&token), "value=1".into()).await;
+    assert_eq!(header.status(), StatusCode::OK);
+    assert_eq!(text(header).await, "2");
+
+    let field = add_one(&cookie, None, format!("value=2&_csrf={token}")).await;
+    assert_eq!(field.status(), StatusCode::OK);
+    assert_eq!(text(field).await, "3");
+}
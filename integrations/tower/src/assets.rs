@@ -0,0 +1,70 @@
+use futures::Future;
+use http::{header, HeaderValue, Request, Response};
+use leptos::leptos_config::AssetManifest;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Wraps a service that serves the static files in the `site_root`, like `tower_http`’s
+/// `ServeDir`, and sets the `Cache-Control` header of every file it serves from the
+/// [AssetManifest], so that fingerprinted files are cached forever and anything else is
+/// revalidated. A `Cache-Control` header set by the inner service is left alone.
+///
+/// ```ignore
+/// let manifest = options
+///     .load_asset_manifest()?
+///     .expect("an asset manifest is configured");
+/// let app = Router::new()
+///     .leptos_routes(options.clone(), routes, |cx| view! { cx, <App/> })
+///     .fallback_service(AssetCacheControl::new(
+///         ServeDir::new(&options.site_root),
+///         manifest,
+///     ));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AssetCacheControl<S> {
+    inner: S,
+    manifest: AssetManifest,
+}
+
+impl<S> AssetCacheControl<S> {
+    /// Sets the `Cache-Control` header of the files served by `inner` from `manifest`.
+    pub fn new(inner: S, manifest: AssetManifest) -> Self {
+        Self { inner, manifest }
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for AssetCacheControl<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let cache_control = self.manifest.cache_control(req.uri().path());
+        let res = self.inner.call(req);
+        Box::pin(async move {
+            let mut res = res.await?;
+            if res.status().is_success() {
+                res.headers_mut()
+                    .entry(header::CACHE_CONTROL)
+                    .or_insert(HeaderValue::from_static(cache_control));
+            }
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,146 @@
+use futures::{Stream, StreamExt};
+use http::HeaderValue;
+use hyper::body::Bytes;
+use leptos::LeptosOptions;
+use std::io::{self, Write};
+
+/// A content coding the integrations can compress the HTML of a page with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// `br`
+    Brotli,
+    /// `gzip`
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// Picks the encoding to use for a response from the request’s `Accept-Encoding` header,
+    /// or returns `None` if the browser accepts neither brotli nor gzip.
+    ///
+    /// The coding with the highest quality wins, and brotli wins a tie. A coding that isn’t
+    /// listed gets the quality of `*`, if there is one, and a quality of `0` means the
+    /// coding must not be used.
+    pub fn negotiate(accept_encoding: Option<&str>) -> Option<Self> {
+        let (mut brotli, mut gzip, mut any) = (None, None, None);
+        for coding in accept_encoding.into_iter().flat_map(|h| h.split(',')) {
+            let mut params = coding.split(';').map(str::trim);
+            let name = params.next().unwrap_or_default();
+            let quality = params
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok());
+            let Some(quality) = quality else { continue };
+            match name.to_ascii_lowercase().as_str() {
+                "br" => brotli = Some(quality),
+                "gzip" => gzip = Some(quality),
+                "*" => any = Some(quality),
+                _ => {}
+            }
+        }
+        let brotli = brotli.or(any).unwrap_or(0.0);
+        let gzip = gzip.or(any).unwrap_or(0.0);
+        if brotli > 0.0 && brotli >= gzip {
+            Some(Self::Brotli)
+        } else if gzip > 0.0 {
+            Some(Self::Gzip)
+        } else {
+            None
+        }
+    }
+
+    /// The encoding to compress a rendered page with, if [LeptosOptions::compression] is
+    /// set and the browser accepts one. (See [negotiate](ContentEncoding::negotiate).)
+    pub fn for_options(
+        options: &LeptosOptions,
+        accept_encoding: Option<&HeaderValue>,
+    ) -> Option<Self> {
+        options
+            .compression
+            .then(|| {
+                Self::negotiate(
+                    accept_encoding.and_then(|value| value.to_str().ok()),
+                )
+            })
+            .flatten()
+    }
+
+    /// The value of the `Content-Encoding` header for this encoding.
+    pub fn header_value(&self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gzip",
+        })
+    }
+}
+
+/// Compresses a stream of HTML, one chunk at a time.
+///
+/// The compressor is flushed after each chunk, so that the browser receives everything
+/// that has been rendered as soon as it is ready, like the fragments that replace a
+/// `<Suspense/>` fallback once its resources resolve, at some cost to how small the
+/// response gets.
+pub fn compress_stream(
+    encoding: ContentEncoding,
+    stream: impl Stream<Item = Bytes> + 'static,
+) -> impl Stream<Item = io::Result<Bytes>> + 'static {
+    let encoder = ChunkEncoder::new(encoding);
+    futures::stream::unfold(
+        (Box::pin(stream), Some(encoder)),
+        |(mut stream, encoder)| async move {
+            let mut encoder = encoder?;
+            match stream.next().await {
+                Some(chunk) => {
+                    let compressed = encoder.encode(&chunk);
+                    Some((compressed, (stream, Some(encoder))))
+                }
+                None => Some((encoder.finish(), (stream, None))),
+            }
+        },
+    )
+}
+
+enum ChunkEncoder {
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+    Gzip(flate2::write::GzEncoder<Vec<u8>>),
+}
+
+impl ChunkEncoder {
+    fn new(encoding: ContentEncoding) -> Self {
+        match encoding {
+            // a low quality keeps the time taken to compress each chunk down, which
+            // matters more while streaming than the last few bytes saved
+            ContentEncoding::Brotli => Self::Brotli(Box::new(
+                brotli::CompressorWriter::new(Vec::new(), 4096, 4, 22),
+            )),
+            ContentEncoding::Gzip => Self::Gzip(flate2::write::GzEncoder::new(
+                Vec::new(),
+                flate2::Compression::default(),
+            )),
+        }
+    }
+
+    /// Compresses a chunk, and returns everything compressed so far that hasn't been sent.
+    fn encode(&mut self, chunk: &[u8]) -> io::Result<Bytes> {
+        let output = match self {
+            Self::Brotli(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            Self::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+        };
+        Ok(Bytes::from(std::mem::take(output)))
+    }
+
+    /// Ends the compressed stream, and returns the rest of it.
+    fn finish(self) -> io::Result<Bytes> {
+        let output = match self {
+            Self::Brotli(encoder) => encoder.into_inner(),
+            Self::Gzip(encoder) => encoder.finish()?,
+        };
+        Ok(Bytes::from(output))
+    }
+}
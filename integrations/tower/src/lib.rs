@@ -0,0 +1,1228 @@
+#![forbid(unsafe_code)]
+
+//! Provides [tower](https://docs.rs/tower) services that render Leptos apps and run server
+//! functions, using the request and response types of the [http] crate, so that they can
+//! be used with [hyper] directly or with any server built on tower, such as warp.
+//!
+//! The Axum integration is built on these services, and the Actix integration shares the
+//! way they run server functions and stream pages, turning the [http] responses they build
+//! into its own. For function-as-a-service platforms, where
+//! there is no long-running server, [ServerlessHandler] turns each request into a complete
+//! response.
+//!
+//! ```
+//! use hyper::{
+//!     service::{make_service_fn, service_fn},
+//!     Body, Method, Request, Server,
+//! };
+//! use leptos::*;
+//! use leptos_tower::{RenderService, ServerFnService};
+//! use std::convert::Infallible;
+//! use tower_service::Service;
+//!
+//! # if false { // don't actually try to run a server in a doctest...
+//! #[tokio::main]
+//! async fn main() {
+//!     let options = get_configuration(None).await.unwrap().leptos_options;
+//!     let addr = options.site_addr;
+//!     let render =
+//!         RenderService::new(options, |cx| view! { cx, <p>"Hello"</p> });
+//!     let server_fns = ServerFnService::new("/api");
+//!
+//!     let make_service = make_service_fn(move |_| {
+//!         let render = render.clone();
+//!         let server_fns = server_fns.clone();
+//!         async move {
+//!             Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+//!                 if req.method() == Method::POST
+//!                     && req.uri().path().starts_with("/api/")
+//!                 {
+//!                     server_fns.clone().call(req)
+//!                 } else {
+//!                     render.clone().call(req)
+//!                 }
+//!             }))
+//!         }
+//!     });
+//!     Server::bind(&addr).serve(make_service).await.unwrap();
+//! }
+//! # }
+//! ```
+
+use futures::{future::Either, Future, SinkExt, Stream, StreamExt};
+use http::{
+    header::{self, HeaderName, HeaderValue},
+    method::Method,
+    uri::Uri,
+    version::Version,
+    HeaderMap, Request, Response, StatusCode,
+};
+use hyper::body::{self, Body, Bytes};
+use leptos::{
+    leptos_config::{AssetManifest, HtmlShell, ShellParts},
+    leptos_server::{
+        check_server_fn_urls, server_fn_by_path, Payload, Session, CSRF_HEADER,
+    },
+    ssr::{use_nonce, Nonce, SsrMode},
+    *,
+};
+use leptos_meta::{provide_meta_context, MetaContext};
+use leptos_router::*;
+use parking_lot::RwLock;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    convert::Infallible,
+    io,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::task::spawn_blocking;
+use tower_service::Service;
+use tracing::Instrument;
+
+mod assets;
+mod compression;
+mod serverless;
+pub use assets::*;
+pub use compression::*;
+pub use serverless::*;
+
+/// A struct to hold the parts of the incoming Request. Since `http::Request` isn't cloneable, we're forced
+/// to construct this for Leptos to use
+#[derive(Debug, Clone)]
+pub struct RequestParts {
+    pub version: Version,
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap<HeaderValue>,
+    pub body: Bytes,
+}
+/// This struct lets you define headers and override the status of the Response from an Element or a Server Function
+/// Typically contained inside of a ResponseOptions. Setting this is useful for cookies and custom responses.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseParts {
+    pub status: Option<StatusCode>,
+    pub headers: HeaderMap,
+}
+
+impl ResponseParts {
+    /// Insert a header, overwriting any previous value with the same key
+    pub fn insert_header(&mut self, key: HeaderName, value: HeaderValue) {
+        self.headers.insert(key, value);
+    }
+    /// Append a header, leaving any header with the same key intact
+    pub fn append_header(&mut self, key: HeaderName, value: HeaderValue) {
+        self.headers.append(key, value);
+    }
+}
+
+/// Adding this Struct to your Scope inside of a Server Fn or Element will allow you to override details of the Response
+/// like status and add Headers/Cookies. Because Elements and Server Fns are lower in the tree than the Response generation
+/// code, it needs to be wrapped in an `Arc<RwLock<>>` so that it can be surfaced.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseOptions(pub Arc<RwLock<ResponseParts>>);
+
+impl ResponseOptions {
+    /// A less boilerplatey way to overwrite the contents of `ResponseOptions` with a new `ResponseParts`
+    pub fn overwrite(&self, parts: ResponseParts) {
+        let mut writable = self.0.write();
+        *writable = parts
+    }
+    /// Set the status of the returned Response
+    pub fn set_status(&self, status: StatusCode) {
+        let mut writeable = self.0.write();
+        let res_parts = &mut *writeable;
+        res_parts.status = Some(status);
+    }
+    /// Insert a header, overwriting any previous value with the same key
+    pub fn insert_header(&self, key: HeaderName, value: HeaderValue) {
+        let mut writeable = self.0.write();
+        let res_parts = &mut *writeable;
+        res_parts.headers.insert(key, value);
+    }
+    /// Append a header, leaving any header with the same key intact
+    pub fn append_header(&self, key: HeaderName, value: HeaderValue) {
+        let mut writeable = self.0.write();
+        let res_parts = &mut *writeable;
+        res_parts.headers.append(key, value);
+    }
+}
+
+/// Provides an easy way to redirect the user from within a server function. Mimicing the Remix `redirect()`,
+/// it sets a StatusCode of 302 and a LOCATION header with the provided value.
+/// If looking to redirect from the client, `leptos_router::use_navigate()` should be used instead
+pub fn redirect(cx: leptos::Scope, path: &str) {
+    if let Some(response_options) = use_context::<ResponseOptions>(cx) {
+        response_options.set_status(StatusCode::FOUND);
+        response_options.insert_header(
+            header::LOCATION,
+            header::HeaderValue::from_str(path)
+                .expect("Failed to create HeaderValue"),
+        );
+    }
+}
+
+/// Sets the [StatusCode] of the response from a [Route](leptos_router::Route)'s `status`
+/// or [set_server_status](leptos_router::set_server_status). Codes that aren't valid are ignored.
+///
+/// The integrations provide this with [provide_server_status] when they render a page.
+pub fn set_status(cx: leptos::Scope, status: u16) {
+    if let (Some(response_options), Ok(status)) = (
+        use_context::<ResponseOptions>(cx),
+        StatusCode::from_u16(status),
+    ) {
+        response_options.set_status(status);
+    }
+}
+
+/// Decomposes an HTTP request into its parts, allowing you to read its headers
+/// and other data without consuming the body.
+pub async fn generate_request_parts(req: Request<Body>) -> RequestParts {
+    let (parts, body) = req.into_parts();
+    let body = body::to_bytes(body).await.unwrap_or_default();
+    request_parts(&parts, body)
+}
+
+/// The original parts of the request, including its extensions, which integrations can run
+/// their extractors against.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct ExtractorParts(pub Arc<tokio::sync::Mutex<http::request::Parts>>);
+
+/// Like [generate_request_parts], but also keeps the original parts of the request, and
+/// fails if the body can’t be read rather than leaving it empty.
+#[doc(hidden)]
+pub async fn generate_extractor_parts(
+    req: Request<Body>,
+) -> Result<(RequestParts, ExtractorParts), hyper::Error> {
+    let (parts, body) = req.into_parts();
+    let body = body::to_bytes(body).await?;
+    Ok((
+        request_parts(&parts, body),
+        ExtractorParts(Arc::new(tokio::sync::Mutex::new(parts))),
+    ))
+}
+
+fn request_parts(parts: &http::request::Parts, body: Bytes) -> RequestParts {
+    RequestParts {
+        method: parts.method.clone(),
+        uri: parts.uri.clone(),
+        headers: parts.headers.clone(),
+        version: parts.version,
+        body,
+    }
+}
+
+/// Runs the server function registered at `fn_name`, which is the path of the request
+/// without the server function’s prefix, with the arguments in the body of the request.
+///
+/// The response is encoded the way the request asked for in its `Accept` header. If it
+/// didn’t ask for a format the server function can respond with, as when a `<form>` is
+/// submitted, the response redirects back to the `Referer` instead.
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [RequestParts]
+/// - [ResponseOptions]
+///
+/// It also provides the [Session], if the request has one in its extensions, and responds
+/// with `403 Forbidden` if the request doesn’t carry the session’s CSRF token.
+///
+/// The server function runs in a `server_fn` span inside the `request` span, and the time
+/// it took is reported in a `Server-Timing` header (see [server_timing]).
+pub async fn handle_server_fn(
+    fn_name: &str,
+    req: Request<Body>,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+) -> Response<Body> {
+    let fn_name = fn_name.strip_prefix('/').unwrap_or(fn_name).to_string();
+    let session = req.extensions().get::<Session>().cloned();
+    let span = request_span(req.method(), req.uri().path());
+
+    let (tx, rx) = futures::channel::oneshot::channel();
+    spawn_blocking(move || {
+        let _span = span.enter();
+        tokio::runtime::Runtime::new()
+            .expect("couldn't spawn runtime")
+            .block_on(async move {
+                let res =
+                    run_server_fn(&fn_name, req, session, additional_context)
+                        .await;
+                _ = tx.send(res);
+            })
+    });
+
+    rx.await.unwrap()
+}
+
+async fn run_server_fn(
+    fn_name: &str,
+    req: Request<Body>,
+    session: Option<Session>,
+    additional_context: impl Fn(leptos::Scope),
+) -> Response<Body> {
+    let (req_parts, extractor_parts) = match generate_extractor_parts(req).await
+    {
+        Ok(parts) => parts,
+        Err(e) => {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::from(format!(
+                    "Could not read the body of the request: {e}"
+                )))
+                .expect("could not build Response")
+        }
+    };
+    let headers = req_parts.headers.clone();
+    let body = req_parts.body.clone();
+    dispatch_server_fn(fn_name, &headers, &body, session, move |cx| {
+        additional_context(cx);
+        // Add this so we can get details about the Request
+        provide_context(cx, req_parts);
+        // Add this so that extractors can run against the Request
+        provide_context(cx, extractor_parts);
+    })
+    .await
+    .map(Body::from)
+}
+
+/// Runs the server function registered at `fn_name` with the arguments in `body`, and
+/// builds its response. This is the part of [handle_server_fn] that doesn’t depend on the
+/// server, which the integrations share: `provide_request` provides the server’s own
+/// request types, and any additional context, to the server function’s [Scope].
+#[doc(hidden)]
+pub async fn dispatch_server_fn(
+    fn_name: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    session: Option<Session>,
+    provide_request: impl FnOnce(leptos::Scope),
+) -> Response<Bytes> {
+    let server_fn = match server_fn_by_path(fn_name) {
+        Some(server_fn) => server_fn,
+        None => {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Bytes::from(format!(
+                    "Could not find a server function at the route {fn_name}. \
+                     \n\nIt's likely that the server function is defined in a \
+                     crate that wasn't built with the `ssr` feature, or that \
+                     this route doesn't match its prefix."
+                )))
+                .expect("could not build Response")
+        }
+    };
+    let runtime = create_runtime();
+    let (cx, disposer) = raw_scope_and_disposer(runtime);
+
+    provide_request(cx);
+    // Add this so that we can set headers and status of the response
+    let res_options = ResponseOptions::default();
+    provide_context(cx, res_options.clone());
+
+    if let Some(session) = session {
+        let csrf_token = headers
+            .get(CSRF_HEADER)
+            .and_then(|value| value.to_str().ok());
+        if !session.verify_csrf_request(csrf_token, body) {
+            disposer.dispose();
+            runtime.dispose();
+            return Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(Bytes::from("missing or invalid CSRF token"))
+                .expect("could not build Response");
+        }
+        provide_context(cx, session);
+    }
+
+    let started = Instant::now();
+    let result = server_fn(cx, body)
+        .instrument(tracing::info_span!("server_fn", name = %fn_name))
+        .await;
+    let timing = server_timing([("server-fn", fn_name, started.elapsed())]);
+
+    // clean up the scope, which we only needed to run the server fn
+    disposer.dispose();
+    runtime.dispose();
+
+    let serialized = match result {
+        Ok(serialized) => serialized,
+        Err(e) => {
+            return Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Bytes::from(e.to_string()))
+                .expect("could not build Response")
+        }
+    };
+
+    let mut res = Response::builder();
+
+    // Add headers from ResponseParts if they exist. These should be added as long
+    // as the server function returns an OK response
+    let (status, mut res_headers) = {
+        let res_parts = res_options.0.read();
+        (res_parts.status, res_parts.headers.clone())
+    };
+    if let Some(header_ref) = res.headers_mut() {
+        header_ref.extend(res_headers.drain());
+        header_ref.append(SERVER_TIMING, timing);
+    };
+
+    // if this is Accept: application/json then send a serialized JSON response
+    let accept_header = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    if accept_header == Some("application/json")
+        || accept_header == Some("application/x-www-form-urlencoded")
+        || accept_header == Some("application/cbor")
+    {
+        res = res.status(StatusCode::OK);
+    }
+    // otherwise, it's probably a <form> submit or something: redirect back to the referrer
+    else {
+        let referer = headers
+            .get(header::REFERER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("/");
+        res = res
+            .status(StatusCode::SEE_OTHER)
+            .header(header::LOCATION, referer);
+    }
+    // Override StatusCode if it was set in a Resource or Element
+    if let Some(status) = status {
+        res = res.status(status);
+    }
+
+    let (content_type, body) = match serialized {
+        Payload::Binary(data) => ("application/cbor", Bytes::from(data)),
+        Payload::Url(data) => {
+            ("application/x-www-form-urlencoded", Bytes::from(data))
+        }
+        Payload::Json(data) => ("application/json", Bytes::from(data)),
+    };
+    res.header(header::CONTENT_TYPE, content_type)
+        .body(body)
+        .expect("could not build Response")
+}
+
+/// Whether `authorize` lets a request subscribe to the [Channel](leptos::Channel) served at
+/// `url`. Like a server function, it runs in a new [Scope] that `provide_request` provides
+/// the server’s own request types to, along with the [Session], if there is one.
+#[doc(hidden)]
+pub fn authorize_channel(
+    url: &str,
+    session: Option<Session>,
+    provide_request: impl FnOnce(leptos::Scope),
+    authorize: impl FnOnce(leptos::Scope, &str) -> bool,
+) -> bool {
+    let runtime = create_runtime();
+    let (cx, disposer) = raw_scope_and_disposer(runtime);
+    provide_request(cx);
+    if let Some(session) = session {
+        provide_context(cx, session);
+    }
+    let authorized = authorize(cx, url);
+    disposer.dispose();
+    runtime.dispose();
+    authorized
+}
+
+/// A boxed future that resolves to the response for a rendered page.
+pub type RenderFuture =
+    Pin<Box<dyn Future<Output = Response<Body>> + Send + 'static>>;
+
+/// Returns a function that renders your app for a request, streaming the HTML of the page
+/// in the given [SsrMode].
+///
+/// The response is returned once the app shell has rendered, so that any status or
+/// headers set while rendering it are included, and the rest of the page streams in as
+/// its resources resolve.
+///
+/// The page is built from the [HtmlShell] provided as context, if there is one, or else
+/// the one given by [LeptosOptions::html_shell].
+///
+/// If [LeptosOptions::compression] is set, the page is compressed with the best [ContentEncoding]
+/// the browser accepts, flushing each chunk as it is rendered. A `Link` header preloads the
+/// JS and WASM bundle (see [bundle_preload_header]) and the page's stylesheets. It is sent
+/// with the response, rather than in a `103 Early Hints` response ahead of it, which hyper
+/// can’t send.
+///
+/// The page is rendered in a `request` span, and the time taken to render the shell is
+/// reported in a `Server-Timing` header (see [server_timing]).
+///
+/// ## Provided Context Types
+/// This function always provides context values including the following types:
+/// - [RequestParts]
+/// - [ResponseOptions]
+/// - [MetaContext](leptos_meta::MetaContext)
+/// - [RouterIntegrationContext](leptos_router::RouterIntegrationContext)
+/// - [AssetManifest], if [LeptosOptions::asset_manifest] is set
+/// - [Session], if the request has one in its extensions
+pub fn render_app_to_stream_with_mode<IV>(
+    options: LeptosOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + 'static,
+    mode: SsrMode,
+) -> impl Fn(Request<Body>) -> RenderFuture + Clone + Send + 'static
+where
+    IV: IntoView,
+{
+    // `get_configuration` has already reported a manifest that can't be loaded
+    let manifest = options.load_asset_manifest().unwrap_or_else(|e| {
+        leptos::error!("{e}");
+        None
+    });
+    let shell = options
+        .load_html_shell()
+        .expect("couldn't load the HTML shell");
+
+    move |req: Request<Body>| {
+        let options = options.clone();
+        let app_fn = app_fn.clone();
+        let additional_context = additional_context.clone();
+        let manifest = manifest.clone();
+        let shell = shell.clone();
+        let res_options = ResponseOptions::default();
+        let span = request_span(req.method(), req.uri().path());
+        let render_span = span.clone();
+
+        let render = async move {
+            let started = Instant::now();
+            // Need to get the path and query string of the Request
+            // For reasons that escape me, if the incoming URI protocol is https, it provides the absolute URI
+            // if http, it returns a relative path. Adding .path() seems to make it explicitly return the relative uri
+            let path = req.uri().path_and_query().unwrap().as_str();
+            let full_path = format!("http://leptos.dev{path}");
+            let compression = options.compression;
+            let encoding = ContentEncoding::for_options(
+                &options,
+                req.headers().get(header::ACCEPT_ENCODING),
+            );
+
+            let (tx, rx) = futures::channel::mpsc::channel(8);
+
+            spawn_blocking({
+                let res_options = res_options.clone();
+                move || {
+                    // this thread only renders this page, so the whole render is in its span
+                    let _span = render_span.enter();
+                    tokio::runtime::Runtime::new()
+                        .expect("couldn't spawn runtime")
+                        .block_on(async move {
+                            tokio::task::LocalSet::new()
+                                .run_until(stream_app(
+                                    options,
+                                    req,
+                                    full_path,
+                                    manifest,
+                                    shell,
+                                    res_options,
+                                    additional_context,
+                                    app_fn,
+                                    mode,
+                                    tx,
+                                ))
+                                .await;
+                        })
+                }
+            });
+
+            page_response(rx, &res_options, compression, encoding, started)
+                .await
+                .map(Body::wrap_stream)
+        };
+
+        Box::pin(render.instrument(span)) as RenderFuture
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn stream_app<IV>(
+    options: LeptosOptions,
+    req: Request<Body>,
+    full_path: String,
+    manifest: Option<AssetManifest>,
+    shell: HtmlShell,
+    res_options: ResponseOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    app_fn: impl Fn(leptos::Scope) -> IV + 'static,
+    mode: SsrMode,
+    mut tx: futures::channel::mpsc::Sender<String>,
+) where
+    IV: IntoView,
+{
+    let session = req.extensions().get::<Session>().cloned();
+    let (req_parts, extractor_parts) = match generate_extractor_parts(req).await
+    {
+        Ok(parts) => parts,
+        // with nothing sent, the response is empty
+        Err(_) => {
+            res_options.set_status(StatusCode::BAD_REQUEST);
+            return;
+        }
+    };
+    let bundle_urls = bundle_paths(&options, manifest.as_ref());
+    let app = {
+        let res_options = res_options.clone();
+        move |cx| {
+            let integration = ServerIntegration { path: full_path };
+            provide_context(cx, RouterIntegrationContext::new(integration));
+            provide_context(cx, Nonce::new());
+            provide_meta_context(cx);
+            provide_context(cx, req_parts);
+            provide_context(cx, extractor_parts);
+            provide_context(cx, res_options);
+            if let Some(manifest) = manifest {
+                provide_context(cx, manifest);
+            }
+            if let Some(session) = session {
+                provide_context(cx, session);
+            }
+            provide_server_redirect(cx, move |path| redirect(cx, path));
+            provide_server_status(cx, move |status| set_status(cx, status));
+            app_fn(cx).into_view(cx)
+        }
+    };
+
+    let mut page = Box::pin(stream_page(
+        &options,
+        shell,
+        bundle_urls,
+        app,
+        &res_options,
+        additional_context,
+        mode,
+    ));
+    while let Some(fragment) = page.next().await {
+        _ = tx.send(fragment).await;
+    }
+    tx.close_channel();
+}
+
+/// Renders the app inside the [HtmlShell], and returns the HTML of the page as it renders.
+/// This is the part of rendering a page that doesn’t depend on the server, which the
+/// integrations share: `app` provides the server’s own request types to the app’s [Scope].
+///
+/// The `Link` header that preloads the JS and WASM bundle at `bundle_urls` (see
+/// [bundle_preload_header]) is added to the [ResponseOptions] before the app runs. Once it
+/// has rendered its shell, the `Content-Security-Policy` header, if
+/// [LeptosOptions::csp_header] is set, and the `Link` header that preloads its stylesheets
+/// (see [preload_header]) are added too. No `103 Early Hints` response is sent, because
+/// neither hyper nor Actix can send informational responses: the `Link` headers arrive with
+/// the rest of the response’s head. The stream has to be polled inside a
+/// [LocalSet](tokio::task::LocalSet).
+#[doc(hidden)]
+pub fn stream_page(
+    options: &LeptosOptions,
+    shell: HtmlShell,
+    bundle_urls: (String, String),
+    app: impl FnOnce(leptos::Scope) -> View + 'static,
+    res_options: &ResponseOptions,
+    additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send,
+    mode: SsrMode,
+) -> impl Stream<Item = String> {
+    // the bundle doesn't depend on the app, so the browser can be told about it before
+    // the app has run
+    res_options
+        .append_header(header::LINK, bundle_preload_header(&bundle_urls));
+
+    // the shell is filled in once the app has rendered, because the parts that go into it,
+    // like the <head> tags from leptos_meta, are only known then
+    let tail = Rc::new(RefCell::new(String::new()));
+    let prefix = {
+        let options = options.clone();
+        let tail = Rc::clone(&tail);
+        move |cx| {
+            let shell = use_context::<HtmlShell>(cx).unwrap_or(shell);
+            let (head, rest) = html_parts(
+                &options,
+                &shell,
+                Some(&bundle_urls),
+                use_context::<MetaContext>(cx).as_ref(),
+                use_nonce(cx).as_ref(),
+                use_context::<Session>(cx).as_ref(),
+            );
+            *tail.borrow_mut() = rest;
+            head.into()
+        }
+    };
+    let (page, runtime, scope) = match mode {
+        SsrMode::OutOfOrder => {
+            let (page, runtime, scope) =
+                ssr::render_to_stream_with_prefix_undisposed_with_context(
+                    app,
+                    prefix,
+                    additional_context,
+                );
+            (
+                Box::pin(page) as Pin<Box<dyn Stream<Item = String>>>,
+                runtime,
+                scope,
+            )
+        }
+        SsrMode::InOrder => {
+            let (page, runtime, scope) =
+                ssr::render_to_stream_in_order_with_prefix_undisposed_with_context(
+                    app,
+                    prefix,
+                    additional_context,
+                );
+            (
+                Box::pin(page) as Pin<Box<dyn Stream<Item = String>>>,
+                runtime,
+                scope,
+            )
+        }
+    };
+
+    let cx = Scope { runtime, id: scope };
+    let meta = use_context::<MetaContext>(cx);
+    let nonce = use_nonce(cx);
+    if let (true, Some(nonce)) = (options.csp_header, &nonce) {
+        if !res_options
+            .0
+            .read()
+            .headers
+            .contains_key(header::CONTENT_SECURITY_POLICY)
+        {
+            res_options.insert_header(
+                header::CONTENT_SECURITY_POLICY,
+                csp_header(nonce),
+            );
+        }
+    }
+    if let Some(links) = preload_header(meta.as_ref()) {
+        res_options.append_header(header::LINK, links);
+    }
+
+    page.chain(futures::stream::once(async move {
+        runtime.dispose();
+        tail.take()
+    }))
+}
+
+/// Waits for the first chunk of the `page`, which renders the app shell, and builds the
+/// response that streams the rest of it, with the status and headers set while the shell
+/// rendered. If there is an `encoding`, the page is compressed with it. (See
+/// [compress_stream].)
+///
+/// The response is built from [http] types, so that every integration can turn it into its
+/// own response type.
+#[doc(hidden)]
+pub async fn page_response<S>(
+    page: S,
+    res_options: &ResponseOptions,
+    compression: bool,
+    encoding: Option<ContentEncoding>,
+    started: Instant,
+) -> Response<impl Stream<Item = io::Result<Bytes>>>
+where
+    S: Stream<Item = String> + 'static,
+{
+    let mut page = Box::pin(page);
+    // Get the first chunk in the stream, which renders the app shell, and thus allows Resources to run
+    let first_chunk = page.next().await;
+    let timing = server_timing([("shell", "Render shell", started.elapsed())]);
+
+    let chunks = futures::stream::iter(first_chunk)
+        .chain(page)
+        .map(Bytes::from);
+    let body = match encoding {
+        Some(encoding) => Either::Left(compress_stream(encoding, chunks)),
+        None => Either::Right(chunks.map(Ok)),
+    };
+
+    let mut res = Response::new(body);
+    if let Some(encoding) = encoding {
+        res.headers_mut()
+            .insert(header::CONTENT_ENCODING, encoding.header_value());
+    }
+    if compression {
+        res.headers_mut()
+            .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+    }
+
+    // Extract the status and headers now that the shell has rendered
+    let res_parts = res_options.0.read();
+    if let Some(status) = res_parts.status {
+        *res.status_mut() = status
+    }
+    let mut res_headers = res_parts.headers.clone();
+    res.headers_mut().extend(res_headers.drain());
+    res.headers_mut()
+        .entry(header::CONTENT_TYPE)
+        .or_insert(HeaderValue::from_static("text/html; charset=utf-8"));
+    res.headers_mut().append(SERVER_TIMING, timing);
+
+    res
+}
+
+/// A [Service] that runs server functions, for requests whose path starts with the prefix
+/// the server functions were declared with. (See [handle_server_fn].)
+///
+/// Server functions are registered automatically, and a warning is logged when the service
+/// is created if two of them share a URL. (See [check_server_fn_urls].)
+#[derive(Clone)]
+pub struct ServerFnService<C = fn(leptos::Scope)> {
+    prefix: String,
+    additional_context: C,
+}
+
+impl<C> std::fmt::Debug for ServerFnService<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerFnService")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerFnService {
+    /// Creates a service for the server functions declared with the given prefix, like `/api`.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        check_server_fn_urls();
+        Self {
+            prefix: prefix.into(),
+            additional_context: |_| {},
+        }
+    }
+}
+
+impl<C> ServerFnService<C> {
+    /// Runs a closure before each server function, to provide it with additional context.
+    pub fn with_context<D>(self, additional_context: D) -> ServerFnService<D>
+    where
+        D: Fn(leptos::Scope) + 'static + Clone + Send,
+    {
+        ServerFnService {
+            prefix: self.prefix,
+            additional_context,
+        }
+    }
+}
+
+impl<C> Service<Request<Body>> for ServerFnService<C>
+where
+    C: Fn(leptos::Scope) + 'static + Clone + Send,
+{
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Response<Body>, Infallible>> + Send>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let fn_name = req
+            .uri()
+            .path()
+            .strip_prefix(self.prefix.trim_end_matches('/'))
+            .unwrap_or_default()
+            .to_string();
+        let additional_context = self.additional_context.clone();
+        Box::pin(async move {
+            Ok(handle_server_fn(&fn_name, req, additional_context).await)
+        })
+    }
+}
+
+/// A [Service] that renders your app for each request. (See
+/// [render_app_to_stream_with_mode].)
+#[derive(Clone)]
+pub struct RenderService {
+    render: Arc<dyn Fn(Request<Body>) -> RenderFuture + Send + Sync>,
+}
+
+impl std::fmt::Debug for RenderService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RenderService").finish_non_exhaustive()
+    }
+}
+
+impl RenderService {
+    /// Creates a service that streams your app out of order.
+    pub fn new<IV>(
+        options: LeptosOptions,
+        app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + Sync + 'static,
+    ) -> Self
+    where
+        IV: IntoView,
+    {
+        Self::with_context(options, |_| {}, app_fn, SsrMode::OutOfOrder)
+    }
+
+    /// Creates a service that streams your app in the given [SsrMode], running a closure to
+    /// provide additional context before rendering it.
+    pub fn with_context<IV>(
+        options: LeptosOptions,
+        additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send + Sync,
+        app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + Sync + 'static,
+        mode: SsrMode,
+    ) -> Self
+    where
+        IV: IntoView,
+    {
+        let render = render_app_to_stream_with_mode(
+            options,
+            additional_context,
+            app_fn,
+            mode,
+        );
+        Self {
+            render: Arc::new(render),
+        }
+    }
+}
+
+impl Service<Request<Body>> for RenderService {
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Response<Body>, Infallible>> + Send>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let res = (self.render)(req);
+        Box::pin(async move { Ok(res.await) })
+    }
+}
+
+/// The name of the `Server-Timing` header.
+pub const SERVER_TIMING: HeaderName = HeaderName::from_static("server-timing");
+
+/// A `Server-Timing` header that shows how long each phase of handling a request took in
+/// the browser’s developer tools, from the name, description and duration of each phase.
+///
+/// Only phases that have ended by the time the headers are sent can be reported, so for a
+/// streamed page this covers rendering the shell, but not the resources and `<Suspense/>`
+/// fragments that resolve afterwards: those are in the `resource` and `fragment` spans of
+/// the request’s trace instead. Pages from a [ServerlessHandler], which are buffered before
+/// they are sent, also report the time taken to render the whole page.
+pub fn server_timing<'a>(
+    phases: impl IntoIterator<Item = (&'a str, &'a str, Duration)>,
+) -> HeaderValue {
+    let metrics = phases
+        .into_iter()
+        .map(|(name, description, duration)| {
+            let description = description
+                .chars()
+                .filter(|c| c.is_ascii_graphic() || *c == ' ')
+                .collect::<String>()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            format!(
+                r#"{name};desc="{description}";dur={:.1}"#,
+                duration.as_secs_f64() * 1000.0
+            )
+        })
+        .collect::<Vec<_>>();
+    HeaderValue::from_str(&metrics.join(", "))
+        .expect("the metrics only contain visible ASCII characters")
+}
+
+/// The span in which a request is handled, with child spans for rendering the shell, each
+/// resource, each `<Suspense/>` fragment and each server function. It only needs the
+/// request’s method and path, so that every integration can use it with its own request type.
+#[doc(hidden)]
+pub fn request_span(method: &Method, path: &str) -> tracing::Span {
+    tracing::info_span!("request", method = %method, path = %path)
+}
+
+/// The URLs of the JS and WASM bundle, resolved to fingerprinted files if there
+/// is an [AssetManifest].
+pub fn bundle_paths(
+    options: &LeptosOptions,
+    manifest: Option<&AssetManifest>,
+) -> (String, String) {
+    let pkg_path = &options.site_pkg_dir;
+    let output_name = &options.output_name;
+
+    // Because wasm-pack adds _bg to the end of the WASM filename, and we want to mantain compatibility with it's default options
+    // we add _bg to the wasm files if cargo-leptos doesn't set the env var LEPTOS_OUTPUT_NAME
+    // Otherwise we need to add _bg because wasm_pack always does. This is not the same as options.output_name, which is set regardless
+    let mut wasm_output_name = output_name.clone();
+    if std::env::var("LEPTOS_OUTPUT_NAME").is_err() {
+        wasm_output_name.push_str("_bg");
+    }
+
+    let js_path = format!("/{pkg_path}/{output_name}.js");
+    let wasm_path = format!("/{pkg_path}/{wasm_output_name}.wasm");
+    match manifest {
+        Some(manifest) => {
+            (manifest.resolve(&js_path), manifest.resolve(&wasm_path))
+        }
+        None => (js_path, wasm_path),
+    }
+}
+
+/// The `index.html` file under the `site_root` that the statically generated page for
+/// `path` is written to. Returns an error instead if `path` would leave the `site_root`,
+/// for example through a `..` segment.
+pub fn static_page_file(site_root: &str, path: &str) -> io::Result<PathBuf> {
+    let outside = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the page {path:?} would be written outside the site root"),
+        )
+    };
+    let site_root = Path::new(site_root);
+    let mut dir = site_root.to_path_buf();
+    for component in Path::new(path.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(segment) => dir.push(segment),
+            Component::CurDir => {}
+            Component::ParentDir
+            | Component::RootDir
+            | Component::Prefix(_) => return Err(outside()),
+        }
+    }
+    if path.contains('\\') || !dir.starts_with(site_root) {
+        return Err(outside());
+    }
+    Ok(dir.join("index.html"))
+}
+
+/// Renders a page for each of the app’s routes ahead of time, and writes it to an
+/// `index.html` file in the matching directory under [LeptosOptions::site_root], so
+/// that the site can be served as static files. (See [static_page_file].)
+///
+/// Routes without dynamic segments are rendered once. Routes with dynamic segments, like
+/// `/post/:id`, are rendered once for each set of params given for them in `params`, and
+/// skipped if there are none. (See [expand_static_paths].) Each page waits for all of its
+/// resources to resolve, using [render_to_string_async](leptos::ssr::render_to_string_async).
+///
+/// If `hydrate` is `true`, the pages load the app’s JS and WASM bundle and hydrate it,
+/// using the values the resources had when the page was rendered. Otherwise, they are
+/// plain HTML.
+///
+/// Each page is rendered inside its own [LocalSet](tokio::task::LocalSet), so this can
+/// run on any Tokio runtime. Returns the paths of the files that were written.
+pub async fn generate_static_site<IV>(
+    options: &LeptosOptions,
+    app_fn: impl Fn(leptos::Scope) -> IV + Clone + 'static,
+    params: HashMap<String, Vec<ParamsMap>>,
+    hydrate: bool,
+) -> io::Result<Vec<PathBuf>>
+where
+    IV: IntoView + 'static,
+{
+    let manifest = options
+        .load_asset_manifest()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let shell = options
+        .load_html_shell()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let routes = generate_route_list_inner(app_fn.clone());
+
+    let mut files = Vec::new();
+    for path in expand_static_paths(&routes, &params) {
+        let file = static_page_file(&options.site_root, &path)?;
+        let html = tokio::task::LocalSet::new()
+            .run_until(render_static_page(
+                options,
+                &path,
+                app_fn.clone(),
+                manifest.clone(),
+                &shell,
+                hydrate,
+            ))
+            .await;
+
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&file, html)?;
+        files.push(file);
+    }
+    Ok(files)
+}
+
+async fn render_static_page<IV>(
+    options: &LeptosOptions,
+    path: &str,
+    app_fn: impl Fn(leptos::Scope) -> IV + 'static,
+    manifest: Option<AssetManifest>,
+    shell: &HtmlShell,
+    hydrate: bool,
+) -> String
+where
+    IV: IntoView + 'static,
+{
+    let full_path = format!("http://leptos.dev{path}");
+    let (html, resources, runtime, scope) =
+        ssr::render_to_string_async_undisposed_with_context(
+            move |cx| app_fn(cx).into_view(cx),
+            move |cx| {
+                let integration = ServerIntegration { path: full_path };
+                provide_context(cx, RouterIntegrationContext::new(integration));
+                provide_meta_context(cx);
+                if let Some(manifest) = manifest {
+                    provide_context(cx, manifest);
+                }
+            },
+        )
+        .await;
+
+    let cx = leptos::Scope { runtime, id: scope };
+    let meta = use_context::<MetaContext>(cx);
+    let bundle_urls =
+        bundle_paths(options, use_context::<AssetManifest>(cx).as_ref());
+    let (head, tail) = html_parts(
+        options,
+        shell,
+        hydrate.then_some(&bundle_urls),
+        meta.as_ref(),
+        None,
+        None,
+    );
+    let resources = if hydrate {
+        ssr::resolved_resources_script(None, &resources)
+    } else {
+        String::new()
+    };
+    runtime.dispose();
+
+    format!("{head}{resources}{html}{tail}")
+}
+
+/// A `Link` header that tells the browser to start loading the JS and WASM bundle before
+/// it has received the `<head>`. The integrations send it with every rendered page,
+/// alongside the [preload_header] for its stylesheets.
+pub fn bundle_preload_header(
+    (js_path, wasm_path): &(String, String),
+) -> HeaderValue {
+    HeaderValue::from_str(&format!(
+        "<{js_path}>; rel=modulepreload, <{wasm_path}>; rel=preload; \
+         as=fetch; crossorigin"
+    ))
+    .expect("the bundle paths are valid header values")
+}
+
+/// A `Link` header that tells the browser to start loading the page's stylesheets before
+/// it has received the `<head>`, or `None` if the page has none.
+pub fn preload_header(meta: Option<&MetaContext>) -> Option<HeaderValue> {
+    let links = meta?
+        .stylesheets()
+        .into_iter()
+        .map(|href| format!("<{href}>; rel=preload; as=style"))
+        .collect::<Vec<_>>();
+    if links.is_empty() {
+        return None;
+    }
+    HeaderValue::from_str(&links.join(", ")).ok()
+}
+
+/// A `Content-Security-Policy` that only runs scripts carrying the response's [Nonce],
+/// and the scripts and WASM they load.
+pub fn csp_header(nonce: &Nonce) -> HeaderValue {
+    HeaderValue::from_str(&format!(
+        "script-src 'nonce-{nonce}' 'strict-dynamic' 'wasm-unsafe-eval'; \
+         object-src 'none'"
+    ))
+    .expect("a nonce is always a valid header value")
+}
+
+/// The tag from which the browser reads the session’s CSRF token.
+pub fn csrf_meta_tag(session: &Session) -> String {
+    format!(
+        r#"<meta name="csrf-token" content="{}"/>"#,
+        session.csrf_token()
+    )
+}
+
+/// The HTML that comes before and after the app: the [HtmlShell], filled in with the page’s
+/// tags from [leptos_meta], the scripts that load and hydrate the `bundle`, and the
+/// session’s CSRF token.
+pub fn html_parts(
+    options: &LeptosOptions,
+    shell: &HtmlShell,
+    bundle: Option<&(String, String)>,
+    meta: Option<&MetaContext>,
+    nonce: Option<&Nonce>,
+    session: Option<&Session>,
+) -> (String, String) {
+    let mut head = session.map(csrf_meta_tag).unwrap_or_default();
+    if let Some(meta) = meta {
+        head.push_str(&meta.dehydrate());
+    }
+    let html_attributes = meta
+        .and_then(|meta| meta.html.as_string())
+        .unwrap_or_default();
+    let body_attributes = meta
+        .and_then(|meta| meta.body.as_string())
+        .unwrap_or_default();
+    shell.render(&ShellParts {
+        html_attributes: &html_attributes,
+        head: &head,
+        hydration: &hydration_scripts(options, bundle, nonce),
+        body_attributes: &body_attributes,
+    })
+}
+
+/// The tags that load and hydrate the `bundle`, and the script that reloads the page while
+/// cargo-leptos is watching for changes.
+fn hydration_scripts(
+    options: &LeptosOptions,
+    bundle: Option<&(String, String)>,
+    nonce: Option<&Nonce>,
+) -> String {
+    let nonce = nonce.map(Nonce::attr).unwrap_or_default();
+    let hydration = bundle
+        .map(|(js_path, wasm_path)| {
+            format!(
+                r#"<link rel="modulepreload" href="{js_path}">
+                <link rel="preload" href="{wasm_path}" as="fetch" type="application/wasm" crossorigin="">
+                <script type="module"{nonce}>import init, {{ hydrate }} from '{js_path}'; init('{wasm_path}').then(hydrate);</script>"#
+            )
+        })
+        .unwrap_or_default();
+    let site_ip = &options.site_addr.ip().to_string();
+    let reload_port = options.reload_port;
+
+    let leptos_autoreload = match std::env::var("LEPTOS_WATCH").is_ok() {
+        true => format!(
+            r#"
+            <script crossorigin=""{nonce}>(function () {{
+                var ws = new WebSocket('ws://{site_ip}:{reload_port}/live_reload');
+                ws.onmessage = (ev) => {{
+                    let msg = JSON.parse(ev.data);
+                    if (msg.all) window.location.reload();
+                    if (msg.css) {{
+                        let found = false;
+                        document.querySelectorAll("link").forEach((link) => {{
+                            if (link.getAttribute('href').includes(msg.css)) {{
+                                let newHref = '/' + msg.css + '?version=' + new Date().getMilliseconds();
+                                link.setAttribute('href', newHref);
+                                found = true;
+                            }}
+                        }});
+                        if (!found) console.warn(`CSS hot-reload: Could not find a <link href=/\"${{msg.css}}\"> element`);
+                    }};
+                }};
+                ws.onclose = () => console.warn('Live-reload stopped. Manual reload necessary.');
+            }})()
+            </script>
+            "#
+        ),
+        false => "".to_string(),
+    };
+
+    hydration + &leptos_autoreload
+}
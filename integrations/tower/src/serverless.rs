@@ -0,0 +1,149 @@
+use crate::{
+    handle_server_fn, render_app_to_stream_with_mode, server_timing,
+    RenderFuture, SERVER_TIMING,
+};
+use http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use hyper::body::{self, Body, Bytes};
+use leptos::{ssr::SsrMode, IntoView, LeptosOptions};
+use std::{sync::Arc, time::Instant};
+
+/// Handles one request at a time, for function-as-a-service platforms where there is no
+/// long-running server: each [Request] is turned into a complete [Response], with the whole
+/// body buffered, either by calling a server function or by rendering the app.
+///
+/// `POST` requests under the server function prefix (`/api` unless set with
+/// [server_fn_prefix](ServerlessHandler::server_fn_prefix)) run the server function
+/// registered at the rest of the path. Everything else renders the app, the same way as
+/// [render_app_to_stream_with_mode] with [SsrMode::OutOfOrder]. If the response can’t be
+/// built, as when compressing it fails, the handler responds with `500 Internal Server Error`.
+///
+/// Since the page is only sent once it is complete, its `Server-Timing` header reports the
+/// time taken to render the whole page, including its resources and `<Suspense/>` fragments,
+/// as a `render` metric alongside the `shell` metric.
+///
+/// Because it takes and returns plain [http] types, you can also call it with requests you
+/// build yourself, to test your app without starting a server:
+/// ```
+/// use http::{Request, StatusCode};
+/// use leptos::*;
+/// use leptos_tower::ServerlessHandler;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let options = LeptosOptions::builder().output_name("my_app").build();
+/// let handler =
+///     ServerlessHandler::new(options, |cx| view! { cx, <p>"Hello"</p> });
+///
+/// let res = handler.handle(Request::get("/").body("").unwrap()).await;
+/// assert_eq!(res.status(), StatusCode::OK);
+/// assert!(String::from_utf8_lossy(res.body()).contains("Hello"));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ServerlessHandler {
+    server_fn_prefix: String,
+    additional_context: Arc<dyn Fn(leptos::Scope) + Send + Sync>,
+    render: Arc<dyn Fn(Request<Body>) -> RenderFuture + Send + Sync>,
+}
+
+impl std::fmt::Debug for ServerlessHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerlessHandler")
+            .field("server_fn_prefix", &self.server_fn_prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerlessHandler {
+    /// Creates a handler that renders your app, and runs the server functions under `/api`.
+    pub fn new<IV>(
+        options: LeptosOptions,
+        app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + Sync + 'static,
+    ) -> Self
+    where
+        IV: IntoView,
+    {
+        Self::with_context(options, |_| {}, app_fn)
+    }
+
+    /// Creates a handler that runs a closure to provide additional context before rendering
+    /// your app or running a server function.
+    pub fn with_context<IV>(
+        options: LeptosOptions,
+        additional_context: impl Fn(leptos::Scope) + 'static + Clone + Send + Sync,
+        app_fn: impl Fn(leptos::Scope) -> IV + Clone + Send + Sync + 'static,
+    ) -> Self
+    where
+        IV: IntoView,
+    {
+        let render = render_app_to_stream_with_mode(
+            options,
+            additional_context.clone(),
+            app_fn,
+            SsrMode::OutOfOrder,
+        );
+        Self {
+            server_fn_prefix: "/api".to_string(),
+            additional_context: Arc::new(additional_context),
+            render: Arc::new(render),
+        }
+    }
+
+    /// Sets the prefix the server functions were declared with. Defaults to `/api`.
+    pub fn server_fn_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.server_fn_prefix = prefix.into();
+        self
+    }
+
+    /// Responds to the request, with the whole body of the response.
+    pub async fn handle(
+        &self,
+        req: Request<impl Into<Bytes>>,
+    ) -> Response<Bytes> {
+        let started = Instant::now();
+        let req = req.map(|body| Body::from(body.into()));
+        let fn_name = (req.method() == Method::POST)
+            .then(|| {
+                req.uri()
+                    .path()
+                    .strip_prefix(self.server_fn_prefix.trim_end_matches('/'))?
+                    .strip_prefix('/')
+            })
+            .flatten()
+            .map(String::from);
+
+        let is_page = fn_name.is_none();
+        let res = match fn_name {
+            Some(fn_name) => {
+                let additional_context = Arc::clone(&self.additional_context);
+                handle_server_fn(&fn_name, req, move |cx| {
+                    additional_context(cx)
+                })
+                .await
+            }
+            None => (self.render)(req).await,
+        };
+
+        let (mut parts, body) = res.into_parts();
+        let body = match body::to_bytes(body).await {
+            Ok(body) => body,
+            Err(e) => {
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Bytes::from(format!(
+                        "Could not build the response: {e}"
+                    )))
+                    .expect("could not build Response")
+            }
+        };
+        if is_page {
+            let timing =
+                server_timing([("render", "Render page", started.elapsed())]);
+            parts.headers.append(SERVER_TIMING, timing);
+        }
+        parts
+            .headers
+            .insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+        Response::from_parts(parts, body)
+    }
+}
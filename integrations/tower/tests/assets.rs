@@ -0,0 +1,99 @@
+use futures::future::{ready, Ready};
+use http::{header, Request, Response, StatusCode};
+use hyper::Body;
+use leptos::leptos_config::{AssetManifest, IMMUTABLE_CACHE_CONTROL};
+use leptos_tower::AssetCacheControl;
+use std::{
+    convert::Infallible,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Stands in for a static file server, answering every request with `status`.
+#[derive(Clone)]
+struct Files {
+    status: StatusCode,
+    cache_control: Option<&'static str>,
+}
+
+impl Service<Request<Body>> for Files {
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = Ready<Result<Response<Body>, Infallible>>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: Request<Body>) -> Self::Future {
+        let mut res = Response::builder().status(self.status);
+        if let Some(cache_control) = self.cache_control {
+            res = res.header(header::CACHE_CONTROL, cache_control);
+        }
+        ready(Ok(res.body(Body::empty()).unwrap()))
+    }
+}
+
+fn manifest() -> AssetManifest {
+    AssetManifest::from_json(r#"{ "pkg/app.js": "pkg/app.3f9a2c.js" }"#)
+        .unwrap()
+}
+
+async fn cache_control(files: Files, path: &str) -> Option<String> {
+    let req = Request::get(path).body(Body::empty()).unwrap();
+    let res = AssetCacheControl::new(files, manifest())
+        .call(req)
+        .await
+        .unwrap();
+    res.headers()
+        .get(header::CACHE_CONTROL)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+const OK: Files = Files {
+    status: StatusCode::OK,
+    cache_control: None,
+};
+
+#[tokio::test]
+async fn fingerprinted_files_are_immutable() {
+    assert_eq!(
+        cache_control(OK, "/pkg/app.3f9a2c.js").await.as_deref(),
+        Some(IMMUTABLE_CACHE_CONTROL)
+    );
+}
+
+#[tokio::test]
+async fn other_files_are_revalidated() {
+    assert_eq!(
+        cache_control(OK, "/pkg/app.js").await.as_deref(),
+        Some("no-cache")
+    );
+    assert_eq!(
+        cache_control(OK, "/favicon.ico").await.as_deref(),
+        Some("no-cache")
+    );
+}
+
+#[tokio::test]
+async fn errors_and_existing_headers_are_left_alone() {
+    let missing = Files {
+        status: StatusCode::NOT_FOUND,
+        cache_control: None,
+    };
+    assert_eq!(cache_control(missing, "/pkg/app.3f9a2c.js").await, None);
+
+    let private = Files {
+        status: StatusCode::OK,
+        cache_control: Some("private"),
+    };
+    assert_eq!(
+        cache_control(private, "/pkg/app.3f9a2c.js")
+            .await
+            .as_deref(),
+        Some("private")
+    );
+}
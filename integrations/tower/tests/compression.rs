@@ -0,0 +1,126 @@
+use futures::StreamExt;
+use http::HeaderValue;
+use hyper::body::Bytes;
+use leptos::LeptosOptions;
+use leptos_tower::{compress_stream, ContentEncoding};
+use std::io::{Read, Write};
+
+fn negotiate(accept_encoding: &str) -> Option<ContentEncoding> {
+    ContentEncoding::negotiate(Some(accept_encoding))
+}
+
+#[test]
+fn brotli_wins_a_tie() {
+    assert_eq!(
+        negotiate("gzip, deflate, br"),
+        Some(ContentEncoding::Brotli)
+    );
+    assert_eq!(negotiate("gzip"), Some(ContentEncoding::Gzip));
+    assert_eq!(negotiate("deflate"), None);
+    assert_eq!(ContentEncoding::negotiate(None), None);
+}
+
+#[test]
+fn quality_values_are_respected() {
+    assert_eq!(
+        negotiate("br;q=0.5, gzip;q=0.8"),
+        Some(ContentEncoding::Gzip)
+    );
+    assert_eq!(
+        negotiate("br;q=1.0, gzip;q=0.8"),
+        Some(ContentEncoding::Brotli)
+    );
+    assert_eq!(negotiate("br;q=0, gzip"), Some(ContentEncoding::Gzip));
+    assert_eq!(negotiate("br; q=0, gzip; q=0"), None);
+}
+
+#[test]
+fn wildcard_covers_unlisted_codings() {
+    assert_eq!(negotiate("*"), Some(ContentEncoding::Brotli));
+    assert_eq!(negotiate("br;q=0, *"), Some(ContentEncoding::Gzip));
+    assert_eq!(negotiate("gzip, *;q=0"), Some(ContentEncoding::Gzip));
+    assert_eq!(negotiate("*;q=0"), None);
+}
+
+#[test]
+fn identity_is_not_a_compression() {
+    assert_eq!(negotiate("identity"), None);
+    assert_eq!(negotiate("identity;q=0"), None);
+    assert_eq!(negotiate("identity;q=0, gzip"), Some(ContentEncoding::Gzip));
+}
+
+#[test]
+fn compression_has_to_be_enabled() {
+    let accept = HeaderValue::from_static("gzip");
+    let options = LeptosOptions::builder().output_name("app").build();
+    assert_eq!(ContentEncoding::for_options(&options, Some(&accept)), None);
+
+    let options = LeptosOptions::builder()
+        .output_name("app")
+        .compression(true)
+        .build();
+    assert_eq!(
+        ContentEncoding::for_options(&options, Some(&accept)),
+        Some(ContentEncoding::Gzip)
+    );
+    assert_eq!(ContentEncoding::for_options(&options, None), None);
+}
+
+const CHUNKS: [&str; 3] = [
+    "<!DOCTYPE html><html><head></head><body>",
+    "<p>Loading...</p>",
+    "<template>Loaded</template></body></html>",
+];
+
+fn compressed_chunks(encoding: ContentEncoding) -> Vec<Bytes> {
+    let chunks = futures::stream::iter(CHUNKS)
+        .map(|chunk| Bytes::from_static(chunk.as_bytes()));
+    futures::executor::block_on(
+        compress_stream(encoding, chunks)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[test]
+fn gzip_round_trips_and_flushes_each_chunk() {
+    let compressed = compressed_chunks(ContentEncoding::Gzip);
+
+    // everything sent so far decompresses to everything rendered so far
+    let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+    for (i, chunk) in compressed.iter().take(CHUNKS.len()).enumerate() {
+        decoder.write_all(chunk).unwrap();
+        decoder.flush().unwrap();
+        assert_eq!(
+            decoder.get_ref().as_slice(),
+            CHUNKS[..=i].concat().as_bytes()
+        );
+    }
+
+    let mut html = String::new();
+    flate2::read::GzDecoder::new(compressed.concat().as_slice())
+        .read_to_string(&mut html)
+        .unwrap();
+    assert_eq!(html, CHUNKS.concat());
+}
+
+#[test]
+fn brotli_round_trips_and_flushes_each_chunk() {
+    let compressed = compressed_chunks(ContentEncoding::Brotli);
+
+    let mut decoder = brotli::DecompressorWriter::new(Vec::new(), 4096);
+    for (i, chunk) in compressed.iter().take(CHUNKS.len()).enumerate() {
+        decoder.write_all(chunk).unwrap();
+        decoder.flush().unwrap();
+        assert_eq!(
+            decoder.get_ref().as_slice(),
+            CHUNKS[..=i].concat().as_bytes()
+        );
+    }
+
+    let mut html = String::new();
+    brotli::Decompressor::new(compressed.concat().as_slice(), 4096)
+        .read_to_string(&mut html)
+        .unwrap();
+    assert_eq!(html, CHUNKS.concat());
+}
@@ -0,0 +1,28 @@
+use leptos_tower::server_timing;
+use std::time::Duration;
+
+#[test]
+fn phases_are_listed_in_order() {
+    let header = server_timing([
+        ("shell", "Render shell", Duration::from_micros(12_345)),
+        ("server_fn", "Run add_one", Duration::from_millis(3)),
+    ]);
+    assert_eq!(
+        header,
+        r#"shell;desc="Render shell";dur=12.3, server_fn;desc="Run add_one";dur=3.0"#
+    );
+}
+
+#[test]
+fn descriptions_are_escaped() {
+    let header =
+        server_timing([("server_fn", r#"Run "quote\path""#, Duration::ZERO)]);
+    assert_eq!(header, r#"server_fn;desc="Run \"quote\\path\"";dur=0.0"#);
+}
+
+#[test]
+fn characters_a_header_cannot_hold_are_dropped() {
+    let header =
+        server_timing([("shell", "Rendér\nshell", Duration::from_millis(1))]);
+    assert_eq!(header, r#"shell;desc="Rendrshell";dur=1.0"#);
+}
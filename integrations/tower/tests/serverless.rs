@@ -0,0 +1,121 @@
+use futures::Future;
+use http::{header, Request, StatusCode};
+use leptos::{
+    leptos_server::{Encoding, ServerFn},
+    *,
+};
+use leptos_tower::{ServerlessHandler, SERVER_TIMING};
+use serde::{Deserialize, Serialize};
+use std::{pin::Pin, time::Duration};
+
+/// A server function implemented by hand, because the `#[server]` macro only registers
+/// functions in crates built with an `ssr` feature: doubles `value`.
+#[derive(Clone, Serialize, Deserialize)]
+struct Double {
+    value: u32,
+}
+
+impl ServerFn for Double {
+    type Output = u32;
+
+    fn prefix() -> &'static str {
+        "/functions"
+    }
+
+    fn url() -> &'static str {
+        "double"
+    }
+
+    fn encoding() -> Encoding {
+        Encoding::Url
+    }
+
+    fn call_fn(
+        self,
+        _cx: Scope,
+    ) -> Pin<Box<dyn Future<Output = Result<u32, ServerFnError>>>> {
+        Box::pin(async move { Ok(self.value * 2) })
+    }
+}
+
+fn handler() -> ServerlessHandler {
+    let options = LeptosOptions::builder().output_name("serverless").build();
+    ServerlessHandler::new(options, |cx| view! { cx, <p>"Hello"</p> })
+        .server_fn_prefix("/functions")
+}
+
+#[tokio::test]
+async fn pages_are_rendered_whole() {
+    let res = handler().handle(Request::get("/").body("").unwrap()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()[header::CONTENT_TYPE],
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(
+        res.headers()[header::CONTENT_LENGTH],
+        res.body().len().to_string()
+    );
+
+    let html = String::from_utf8(res.body().to_vec()).unwrap();
+    assert!(html.trim_start().starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("Hello"));
+    assert!(html.trim_end().ends_with("</html>"));
+}
+
+#[tokio::test]
+async fn pages_report_the_time_taken_to_render_them_whole() {
+    let options = LeptosOptions::builder().output_name("serverless").build();
+    let handler = ServerlessHandler::new(options, |cx| {
+        let greeting = create_resource(
+            cx,
+            || (),
+            |_| async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                "Hello".to_string()
+            },
+        );
+        view! { cx,
+            <Suspense fallback=|| ()>
+                <p>{move || greeting.read()}</p>
+            </Suspense>
+        }
+    });
+
+    let res = handler.handle(Request::get("/").body("").unwrap()).await;
+    assert!(String::from_utf8_lossy(res.body()).contains("Hello"));
+    let timing = res
+        .headers()
+        .get_all(SERVER_TIMING)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect::<Vec<_>>();
+    assert!(timing[0].starts_with(r#"shell;desc="Render shell";dur="#));
+    let render = timing[1]
+        .strip_prefix(r#"render;desc="Render page";dur="#)
+        .unwrap();
+    assert!(render.parse::<f64>().unwrap() >= 50.0);
+}
+
+#[tokio::test]
+async fn server_fns_are_called() {
+    _ = Double::register();
+    let req = Request::post("/functions/double")
+        .header(header::ACCEPT, "application/json")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body("value=21")
+        .unwrap();
+    let res = handler().handle(req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body().as_ref(), b"42");
+    assert_eq!(res.headers()[header::CONTENT_LENGTH], "2");
+}
+
+#[tokio::test]
+async fn get_requests_under_the_prefix_render_the_app() {
+    let res = handler()
+        .handle(Request::get("/functions/double").body("").unwrap())
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(String::from_utf8_lossy(res.body()).contains("Hello"));
+}
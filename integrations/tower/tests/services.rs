@@ -0,0 +1,164 @@
+use futures::Future;
+use http::{header, Request, Response, StatusCode};
+use hyper::{body, Body};
+use leptos::{
+    leptos_server::{Encoding, ServerFn},
+    *,
+};
+use leptos_router::set_server_status;
+use leptos_tower::{
+    redirect, set_status, RenderService, ServerFnService, SERVER_TIMING,
+};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use tower_service::Service;
+
+/// A server function implemented by hand, because the `#[server]` macro only registers
+/// functions in crates built with an `ssr` feature: adds one to `value`, after redirecting
+/// or overriding the status if asked to.
+#[derive(Clone, Serialize, Deserialize)]
+struct AddOne {
+    value: u32,
+    redirect_to: Option<String>,
+    status: Option<u16>,
+}
+
+impl ServerFn for AddOne {
+    type Output = u32;
+
+    fn prefix() -> &'static str {
+        "/api"
+    }
+
+    fn url() -> &'static str {
+        "add_one"
+    }
+
+    fn encoding() -> Encoding {
+        Encoding::Url
+    }
+
+    fn call_fn(
+        self,
+        cx: Scope,
+    ) -> Pin<Box<dyn Future<Output = Result<u32, ServerFnError>>>> {
+        Box::pin(async move {
+            if let Some(path) = &self.redirect_to {
+                redirect(cx, path);
+            }
+            if let Some(status) = self.status {
+                set_status(cx, status);
+            }
+            Ok(self.value + 1)
+        })
+    }
+}
+
+async fn call_add_one(body: &'static str) -> Response<Body> {
+    _ = AddOne::register();
+    let req = Request::post("/api/add_one")
+        .header(header::ACCEPT, "application/json")
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(Body::from(body))
+        .unwrap();
+    ServerFnService::new("/api").call(req).await.unwrap()
+}
+
+async fn text(res: Response<Body>) -> String {
+    let body = body::to_bytes(res.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn server_fns_round_trip() {
+    let res = call_add_one("value=1").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers()[SERVER_TIMING]
+        .to_str()
+        .unwrap()
+        .starts_with(r#"server-fn;desc="add_one";dur="#));
+    assert_eq!(text(res).await, "2");
+}
+
+#[tokio::test]
+async fn server_fns_can_redirect() {
+    let res = call_add_one("value=1&redirect_to=%2Flogin").await;
+    assert_eq!(res.status(), StatusCode::FOUND);
+    assert_eq!(res.headers()[header::LOCATION], "/login");
+}
+
+#[tokio::test]
+async fn server_fns_can_override_the_status() {
+    let res = call_add_one("value=1&status=202").await;
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    assert_eq!(text(res).await, "2");
+}
+
+#[tokio::test]
+async fn unknown_server_fns_are_bad_requests() {
+    let req = Request::post("/api/missing").body(Body::empty()).unwrap();
+    let res = ServerFnService::new("/api").call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+fn render_service(
+    app_fn: impl Fn(Scope) -> View + Clone + Send + Sync + 'static,
+) -> RenderService {
+    let options = LeptosOptions::builder().output_name("app").build();
+    RenderService::new(options, app_fn)
+}
+
+#[tokio::test]
+async fn pages_stream_inside_the_shell() {
+    let mut render =
+        render_service(|cx| view! { cx, <p>"Hello"</p> }.into_view(cx));
+    let req = Request::get("/").body(Body::empty()).unwrap();
+    let res = render.call(req).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()[header::CONTENT_TYPE],
+        "text/html; charset=utf-8"
+    );
+    assert!(res.headers()[SERVER_TIMING]
+        .to_str()
+        .unwrap()
+        .starts_with(r#"shell;desc="Render shell";dur="#));
+    assert!(res.headers()[header::LINK]
+        .to_str()
+        .unwrap()
+        .contains("/pkg/app.js"));
+    let html = text(res).await;
+    assert!(html.trim_start().starts_with("<!DOCTYPE html>"), "{html}");
+    assert!(html.contains("Hello</p>"), "{html}");
+    assert!(html.contains("import init, { hydrate }"), "{html}");
+    assert!(html.trim_end().ends_with("</html>"), "{html}");
+}
+
+#[tokio::test]
+async fn the_bundle_is_preloaded_before_the_app_runs() {
+    let mut render = render_service(|cx| {
+        let res_options = use_context::<leptos_tower::ResponseOptions>(cx)
+            .expect("the response options are provided");
+        let preloaded = res_options.0.read().headers.contains_key(header::LINK);
+        view! { cx, <p>{preloaded.to_string()}</p> }.into_view(cx)
+    });
+    let req = Request::get("/").body(Body::empty()).unwrap();
+    let res = render.call(req).await.unwrap();
+
+    let html = text(res).await;
+    assert!(html.contains("true</p>"), "{html}");
+}
+
+#[tokio::test]
+async fn pages_can_set_the_status() {
+    let mut render = render_service(|cx| {
+        set_server_status(cx, 404);
+        view! { cx, <p>"Not Found"</p> }.into_view(cx)
+    });
+    let req = Request::get("/missing").body(Body::empty()).unwrap();
+    let res = render.call(req).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(text(res).await.contains("Not Found"));
+}
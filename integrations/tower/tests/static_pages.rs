@@ -0,0 +1,96 @@
+use leptos::*;
+use leptos_router::*;
+use leptos_tower::{generate_static_site, static_page_file};
+use std::{collections::HashMap, path::Path};
+
+#[component]
+fn Site(cx: Scope) -> impl IntoView {
+    view! { cx,
+        <Router>
+            <Routes>
+                <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+                <Route path="post/:id" view=|cx| view! { cx, <Post/> }/>
+            </Routes>
+        </Router>
+    }
+}
+
+#[component]
+fn Post(cx: Scope) -> impl IntoView {
+    let params = use_params_map(cx);
+    let title = create_resource(
+        cx,
+        move || params.with(|p| p.get("id").cloned().unwrap_or_default()),
+        |id| async move {
+            tokio::task::yield_now().await;
+            format!("Post {id}")
+        },
+    );
+    view! { cx,
+        <Suspense fallback=|| "Loading...">
+            <h1>{move || title.read()}</h1>
+        </Suspense>
+    }
+}
+
+#[test]
+fn static_pages_are_written_under_the_site_root() {
+    assert_eq!(
+        static_page_file("site", "/").unwrap(),
+        Path::new("site/index.html")
+    );
+    assert_eq!(
+        static_page_file("site", "/post/1").unwrap(),
+        Path::new("site/post/1/index.html")
+    );
+    for path in ["/../outside", "/post/../../outside", "/a\\..\\b"] {
+        let err = static_page_file("site", path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{path}");
+    }
+}
+
+#[tokio::test]
+async fn every_route_is_rendered_once_its_resources_resolve() {
+    let site_root = std::env::temp_dir()
+        .join(format!("leptos-static-site-{}", std::process::id()));
+    let options = LeptosOptions::builder()
+        .output_name("app")
+        .site_root(site_root.to_string_lossy().to_string())
+        .build();
+    let posts = ["1", "2"]
+        .into_iter()
+        .map(|id| {
+            let mut params = ParamsMap::new();
+            params.insert("id".to_string(), id.to_string());
+            params
+        })
+        .collect();
+    let params = HashMap::from([("/post/:id".to_string(), posts)]);
+
+    let mut files = generate_static_site(
+        &options,
+        |cx| view! { cx, <Site/> },
+        params,
+        false,
+    )
+    .await
+    .unwrap();
+    files.sort();
+    let read = |path: &str| std::fs::read_to_string(site_root.join(path));
+    let home = read("index.html").unwrap();
+    let post = read("post/2/index.html").unwrap();
+    std::fs::remove_dir_all(&site_root).unwrap();
+
+    assert_eq!(
+        files,
+        vec![
+            site_root.join("index.html"),
+            site_root.join("post/1/index.html"),
+            site_root.join("post/2/index.html"),
+        ]
+    );
+    assert!(home.contains("Home"), "{home}");
+    assert!(post.contains("Post 2"), "{post}");
+    assert!(!post.contains("Loading..."), "{post}");
+    assert!(!post.contains("app.js"), "{post}");
+}
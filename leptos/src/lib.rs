@@ -146,7 +146,7 @@ pub use leptos_config::{self, get_configuration, LeptosOptions};
     target_arch = "wasm32",
     any(feature = "csr", feature = "hydrate")
 )))]
-pub use leptos_dom::ssr::{self, render_to_string};
+pub use leptos_dom::ssr::{self, render_to_string, render_to_string_async};
 pub use leptos_dom::{
     self, create_node_ref, debug_warn, document, error, ev,
     helpers::{
@@ -161,8 +161,9 @@ pub use leptos_dom::{
 pub use leptos_macro::*;
 pub use leptos_reactive::*;
 pub use leptos_server::{
-    self, create_action, create_multi_action, create_server_action,
-    create_server_multi_action, Action, MultiAction, ServerFn, ServerFnError,
+    self, create_action, create_channel_signal, create_multi_action,
+    create_server_action, create_server_multi_action, csrf_token, Action,
+    Channel, MultiAction, ServerFn, ServerFnError,
 };
 pub use typed_builder;
 mod error_boundary;
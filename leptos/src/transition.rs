@@ -1,7 +1,13 @@
 use leptos_dom::{Fragment, IntoView, View};
 use leptos_macro::component;
-use leptos_reactive::{Scope, SignalSetter};
-use std::{cell::RefCell, rc::Rc};
+use leptos_reactive::{
+    create_signal, on_cleanup, use_context, ReadSignal, Scope, SignalSetter,
+    WriteSignal,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 /// If any [Resource](leptos_reactive::Resource)s are read in the `children` of this
 /// component, it will show the `fallback` while they are loading. Once all are resolved,
@@ -75,15 +81,40 @@ where
     E: IntoView,
 {
     let prev_children = std::rc::Rc::new(RefCell::new(None::<Vec<View>>));
+
+    // let any PendingTransitions above this know whether it's waiting on resources
+    let pending_transitions = use_context::<PendingTransitions>(cx);
+    let is_pending = Rc::new(Cell::new(false));
+    let report_pending = move |pending: bool| {
+        if let Some(pending_transitions) = pending_transitions {
+            if is_pending.replace(pending) != pending {
+                if pending {
+                    pending_transitions.increment();
+                } else {
+                    pending_transitions.decrement();
+                }
+            }
+        }
+    };
+    on_cleanup(cx, {
+        let report_pending = report_pending.clone();
+        move || report_pending(false)
+    });
+    let set_pending = move |pending: bool| {
+        if let Some(set_pending) = &set_pending {
+            set_pending.set(pending);
+        }
+        report_pending(pending);
+    };
+
     crate::Suspense(
         cx,
         crate::SuspenseProps::builder()
             .fallback({
                 let prev_child = Rc::clone(&prev_children);
+                let set_pending = set_pending.clone();
                 move || {
-                    if let Some(set_pending) = &set_pending {
-                        set_pending.set(true);
-                    }
+                    set_pending(true);
                     if let Some(prev_children) = &*prev_child.borrow() {
                         prev_children.clone().into_view(cx)
                     } else {
@@ -94,11 +125,50 @@ where
             .children(Box::new(move |cx| {
                 let frag = children(cx);
                 *prev_children.borrow_mut() = Some(frag.nodes.clone());
-                if let Some(set_pending) = &set_pending {
-                    set_pending.set(false);
-                }
+                set_pending(false);
                 frag
             }))
             .build(),
     )
 }
+
+/// Counts the [`<Transition/>`](Transition)s beneath it that are currently waiting
+/// for resources to load.
+///
+/// Provide this as context above a view to find out when it is ready to be shown.
+/// For example, the router uses it to keep showing the previous route until every
+/// `<Transition/>` in the next route has resolved.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PendingTransitions {
+    pending: ReadSignal<usize>,
+    set_pending: WriteSignal<usize>,
+}
+
+impl PendingTransitions {
+    /// Creates a new counter, with no pending transitions.
+    pub fn new(cx: Scope) -> Self {
+        let (pending, set_pending) = create_signal(cx, 0);
+        Self {
+            pending,
+            set_pending,
+        }
+    }
+
+    /// A signal containing the number of transitions that are currently pending.
+    pub fn pending(&self) -> ReadSignal<usize> {
+        self.pending
+    }
+
+    /// Whether every transition has resolved. Reactive.
+    pub fn ready(&self) -> bool {
+        self.pending.with(|n| *n == 0)
+    }
+
+    fn increment(&self) {
+        self.set_pending.update(|n| *n += 1);
+    }
+
+    fn decrement(&self) {
+        self.set_pending.update(|n| *n = n.saturating_sub(1));
+    }
+}
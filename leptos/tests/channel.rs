@@ -0,0 +1,99 @@
+#[cfg(feature = "ssr")]
+mod channels {
+    use futures::StreamExt;
+    use leptos::{
+        leptos_server::{subscribe_to_channel, CHANNEL_BUFFER},
+        *,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Message {
+        text: String,
+    }
+
+    #[test]
+    fn channel_url_uses_prefix() {
+        let channel = Channel::<Message>::new("chat");
+        assert_eq!(channel.name(), "chat");
+        assert_eq!(channel.url(), "/channels/chat");
+        let channel = channel.with_prefix("/api/events/");
+        assert_eq!(channel.url(), "/api/events/chat");
+    }
+
+    #[test]
+    fn broadcast_reaches_subscribers() {
+        let channel = Channel::<Message>::new("broadcast_reaches_subscribers");
+        let message = Message {
+            text: "hello".into(),
+        };
+        assert_eq!(channel.broadcast(&message).unwrap(), 0);
+
+        let first = subscribe_to_channel(&channel.url());
+        let second = subscribe_to_channel(&channel.url());
+        let other = subscribe_to_channel("/channels/some_other_channel");
+        assert_eq!(channel.broadcast(&message).unwrap(), 2);
+
+        let received = futures::executor::block_on(async {
+            let first = first.take(1).collect::<Vec<_>>().await;
+            let second = second.take(1).collect::<Vec<_>>().await;
+            (first, second)
+        });
+        assert_eq!(received.0, vec![r#"{"text":"hello"}"#.to_string()]);
+        assert_eq!(received.0, received.1);
+        drop(other);
+    }
+
+    #[test]
+    fn dropped_subscribers_are_removed() {
+        let channel =
+            Channel::<Message>::new("dropped_subscribers_are_removed");
+        let message = Message { text: "hi".into() };
+        let kept = subscribe_to_channel(&channel.url());
+        drop(subscribe_to_channel(&channel.url()));
+        assert_eq!(channel.broadcast(&message).unwrap(), 1);
+        drop(kept);
+        assert_eq!(channel.broadcast(&message).unwrap(), 0);
+    }
+
+    #[test]
+    fn same_name_under_other_prefix_is_separate() {
+        let channel = Channel::<Message>::new("same_name_under_other_prefix");
+        let other = channel.clone().with_prefix("/admin/channels");
+        let subscriber = subscribe_to_channel(&channel.url());
+        let message = Message { text: "hi".into() };
+        assert_eq!(other.broadcast(&message).unwrap(), 0);
+        assert_eq!(channel.broadcast(&message).unwrap(), 1);
+        drop(subscriber);
+    }
+
+    #[test]
+    fn slow_subscribers_are_dropped() {
+        let channel = Channel::<Message>::new("slow_subscribers_are_dropped");
+        let message = Message { text: "hi".into() };
+        let slow = subscribe_to_channel(&channel.url());
+        let mut fast = subscribe_to_channel(&channel.url());
+        let mut sent = 0;
+        while channel.broadcast(&message).unwrap() == 2 {
+            futures::executor::block_on(fast.next()).unwrap();
+            sent += 1;
+            assert!(sent <= CHANNEL_BUFFER + 1, "slow subscriber was kept");
+        }
+        assert!(sent >= CHANNEL_BUFFER);
+
+        // the slow subscriber still gets what it was sent, then its stream ends
+        let received = futures::executor::block_on(slow.collect::<Vec<_>>());
+        assert_eq!(received.len(), sent);
+        assert_eq!(channel.broadcast(&message).unwrap(), 1);
+        drop(fast);
+    }
+
+    #[test]
+    fn channel_signal_is_empty_on_server() {
+        run_scope(create_runtime(), |cx| {
+            let message =
+                create_channel_signal(cx, Channel::<Message>::new("chat"));
+            assert_eq!(message.get(), None);
+        });
+    }
+}
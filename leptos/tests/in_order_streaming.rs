@@ -0,0 +1,102 @@
+#![cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+
+#[test]
+fn in_order_stream_waits_for_suspense() {
+    use futures::StreamExt;
+    use leptos::*;
+
+    #[component]
+    fn Post(cx: Scope) -> impl IntoView {
+        let title = create_resource(
+            cx,
+            || (),
+            |_| async {
+                tokio::task::yield_now().await;
+                "Hello, world!".to_string()
+            },
+        );
+        view! { cx,
+            <header>"Blog"</header>
+            <Suspense fallback=|| "Loading...">
+                <h1>{move || title.read()}</h1>
+            </Suspense>
+            <footer>"Footer"</footer>
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let chunks =
+        runtime.block_on(tokio::task::LocalSet::new().run_until(async {
+            ssr::render_to_stream_in_order(|cx| {
+                view! { cx, <Post/> }.into_view(cx)
+            })
+            .collect::<Vec<_>>()
+            .await
+        }));
+    let html = chunks.concat();
+
+    assert!(!html.contains("Loading..."));
+    assert!(!html.contains("<template"));
+    let header = html.find("Blog").unwrap();
+    let title = html.find("<h1").unwrap();
+    let footer = html.find("Footer").unwrap();
+    assert!(header < title && title < footer);
+    assert!(html[title..].contains("Hello, world!"));
+    // everything before the <Suspense/> is sent before it resolves
+    assert!(chunks.iter().any(
+        |chunk| chunk.contains("Blog") && !chunk.contains("Hello, world!")
+    ));
+}
+
+#[test]
+fn in_order_stream_resolves_nested_suspense() {
+    use futures::StreamExt;
+    use leptos::*;
+
+    #[component]
+    fn Post(cx: Scope) -> impl IntoView {
+        let title = create_resource(
+            cx,
+            || (),
+            |_| async {
+                tokio::task::yield_now().await;
+                "Title".to_string()
+            },
+        );
+        let comments = create_resource(
+            cx,
+            || (),
+            |_| async {
+                tokio::task::yield_now().await;
+                tokio::task::yield_now().await;
+                "Comments".to_string()
+            },
+        );
+        view! { cx,
+            <Suspense fallback=|| "Loading post...">
+                <h1>{move || title.read()}</h1>
+                <Suspense fallback=|| "Loading comments...">
+                    <p>{move || comments.read()}</p>
+                </Suspense>
+            </Suspense>
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let html =
+        runtime.block_on(tokio::task::LocalSet::new().run_until(async {
+            ssr::render_to_stream_in_order(|cx| {
+                view! { cx, <Post/> }.into_view(cx)
+            })
+            .collect::<String>()
+            .await
+        }));
+
+    assert!(!html.contains("Loading"));
+    assert!(html.contains("Title"));
+    assert!(html.contains("Comments"));
+}
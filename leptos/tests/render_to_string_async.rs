@@ -0,0 +1,40 @@
+#![cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+
+#[test]
+fn render_to_string_async_waits_for_resources() {
+    use leptos::*;
+
+    #[component]
+    fn Post(cx: Scope) -> impl IntoView {
+        let title = create_resource(
+            cx,
+            || (),
+            |_| async {
+                tokio::task::yield_now().await;
+                "Hello, world!".to_string()
+            },
+        );
+        view! { cx,
+            <Suspense fallback=|| "Loading...">
+                <h1>{move || title.read()}</h1>
+            </Suspense>
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let (html, resources) = runtime.block_on(
+        tokio::task::LocalSet::new().run_until(ssr::render_to_string_async(
+            |cx| view! { cx, <Post/> }.into_view(cx),
+        )),
+    );
+
+    assert!(!html.contains("Loading..."));
+    assert!(!html.contains("<script"));
+    assert!(html.contains("Hello, world!"));
+    assert_eq!(
+        resources.into_values().collect::<Vec<_>>(),
+        vec!["\"Hello, world!\"".to_string()]
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+
+use leptos::*;
+
+const PAYLOAD: &str = "</script><script>alert(1)</script>";
+
+#[component]
+fn Post(cx: Scope) -> impl IntoView {
+    let title = create_resource(
+        cx,
+        || (),
+        |_| async {
+            tokio::task::yield_now().await;
+            PAYLOAD.to_string()
+        },
+    );
+    view! { cx,
+        <Suspense fallback=|| "Loading...">
+            <h1>{move || title.read().map(|_| "Loaded")}</h1>
+        </Suspense>
+    }
+}
+
+fn run<T>(fut: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(tokio::task::LocalSet::new().run_until(fut))
+}
+
+#[test]
+fn streamed_resources_cannot_close_their_script() {
+    use futures::StreamExt;
+
+    let html = run(async {
+        let (stream, runtime, _) = ssr::render_to_stream_with_prefix_undisposed(
+            |cx| view! { cx, <Post/> }.into_view(cx),
+            |_| "".into(),
+        );
+        let html = stream.collect::<String>().await;
+        runtime.dispose();
+        html
+    });
+
+    assert!(html.contains("Loaded"));
+    assert!(!html.contains("<script>alert(1)"), "{html}");
+    assert!(html.contains(r"\u003c/script\u003e"), "{html}");
+}
+
+#[test]
+fn resolved_resources_cannot_close_their_script() {
+    let (_, resources) = run(ssr::render_to_string_async(|cx| {
+        view! { cx, <Post/> }.into_view(cx)
+    }));
+    let script = ssr::resolved_resources_script(None, &resources);
+
+    assert!(!script.contains("<script>alert(1)"), "{script}");
+    assert_eq!(script.matches("</script>").count(), 1, "{script}");
+    assert!(script.contains(r"\u003c/script\u003e"), "{script}");
+}
+
+#[test]
+fn escaped_scripts_only_change_markup_characters() {
+    assert_eq!(
+        ssr::escape_script("{\"a\":\"</script> & \u{2028}\u{2029}\"}"),
+        r#"{"a":"\u003c/script\u003e \u0026 \u2028\u2029"}"#
+    );
+}
@@ -0,0 +1,101 @@
+#[cfg(feature = "ssr")]
+mod server_fns {
+    use leptos::{leptos_server::openapi_document, *};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct Post {
+        title: String,
+    }
+
+    /// Lists the posts.
+    ///
+    /// Returns at most `count` posts, newest first.
+    #[server(ListPosts, "/api")]
+    pub async fn list_posts(
+        cx: Scope,
+        count: usize,
+        tag: Option<String>,
+    ) -> Result<Vec<Post>, ServerFnError> {
+        _ = (cx, count, tag);
+        Ok(Vec::new())
+    }
+
+    #[server(DeletePost, "/api", "Cbor")]
+    pub async fn delete_post(id: u32) -> Result<(), ServerFnError> {
+        _ = id;
+        Ok(())
+    }
+
+    #[test]
+    fn document_describes_server_fns() {
+        let document = openapi_document("Blog", "1.0.0");
+        assert_eq!(document["openapi"], "3.1.0");
+        assert_eq!(document["info"]["title"], "Blog");
+
+        let list =
+            &document["paths"][format!("/api/{}", ListPosts::url())]["post"];
+        assert_eq!(list["operationId"], "list_posts");
+        assert_eq!(list["summary"], "Lists the posts.");
+        assert_eq!(
+            list["description"],
+            "Returns at most `count` posts, newest first."
+        );
+
+        let request = &list["requestBody"]["content"]
+            ["application/x-www-form-urlencoded"]["schema"];
+        assert_eq!(request["properties"]["count"]["type"], "integer");
+        assert_eq!(request["properties"]["tag"]["type"][0], "string");
+        assert_eq!(request["properties"]["tag"]["type"][1], "null");
+        assert!(request["properties"]["cx"].is_null());
+        assert_eq!(request["required"][0], "count");
+        assert!(request["required"][1].is_null());
+
+        let response = &list["responses"]["200"]["content"]
+            ["application/x-www-form-urlencoded"]["schema"];
+        assert_eq!(response["type"], "array");
+        assert_eq!(response["items"]["$ref"], "#/components/schemas/Post");
+        let post = &document["components"]["schemas"]["Post"];
+        assert_eq!(post["type"], "object");
+        assert_eq!(post["title"], "Post");
+        assert_eq!(
+            list["responses"]["500"]["content"]["text/plain"]["schema"]["$ref"],
+            "#/components/schemas/ServerFnError"
+        );
+    }
+
+    #[server(FeaturedPost, "/api")]
+    pub async fn featured_post() -> Result<(u32, Option<Post>), ServerFnError> {
+        Ok((0, None))
+    }
+
+    #[test]
+    fn document_describes_tuples_by_position() {
+        let document = openapi_document("Blog", "1.0.0");
+        let featured =
+            &document["paths"][format!("/api/{}", FeaturedPost::url())]["post"];
+        let response = &featured["responses"]["200"]["content"]
+            ["application/x-www-form-urlencoded"]["schema"];
+        assert_eq!(response["type"], "array");
+        assert_eq!(response["prefixItems"][0]["type"], "integer");
+        assert_eq!(
+            response["prefixItems"][1]["anyOf"][0]["$ref"],
+            "#/components/schemas/Post"
+        );
+        assert_eq!(response["prefixItems"][1]["anyOf"][1]["type"], "null");
+        assert_eq!(response["items"], false);
+        assert_eq!(response["minItems"], 2);
+        assert_eq!(response["maxItems"], 2);
+    }
+
+    #[test]
+    fn document_uses_encoding_for_content_type() {
+        let document = openapi_document("Blog", "1.0.0");
+        let delete =
+            &document["paths"][format!("/api/{}", DeletePost::url())]["post"];
+        let request =
+            &delete["requestBody"]["content"]["application/cbor"]["schema"];
+        assert_eq!(request["properties"]["id"]["format"], "int64");
+        assert!(delete["summary"].is_null());
+    }
+}
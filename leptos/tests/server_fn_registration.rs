@@ -0,0 +1,40 @@
+#[cfg(feature = "ssr")]
+mod server_fns {
+    use leptos::{
+        leptos_server::{
+            check_server_fn_urls, server_fn_by_path, server_fns_by_path,
+            Payload,
+        },
+        *,
+    };
+
+    #[server(AddOne, "/api")]
+    pub async fn add_one(value: i32) -> Result<i32, ServerFnError> {
+        Ok(value + 1)
+    }
+
+    #[test]
+    fn server_fns_are_registered_automatically() {
+        assert!(server_fns_by_path().contains(&AddOne::url()));
+        assert!(server_fn_by_path(AddOne::url()).is_some());
+        assert!(check_server_fn_urls().is_empty());
+    }
+
+    #[test]
+    fn registering_again_is_a_no_op() {
+        assert!(AddOne::register().is_ok());
+        assert!(server_fn_by_path(AddOne::url()).is_some());
+    }
+
+    #[test]
+    fn registered_server_fn_runs() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let server_fn = server_fn_by_path(AddOne::url()).unwrap();
+        let result = run_scope(create_runtime(), move |cx| {
+            runtime.block_on(server_fn(cx, b"value=41"))
+        });
+        assert!(matches!(result, Ok(Payload::Url(json)) if json == "42"));
+    }
+}
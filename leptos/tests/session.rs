@@ -0,0 +1,180 @@
+#[cfg(feature = "ssr")]
+mod sessions {
+    use leptos::{
+        leptos_server::{
+            MemorySessionStore, Session, SessionData, SessionOptions,
+            SessionStore, CSRF_FIELD,
+        },
+        *,
+    };
+    use std::{sync::Arc, time::Duration};
+
+    const KEY: &[u8] = b"a secret key of at least 32 bytes!";
+
+    fn cookie_value(set_cookie: &str) -> &str {
+        set_cookie.split(';').next().unwrap()
+    }
+
+    #[test]
+    fn unchanged_new_session_sets_no_cookie() {
+        let options = SessionOptions::new(KEY);
+        let session = options.load(None);
+        assert_eq!(options.save(&session), None);
+    }
+
+    #[test]
+    fn session_is_loaded_from_signed_cookie() {
+        let options = SessionOptions::new(KEY);
+        let session = options.load(None);
+        session.insert("user", "alice").unwrap();
+        let set_cookie = options.save(&session).unwrap();
+        assert!(set_cookie.starts_with("leptos_session="));
+        assert!(set_cookie.contains("HttpOnly"));
+        assert!(set_cookie.contains("; Max-Age=604800;"), "{set_cookie}");
+
+        let cookie = format!("theme=dark; {}", cookie_value(&set_cookie));
+        let loaded = options.load(Some(&cookie));
+        assert_eq!(loaded.id(), session.id());
+        assert_eq!(loaded.get::<String>("user").as_deref(), Some("alice"));
+        // the cookie is only sent when the session starts
+        loaded.insert("user", "bob").unwrap();
+        assert_eq!(options.save(&loaded), None);
+    }
+
+    #[test]
+    fn forged_cookie_starts_new_session() {
+        let options = SessionOptions::new(KEY);
+        let session = options.load(None);
+        session.insert("user", "alice").unwrap();
+        let set_cookie = options.save(&session).unwrap();
+        let (_, signature) = cookie_value(&set_cookie).split_once('.').unwrap();
+
+        let forged = format!("leptos_session=someone-else.{signature}");
+        let loaded = options.load(Some(&forged));
+        assert_ne!(loaded.id(), session.id());
+        assert_eq!(loaded.get::<String>("user"), None);
+
+        let other_key = SessionOptions::new([7; 32]);
+        let loaded = other_key.load(Some(cookie_value(&set_cookie)));
+        assert_ne!(loaded.id(), session.id());
+    }
+
+    #[test]
+    fn renewed_session_moves_to_new_id() {
+        let options = SessionOptions::new(KEY);
+        let session = options.load(None);
+        session.insert("user", "alice").unwrap();
+        let old_cookie = options.save(&session).unwrap();
+
+        let loaded = options.load(Some(cookie_value(&old_cookie)));
+        loaded.renew();
+        let new_cookie = options.save(&loaded).unwrap();
+        assert_ne!(loaded.id(), session.id());
+
+        let old = options.load(Some(cookie_value(&old_cookie)));
+        assert_eq!(old.get::<String>("user"), None);
+        let new = options.load(Some(cookie_value(&new_cookie)));
+        assert_eq!(new.get::<String>("user").as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn csrf_token_is_checked_from_header_or_form_field() {
+        let session = SessionOptions::new(KEY).load(None);
+        assert!(!session.verify_csrf_request(None, b""));
+
+        let token = session.csrf_token();
+        assert_eq!(session.csrf_token(), token);
+        assert!(session.verify_csrf_request(Some(&token), b""));
+        assert!(!session.verify_csrf_request(Some("wrong"), b""));
+
+        let body = format!("name=alice&{CSRF_FIELD}={token}");
+        assert!(session.verify_csrf_request(None, body.as_bytes()));
+        assert!(!session.verify_csrf_request(None, b"name=alice&_csrf=x"));
+        assert!(!session.verify_csrf_request(None, b"name=alice"));
+    }
+
+    #[test]
+    fn csrf_token_is_read_from_context() {
+        let session = SessionOptions::new(KEY).load(None);
+        let expected = session.csrf_token();
+        let token = run_scope(create_runtime(), move |cx| {
+            assert_eq!(csrf_token(cx), None);
+            provide_context::<Session>(cx, session);
+            csrf_token(cx)
+        });
+        assert_eq!(token, Some(expected));
+    }
+
+    /// Lets a test look into the store its [SessionOptions] use.
+    struct SharedStore(Arc<MemorySessionStore>);
+
+    impl SessionStore for SharedStore {
+        fn load(&self, id: &str) -> Option<SessionData> {
+            self.0.load(id)
+        }
+
+        fn save(&self, id: &str, data: SessionData, max_age: Duration) {
+            self.0.save(id, data, max_age)
+        }
+
+        fn remove(&self, id: &str) {
+            self.0.remove(id)
+        }
+    }
+
+    #[test]
+    fn sessions_with_only_a_csrf_token_are_not_stored() {
+        let store = Arc::new(MemorySessionStore::default());
+        let options =
+            SessionOptions::new(KEY).store(SharedStore(Arc::clone(&store)));
+        let session = options.load(None);
+        let token = session.csrf_token();
+        assert_eq!(session.get::<String>(CSRF_FIELD), Some(token.clone()));
+
+        let set_cookie = options.save(&session).unwrap();
+        assert!(store.is_empty());
+
+        let loaded = options.load(Some(cookie_value(&set_cookie)));
+        assert_eq!(loaded.id(), session.id());
+        assert!(loaded.verify_csrf_token(&token));
+        assert_eq!(loaded.csrf_token(), token);
+        assert_eq!(options.save(&loaded), None);
+        assert!(store.is_empty());
+
+        loaded.insert("user", "alice").unwrap();
+        assert_eq!(options.save(&loaded), None);
+        assert_eq!(store.len(), 1);
+        let stored = store.load(&session.id()).unwrap();
+        assert_eq!(stored[CSRF_FIELD], format!("\"{token}\""));
+    }
+
+    #[test]
+    fn memory_store_drops_expired_sessions() {
+        let store = MemorySessionStore::default();
+        store.save("expired", SessionData::new(), Duration::ZERO);
+        store.save("current", SessionData::new(), Duration::from_secs(60));
+        assert_eq!(store.load("expired"), None);
+        assert_eq!(store.load("current"), Some(SessionData::new()));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn memory_store_makes_room_for_new_sessions() {
+        let store = MemorySessionStore::with_max_sessions(2);
+        store.save("expired", SessionData::new(), Duration::ZERO);
+        store.save("first", SessionData::new(), Duration::from_secs(60));
+        store.save("second", SessionData::new(), Duration::from_secs(120));
+        assert_eq!(store.len(), 2);
+        assert!(store.load("first").is_some());
+
+        store.save("third", SessionData::new(), Duration::from_secs(180));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.load("first"), None);
+        assert!(store.load("second").is_some());
+        assert!(store.load("third").is_some());
+
+        // saving a session that is already stored doesn't push another one out
+        store.save("second", SessionData::new(), Duration::from_secs(240));
+        assert!(store.load("third").is_some());
+    }
+}
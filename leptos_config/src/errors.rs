@@ -1,4 +1,4 @@
-use std::{net::AddrParseError, num::ParseIntError};
+use std::{net::AddrParseError, num::ParseIntError, str::ParseBoolError};
 use thiserror::Error;
 
 #[derive(Debug, Error, Clone)]
@@ -13,6 +13,10 @@ pub enum LeptosConfigError {
     ConfigError(String),
     #[error("Config Error: {0}")]
     EnvVarError(String),
+    #[error("Asset Manifest Error: {0}")]
+    AssetManifestError(String),
+    #[error("HTML Shell Error: {0}")]
+    HtmlShellError(String),
 }
 impl From<config::ConfigError> for LeptosConfigError {
     fn from(e: config::ConfigError) -> Self {
@@ -26,6 +30,12 @@ impl From<ParseIntError> for LeptosConfigError {
     }
 }
 
+impl From<ParseBoolError> for LeptosConfigError {
+    fn from(e: ParseBoolError) -> Self {
+        Self::ConfigError(e.to_string())
+    }
+}
+
 impl From<AddrParseError> for LeptosConfigError {
     fn from(e: AddrParseError) -> Self {
         Self::ConfigError(e.to_string())
@@ -1,10 +1,14 @@
 #![forbid(unsafe_code)]
 
 pub mod errors;
+mod manifest;
+mod shell;
 
 use crate::errors::LeptosConfigError;
 use config::{Config, File, FileFormat};
+pub use manifest::*;
 use regex::Regex;
+pub use shell::*;
 use std::{convert::TryFrom, env::VarError, fs, net::SocketAddr, str::FromStr};
 use typed_builder::TypedBuilder;
 
@@ -46,6 +50,34 @@ pub struct LeptosOptions {
     /// Defaults to `3001`
     #[builder(default = 3001)]
     pub reload_port: u32,
+    /// The path of an [AssetManifest] that maps static assets to fingerprinted files,
+    /// relative to the `site_root`. Defaults to `None`, in which case assets are
+    /// served under their own names.
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub asset_manifest: Option<String>,
+    /// The path of a template for the [HtmlShell] the integrations render your app into,
+    /// relative to the directory the server runs in. Defaults to `None`, in which case
+    /// the [DEFAULT_HTML_SHELL] is used.
+    #[builder(default, setter(strip_option, into))]
+    #[serde(default)]
+    pub html_shell: Option<String>,
+    /// Whether the server integrations should send a `Content-Security-Policy` header
+    /// that only allows scripts carrying the response's nonce (and the scripts and WASM
+    /// they load). Styles aren't restricted by it. Defaults to `false`. A header set by the
+    /// app through `ResponseOptions` takes precedence, so an app that wants a `style-src`
+    /// policy as well can send its own, using the nonce that `<Style/>` and scoped styles
+    /// already carry.
+    #[builder(default)]
+    #[serde(default)]
+    pub csp_header: bool,
+    /// Whether the server integrations should compress rendered pages with brotli or gzip,
+    /// when the browser accepts one of them. Defaults to `false`, which leaves compression
+    /// to a reverse proxy if there is one. The stream is flushed after each chunk, so that
+    /// `<Suspense/>` fragments still arrive as soon as they are rendered.
+    #[builder(default)]
+    #[serde(default)]
+    pub compression: bool,
 }
 
 impl LeptosOptions {
@@ -63,6 +95,11 @@ impl LeptosOptions {
                 .parse()?,
             reload_port: env_w_default("LEPTOS_RELOAD_PORT", "3001")?
                 .parse()?,
+            asset_manifest: std::env::var("LEPTOS_ASSET_MANIFEST").ok(),
+            html_shell: std::env::var("LEPTOS_HTML_SHELL").ok(),
+            csp_header: env_w_default("LEPTOS_CSP_HEADER", "false")?.parse()?,
+            compression: env_w_default("LEPTOS_COMPRESSION", "false")?
+                .parse()?,
         })
     }
 }
@@ -139,8 +176,19 @@ impl TryFrom<String> for Env {
 /// you'll need to set the options as environment variables or rely on the defaults. This is the preferred
 /// approach for cargo-leptos. If Some("./Cargo.toml") is provided, Leptos will read in the settings itself. This
 /// option currently does not allow dashes in file or foldernames, as all dashes become underscores
+///
+/// The [AssetManifest] given by [LeptosOptions::asset_manifest] is loaded too, so that a
+/// missing or malformed manifest is reported here, when the server starts.
 pub async fn get_configuration(
     path: Option<&str>,
+) -> Result<ConfFile, LeptosConfigError> {
+    let conf = read_configuration(path)?;
+    conf.leptos_options.load_asset_manifest()?;
+    Ok(conf)
+}
+
+fn read_configuration(
+    path: Option<&str>,
 ) -> Result<ConfFile, LeptosConfigError> {
     if let Some(path) = path {
         let text = fs::read_to_string(path)
@@ -0,0 +1,112 @@
+use crate::{errors::LeptosConfigError, LeptosOptions};
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+/// The `Cache-Control` value for files whose name includes a hash of their contents.
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Maps the logical paths of static assets, like `pkg/app.js`, to the fingerprinted
+/// files generated by your build tool, like `pkg/app.3f9a2c.js`.
+///
+/// The manifest is a JSON object with a key for each logical path, relative to the
+/// site root. It is loaded from the file given by [LeptosOptions::asset_manifest], and the
+/// integrations use it to link to the fingerprinted JS and WASM bundle and provide it
+/// as context, so that components like `leptos_meta::Stylesheet` can link to
+/// fingerprinted files too.
+///
+/// ```
+/// # use leptos_config::AssetManifest;
+/// let manifest = AssetManifest::from_json(
+///     r#"{ "style/main.css": "style/main.8d1e.css" }"#,
+/// )
+/// .unwrap();
+/// assert_eq!(manifest.resolve("/style/main.css"), "/style/main.8d1e.css");
+/// assert_eq!(manifest.resolve("/favicon.ico"), "/favicon.ico");
+/// assert!(manifest.is_fingerprinted("/style/main.8d1e.css"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(from = "HashMap<String, String>")]
+pub struct AssetManifest {
+    assets: Arc<HashMap<String, String>>,
+}
+
+impl From<HashMap<String, String>> for AssetManifest {
+    fn from(assets: HashMap<String, String>) -> Self {
+        Self {
+            assets: Arc::new(
+                assets
+                    .into_iter()
+                    .map(|(path, file)| {
+                        (
+                            path.trim_start_matches('/').to_string(),
+                            file.trim_start_matches('/').to_string(),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl AssetManifest {
+    /// Parses a manifest from a JSON object.
+    pub fn from_json(json: &str) -> Result<Self, LeptosConfigError> {
+        serde_json::from_str(json)
+            .map_err(|e| LeptosConfigError::AssetManifestError(e.to_string()))
+    }
+
+    /// Reads and parses the manifest file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LeptosConfigError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            LeptosConfigError::AssetManifestError(format!(
+                "{}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Returns the path of the fingerprinted file for the asset at `path`, or `path`
+    /// itself if the asset isn't in the manifest. A leading `/` is kept.
+    pub fn resolve(&self, path: &str) -> String {
+        let relative = path.trim_start_matches('/');
+        match self.assets.get(relative) {
+            Some(file) => {
+                format!("{}{file}", &path[..path.len() - relative.len()])
+            }
+            None => path.to_string(),
+        }
+    }
+
+    /// Whether `path` is one of the fingerprinted files in the manifest, which can be
+    /// cached forever because its name changes whenever its contents do.
+    pub fn is_fingerprinted(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/');
+        self.assets.values().any(|file| file == path)
+    }
+
+    /// The `Cache-Control` header that should be sent with the static file at `path`:
+    /// [IMMUTABLE_CACHE_CONTROL] for fingerprinted files, and `no-cache` for anything
+    /// else, so that it is revalidated in case it has changed.
+    pub fn cache_control(&self, path: &str) -> &'static str {
+        if self.is_fingerprinted(path) {
+            IMMUTABLE_CACHE_CONTROL
+        } else {
+            "no-cache"
+        }
+    }
+}
+
+impl LeptosOptions {
+    /// Loads the [AssetManifest] given by [LeptosOptions::asset_manifest], if there is one.
+    pub fn load_asset_manifest(
+        &self,
+    ) -> Result<Option<AssetManifest>, LeptosConfigError> {
+        self.asset_manifest
+            .as_ref()
+            .map(|path| {
+                AssetManifest::load(Path::new(&self.site_root).join(path))
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{errors::LeptosConfigError, LeptosOptions};
+use std::{path::Path, sync::Arc};
+
+/// The template the built-in [HtmlShell] is made from.
+pub const DEFAULT_HTML_SHELL: &str = r#"<!DOCTYPE html>
+<html{html_attributes}>
+    <head>
+        <meta charset="utf-8"/>
+        <meta name="viewport" content="width=device-width, initial-scale=1"/>
+        {hydration}
+        {head}
+    </head>
+    <body{body_attributes}>{app}</body>
+</html>"#;
+
+/// The parts of a page that the server integrations fill into the [HtmlShell] around your
+/// app.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShellParts<'a> {
+    /// The attributes of the `<html>` tag set with `leptos_meta::Html`, each with a
+    /// leading space.
+    pub html_attributes: &'a str,
+    /// The tags for the `<head>` from `leptos_meta`, like the `<title>` and stylesheets.
+    pub head: &'a str,
+    /// The tags that load the JS and WASM bundle and hydrate the app, and, while
+    /// `cargo-leptos` is watching for changes, the script that reloads the page.
+    pub hydration: &'a str,
+    /// The attributes of the `<body>` tag set with `leptos_meta::Body`, each with a
+    /// leading space.
+    pub body_attributes: &'a str,
+}
+
+/// The HTML document the server integrations render your app into.
+///
+/// A shell is usually made from a template, in which `{html_attributes}`, `{head}`,
+/// `{hydration}` and `{body_attributes}` are replaced by the [ShellParts] of each page, and
+/// `{app}` by the HTML of your app. Anything else, including other text in braces, is sent
+/// as it is, which makes it a good place for analytics, service worker registration, or a
+/// loading indicator:
+/// ```
+/// # use leptos_config::*;
+/// let shell = HtmlShell::from_template(
+///     r#"<!DOCTYPE html>
+/// <html{html_attributes}>
+///     <head>{hydration}{head}</head>
+///     <body{body_attributes}>{app}<script src="/analytics.js"></script></body>
+/// </html>"#,
+/// )
+/// .unwrap();
+/// let (before, after) = shell.render(&ShellParts {
+///     head: "<title>Home</title>",
+///     ..Default::default()
+/// });
+/// assert!(before.ends_with("<head><title>Home</title></head>\n    <body>"));
+/// assert!(after.starts_with(r#"<script src="/analytics.js">"#));
+/// ```
+///
+/// The integrations load the template from [LeptosOptions::html_shell], or use
+/// [DEFAULT_HTML_SHELL] if it isn't set. An `HtmlShell` provided as context takes
+/// precedence, which also lets you build the page with a function instead, using
+/// [HtmlShell::from_fn].
+#[derive(Clone)]
+pub struct HtmlShell(Arc<ShellFn>);
+
+type ShellFn = dyn Fn(&ShellParts) -> (String, String) + Send + Sync;
+
+impl std::fmt::Debug for HtmlShell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HtmlShell").finish()
+    }
+}
+
+impl Default for HtmlShell {
+    fn default() -> Self {
+        Self::from_template(DEFAULT_HTML_SHELL)
+            .expect("the default shell has an {app} placeholder")
+    }
+}
+
+impl HtmlShell {
+    /// Parses a template, which must contain the `{app}` placeholder exactly once.
+    pub fn from_template(template: &str) -> Result<Self, LeptosConfigError> {
+        let (before, after) = match template.split_once("{app}") {
+            Some((before, after)) if !after.contains("{app}") => {
+                (before.to_string(), after.to_string())
+            }
+            _ => {
+                return Err(LeptosConfigError::HtmlShellError(
+                    "the template must contain the {app} placeholder exactly \
+                     once"
+                        .to_string(),
+                ))
+            }
+        };
+        Ok(Self::from_fn(move |parts| {
+            (fill(&before, parts), fill(&after, parts))
+        }))
+    }
+
+    /// Reads and parses the template file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LeptosConfigError> {
+        let path = path.as_ref();
+        let template = std::fs::read_to_string(path).map_err(|e| {
+            LeptosConfigError::HtmlShellError(format!(
+                "{}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_template(&template).map_err(|e| match e {
+            LeptosConfigError::HtmlShellError(e) => {
+                LeptosConfigError::HtmlShellError(format!(
+                    "{}: {e}",
+                    path.display()
+                ))
+            }
+            e => e,
+        })
+    }
+
+    /// Builds the page with a function, which returns the HTML that comes before and after
+    /// the app.
+    pub fn from_fn(
+        render: impl Fn(&ShellParts) -> (String, String) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(render))
+    }
+
+    /// The HTML that comes before and after the app on a page with the given parts.
+    pub fn render(&self, parts: &ShellParts) -> (String, String) {
+        (self.0)(parts)
+    }
+}
+
+/// Replaces the placeholders in a piece of a template, in one pass, so that placeholders
+/// inside the parts themselves are left alone.
+fn fill(template: &str, parts: &ShellParts) -> String {
+    let mut html = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        html.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let (placeholder, value) = match rest.find('}').map(|end| &rest[..=end])
+        {
+            Some(p @ "{html_attributes}") => (p, parts.html_attributes),
+            Some(p @ "{head}") => (p, parts.head),
+            Some(p @ "{hydration}") => (p, parts.hydration),
+            Some(p @ "{body_attributes}") => (p, parts.body_attributes),
+            _ => ("{", "{"),
+        };
+        html.push_str(value);
+        rest = &rest[placeholder.len()..];
+    }
+    html.push_str(rest);
+    html
+}
+
+impl LeptosOptions {
+    /// Loads the [HtmlShell] from the template given by [LeptosOptions::html_shell], or the
+    /// default shell if there is none.
+    pub fn load_html_shell(&self) -> Result<HtmlShell, LeptosConfigError> {
+        match &self.html_shell {
+            Some(path) => HtmlShell::load(path),
+            None => Ok(HtmlShell::default()),
+        }
+    }
+}
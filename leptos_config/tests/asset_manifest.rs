@@ -0,0 +1,38 @@
+use leptos_config::{errors::LeptosConfigError, get_configuration};
+use std::{env, fs};
+
+#[test]
+fn configuration_reports_a_bad_asset_manifest() {
+    let site_root = env::temp_dir()
+        .join(format!("leptos_config_manifest_{}", std::process::id()));
+    fs::create_dir_all(&site_root).unwrap();
+    env::set_var("LEPTOS_OUTPUT_NAME", "app");
+    env::set_var("LEPTOS_SITE_ROOT", &site_root);
+
+    env::set_var("LEPTOS_ASSET_MANIFEST", "missing.json");
+    let missing = futures::executor::block_on(get_configuration(None));
+    assert!(matches!(
+        missing,
+        Err(LeptosConfigError::AssetManifestError(_))
+    ));
+
+    fs::write(site_root.join("malformed.json"), "[1, 2]").unwrap();
+    env::set_var("LEPTOS_ASSET_MANIFEST", "malformed.json");
+    let malformed = futures::executor::block_on(get_configuration(None));
+    assert!(matches!(
+        malformed,
+        Err(LeptosConfigError::AssetManifestError(_))
+    ));
+
+    fs::write(
+        site_root.join("manifest.json"),
+        r#"{ "pkg/app.js": "pkg/app.3f9a2c.js" }"#,
+    )
+    .unwrap();
+    env::set_var("LEPTOS_ASSET_MANIFEST", "manifest.json");
+    let conf = futures::executor::block_on(get_configuration(None)).unwrap();
+    let manifest = conf.leptos_options.load_asset_manifest().unwrap().unwrap();
+    assert_eq!(manifest.resolve("/pkg/app.js"), "/pkg/app.3f9a2c.js");
+
+    fs::remove_dir_all(&site_root).unwrap();
+}
@@ -0,0 +1,108 @@
+use leptos_config::{
+    errors::LeptosConfigError, get_configuration, HtmlShell, ShellParts,
+    DEFAULT_HTML_SHELL,
+};
+use std::{env, fs};
+
+fn is_shell_error(result: Result<HtmlShell, LeptosConfigError>) -> bool {
+    matches!(result, Err(LeptosConfigError::HtmlShellError(_)))
+}
+
+#[test]
+fn templates_need_exactly_one_app_placeholder() {
+    assert!(is_shell_error(HtmlShell::from_template("<body></body>")));
+    assert!(is_shell_error(HtmlShell::from_template(
+        "<body>{app}{app}</body>"
+    )));
+    assert!(HtmlShell::from_template("<body>{app}</body>").is_ok());
+}
+
+#[test]
+fn placeholders_are_filled_once() {
+    let shell = HtmlShell::from_template(
+        "<html{html_attributes}><head>{hydration}{head}</\
+         head><body{body_attributes}>{app}<p>{not_a_placeholder}</p>{head}</\
+         body>",
+    )
+    .unwrap();
+    let (before, after) = shell.render(&ShellParts {
+        html_attributes: r#" lang="en""#,
+        head: "<title>{hydration}</title>",
+        hydration: "<script></script>",
+        body_attributes: r#" class="dark""#,
+    });
+    assert_eq!(
+        before,
+        r#"<html lang="en"><head><script></script><title>{hydration}</title></head><body class="dark">"#
+    );
+    assert_eq!(
+        after,
+        "<p>{not_a_placeholder}</p><title>{hydration}</title></body>"
+    );
+}
+
+#[test]
+fn loading_reports_the_path() {
+    let dir = env::temp_dir()
+        .join(format!("leptos_config_shell_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let missing = dir.join("missing.html");
+    match HtmlShell::load(&missing) {
+        Err(LeptosConfigError::HtmlShellError(e)) => {
+            assert!(e.starts_with(&missing.display().to_string()))
+        }
+        other => panic!("expected an HtmlShellError, got {other:?}"),
+    }
+
+    let no_app = dir.join("no_app.html");
+    fs::write(&no_app, "<body></body>").unwrap();
+    match HtmlShell::load(&no_app) {
+        Err(LeptosConfigError::HtmlShellError(e)) => {
+            assert!(e.starts_with(&no_app.display().to_string()));
+            assert!(e.contains("{app}"));
+        }
+        other => panic!("expected an HtmlShellError, got {other:?}"),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn configuration_loads_the_shell_from_the_environment() {
+    let dir = env::temp_dir()
+        .join(format!("leptos_config_env_shell_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    env::set_var("LEPTOS_OUTPUT_NAME", "app");
+
+    env::remove_var("LEPTOS_HTML_SHELL");
+    let conf = futures::executor::block_on(get_configuration(None)).unwrap();
+    assert_eq!(conf.leptos_options.html_shell, None);
+    let default = conf.leptos_options.load_html_shell().unwrap();
+    let expected = HtmlShell::from_template(DEFAULT_HTML_SHELL).unwrap();
+    assert_eq!(
+        default.render(&ShellParts::default()),
+        expected.render(&ShellParts::default())
+    );
+
+    let template = dir.join("shell.html");
+    fs::write(&template, "<main>{app}</main>").unwrap();
+    env::set_var("LEPTOS_HTML_SHELL", &template);
+    let conf = futures::executor::block_on(get_configuration(None)).unwrap();
+    assert_eq!(
+        conf.leptos_options.html_shell.as_deref(),
+        Some(template.to_str().unwrap())
+    );
+    let shell = conf.leptos_options.load_html_shell().unwrap();
+    assert_eq!(
+        shell.render(&ShellParts::default()),
+        ("<main>".to_string(), "</main>".to_string())
+    );
+
+    env::set_var("LEPTOS_HTML_SHELL", dir.join("missing.html"));
+    let conf = futures::executor::block_on(get_configuration(None)).unwrap();
+    assert!(is_shell_error(conf.leptos_options.load_html_shell()));
+
+    env::remove_var("LEPTOS_HTML_SHELL");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -4,10 +4,98 @@
 
 use crate::{CoreComponent, HydrationCtx, IntoView, View};
 use cfg_if::cfg_if;
-use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use futures::{stream::FuturesUnordered, Future, FutureExt, Stream, StreamExt};
 use itertools::Itertools;
 use leptos_reactive::*;
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap, pin::Pin, rc::Rc};
+use tracing::Instrument;
+
+/// Generates additional HTML that is streamed after each `<Suspense/>` fragment,
+/// when provided as context to an app that is rendered to a stream.
+///
+/// This allows parts of the page outside the fragment, like the document `<head>`,
+/// to be updated with anything that was set while the fragment was rendering,
+/// usually by a `<script>`.
+#[derive(Clone)]
+pub struct FragmentSuffix(Rc<dyn Fn() -> String>);
+
+impl FragmentSuffix {
+    /// Creates a suffix that runs `f` each time a fragment has been rendered.
+    pub fn new(f: impl Fn() -> String + 'static) -> Self {
+        Self(Rc::new(f))
+    }
+}
+
+impl std::fmt::Debug for FragmentSuffix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FragmentSuffix").finish()
+    }
+}
+
+/// A random, single-use value that allows the inline `<script>` and `<style>` elements
+/// in a server-rendered response to run under a
+/// [Content Security Policy](https://developer.mozilla.org/en-US/docs/Web/HTTP/CSP)
+/// that doesn't allow `'unsafe-inline'`.
+///
+/// When a `Nonce` is provided as context, it is added to every inline script
+/// the streaming renderer emits. The server integrations generate a new one
+/// for every request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Nonce(Rc<str>);
+
+impl Nonce {
+    /// Generates a new nonce from 128 random bits.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Self {
+        let mut bytes = [0; 16];
+        getrandom::getrandom(&mut bytes)
+            .expect("couldn't generate a random nonce");
+        let hex = bytes.iter().map(|byte| format!("{byte:02x}")).join("");
+        Self(hex.into())
+    }
+
+    /// The value of the nonce.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The nonce as an attribute (` nonce="…"`) that can be added to an HTML tag.
+    pub fn attr(&self) -> String {
+        format!(" nonce=\"{}\"", self.0)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for Nonce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for Nonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Returns the [Nonce] for the current response, if one has been provided.
+pub fn use_nonce(cx: Scope) -> Option<Nonce> {
+    use_context::<Nonce>(cx)
+}
+
+/// How the parts of a page under a `<Suspense/>` are streamed by the server integrations.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SsrMode {
+    /// Sends the whole page with the `fallback` of each `<Suspense/>` first, then the HTML
+    /// for each `<Suspense/>` as it resolves, along with a script that swaps it in. See
+    /// [render_to_stream].
+    #[default]
+    OutOfOrder,
+    /// Sends the page in document order, holding the stream at each `<Suspense/>` until
+    /// it resolves, so the page is complete without running any JavaScript. See
+    /// [render_to_stream_in_order].
+    InOrder,
+}
 
 /// Renders the given function to a static HTML string.
 ///
@@ -116,7 +204,8 @@ pub fn render_to_stream_with_prefix_undisposed(
 ///    server and are sent down to the browser to resolve. On the browser, if the app sees that
 ///    it is waiting for a resource to resolve from the server, it doesn't run it initially.
 /// 4) HTML fragments to replace each `<Suspense/>` fallback with its actual data as the resources
-///    read under that `<Suspense/>` resolve.
+///    read under that `<Suspense/>` resolve, each followed by the [FragmentSuffix] provided
+///    as context, if any.
 pub fn render_to_stream_with_prefix_undisposed_with_context(
     view: impl FnOnce(Scope) -> View + 'static,
     prefix: impl FnOnce(Scope) -> Cow<'static, str> + 'static,
@@ -128,11 +217,19 @@ pub fn render_to_stream_with_prefix_undisposed_with_context(
     let runtime = create_runtime();
 
     let (
-        (shell, prefix, pending_resources, pending_fragments, serializers),
+        (
+            shell,
+            prefix,
+            pending_resources,
+            pending_fragments,
+            serializers,
+            suffix,
+            nonce,
+        ),
         scope,
         _,
-    ) = run_scope_undisposed(runtime, {
-        move |cx| {
+    ) = render_shell(|| {
+        run_scope_undisposed(runtime, move |cx| {
             // Add additional context items
             additional_context(cx);
             // the actual app body/template code
@@ -149,23 +246,32 @@ pub fn render_to_stream_with_prefix_undisposed_with_context(
                 pending_resources,
                 cx.pending_fragments(),
                 cx.serialization_resolvers(),
+                use_context::<FragmentSuffix>(cx),
+                use_nonce(cx).map(|nonce| nonce.attr()).unwrap_or_default(),
             )
-        }
+        })
     });
 
     let fragments = FuturesUnordered::new();
     for (fragment_id, (key_before, fut)) in pending_fragments {
+        let fut = instrument_fragment(&fragment_id, fut);
         fragments.push(async move { (fragment_id, key_before, fut.await) })
     }
 
     // resources and fragments
     // stream HTML for each <Suspense/> as it resolves
     // TODO can remove id_before_suspense entirely now
-    let fragments = fragments.map(|(fragment_id, _, html)| {
-    format!(
-      r#"
+    let fragments = fragments.map({
+        let nonce = nonce.clone();
+        move |(fragment_id, _, html)| {
+            let suffix = suffix
+                .as_ref()
+                .map(|suffix| (suffix.0)())
+                .unwrap_or_default();
+            format!(
+                r#"
               <template id="{fragment_id}f">{html}</template>
-              <script>
+              <script{nonce}>
                   var id = "{fragment_id}";
                   var open;
                   var close;
@@ -184,34 +290,26 @@ pub fn render_to_stream_with_prefix_undisposed_with_context(
                   var tpl = document.getElementById("{fragment_id}f");
                   close.parentNode.insertBefore(tpl.content.cloneNode(true), close);
               </script>
+              {suffix}
               "#
-    )
-  });
+            )
+        }
+    });
     // stream data for each Resource as it resolves
-    let resources = serializers.map(|(id, json)| {
-        let id = serde_json::to_string(&id).unwrap();
-        format!(
-            r#"<script>
-                  if(__LEPTOS_RESOURCE_RESOLVERS.get({id})) {{
-                      __LEPTOS_RESOURCE_RESOLVERS.get({id})({json:?})
-                  }} else {{
-                      __LEPTOS_RESOLVED_RESOURCES.set({id}, {json:?});
-                  }}
-              </script>"#,
-        )
+    let resources = serializers.map({
+        let nonce = nonce.clone();
+        move |(id, json)| resource_script(&nonce, id, json)
     });
 
     // HTML for the view function and script to store resources
     let stream = futures::stream::once(async move {
+        let resources_script =
+            pending_resources_script(&nonce, &pending_resources);
         format!(
             r#"
               {prefix}
               {shell}
-              <script>
-                  __LEPTOS_PENDING_RESOURCES = {pending_resources};
-                  __LEPTOS_RESOLVED_RESOURCES = new Map();
-                  __LEPTOS_RESOURCE_RESOLVERS = new Map();
-              </script>
+              {resources_script}
           "#
         )
     })
@@ -223,6 +321,388 @@ pub fn render_to_stream_with_prefix_undisposed_with_context(
     (stream, runtime, scope)
 }
 
+/// Renders a function to a stream of HTML strings, in document order.
+///
+/// Unlike [render_to_stream], which sends the `fallback` of each `<Suspense/>` and
+/// then a script that swaps in its content once it resolves, this holds the stream
+/// at each `<Suspense/>` until its resources have resolved, and writes its content
+/// in place. Everything before the first pending `<Suspense/>` is still sent
+/// immediately, but the page is complete without running any JavaScript.
+pub fn render_to_stream_in_order(
+    view: impl FnOnce(Scope) -> View + 'static,
+) -> impl Stream<Item = String> {
+    let (stream, runtime, _) =
+        render_to_stream_in_order_with_prefix_undisposed_with_context(
+            view,
+            |_| "".into(),
+            |_| {},
+        );
+    // resources need the runtime until they have all been sent
+    stream.chain(
+        futures::stream::once(async move { runtime.dispose() })
+            .filter_map(|_| async { None }),
+    )
+}
+
+/// Renders a function to a stream of HTML strings, in document order, and returns the
+/// [Scope] and [RuntimeId] that were created, so they can be disposed when appropriate.
+/// After the `view` runs, the `prefix` will run with the same scope.
+///
+/// This renders:
+/// 1) the prefix
+/// 2) JavaScript necessary to receive streaming [Resource](leptos_reactive::Resource) data
+/// 3) the application’s HTML, up to the first `<Suspense/>` that has not resolved yet
+/// 4) once the resources read under that `<Suspense/>` have resolved, its HTML, followed by
+///    the [FragmentSuffix] provided as context, if any, and the application’s HTML up to
+///    the next pending `<Suspense/>`, and so on
+/// 5) streaming [Resource](leptos_reactive::Resource) data, as each resource resolves.
+pub fn render_to_stream_in_order_with_prefix_undisposed_with_context(
+    view: impl FnOnce(Scope) -> View + 'static,
+    prefix: impl FnOnce(Scope) -> Cow<'static, str> + 'static,
+    additional_context: impl FnOnce(Scope) + 'static,
+) -> (impl Stream<Item = String>, RuntimeId, ScopeId) {
+    HydrationCtx::reset_id();
+
+    let runtime = create_runtime();
+
+    let (
+        (
+            shell,
+            prefix,
+            pending_resources,
+            pending_fragments,
+            serializers,
+            suffix,
+            nonce,
+        ),
+        scope,
+        _,
+    ) = render_shell(|| {
+        run_scope_undisposed(runtime, move |cx| {
+            additional_context(cx);
+            let shell = view(cx).render_to_string(cx);
+
+            let resources = cx.pending_resources();
+            let pending_resources = serde_json::to_string(&resources).unwrap();
+            let prefix = prefix(cx);
+
+            (
+                shell,
+                prefix,
+                pending_resources,
+                cx.pending_fragments(),
+                cx.serialization_resolvers(),
+                use_context::<FragmentSuffix>(cx),
+                use_nonce(cx).map(|nonce| nonce.attr()).unwrap_or_default(),
+            )
+        })
+    });
+
+    let resources_script = pending_resources_script(&nonce, &pending_resources);
+    let head = format!("{prefix}{resources_script}");
+
+    let html = InOrderHtml::new(
+        Scope { runtime, id: scope },
+        shell.into_owned(),
+        pending_fragments,
+        suffix,
+    );
+
+    let stream = futures::stream::once(async move { head }).chain(
+        futures::stream::unfold(Some((html, serializers)), move |state| {
+            let nonce = nonce.clone();
+            async move {
+                let (mut html, mut serializers) = state?;
+                let mut chunk = String::new();
+                match html.next_chunk().await {
+                    Some(next) => {
+                        // data for the resources that have resolved while waiting
+                        while let Some(Some((id, json))) =
+                            serializers.next().now_or_never()
+                        {
+                            chunk.push_str(&resource_script(&nonce, id, json));
+                        }
+                        chunk.push_str(&next);
+                        Some((chunk, Some((html, serializers))))
+                    }
+                    None => {
+                        let resources = serializers.collect::<Vec<_>>().await;
+                        for (id, json) in resources {
+                            chunk.push_str(&resource_script(&nonce, id, json));
+                        }
+                        Some((chunk, None))
+                    }
+                }
+            }
+        }),
+    );
+
+    (stream, runtime, scope)
+}
+
+/// Renders a function to HTML, waiting until every [Resource](leptos_reactive::Resource)
+/// and `<Suspense/>` has resolved, so that the HTML is complete.
+///
+/// Returns the HTML and the serialized value of each resource, which can be used to
+/// render static pages, emails, or snapshots for tests.
+///
+/// This needs to run in an async runtime that can run the resources’ futures: when
+/// rendering on the server with Tokio, a [LocalSet](https://docs.rs/tokio/latest/tokio/task/struct.LocalSet.html).
+pub async fn render_to_string_async(
+    view: impl FnOnce(Scope) -> View + 'static,
+) -> (String, HashMap<ResourceId, String>) {
+    render_to_string_async_with_context(view, |_| {}).await
+}
+
+/// Renders a function to HTML, waiting until every [Resource](leptos_reactive::Resource)
+/// and `<Suspense/>` has resolved, after running `additional_context` in the same scope.
+/// Otherwise, this is the same as [render_to_string_async].
+pub async fn render_to_string_async_with_context(
+    view: impl FnOnce(Scope) -> View + 'static,
+    additional_context: impl FnOnce(Scope) + 'static,
+) -> (String, HashMap<ResourceId, String>) {
+    let (html, resources, runtime, _) =
+        render_to_string_async_undisposed_with_context(
+            view,
+            additional_context,
+        )
+        .await;
+    runtime.dispose();
+    (html, resources)
+}
+
+/// Renders a function to HTML, waiting until every [Resource](leptos_reactive::Resource)
+/// and `<Suspense/>` has resolved, and returns the [Scope] and [RuntimeId] that were
+/// created, so they can be disposed when appropriate. This can be used to read context,
+/// like the metadata for the document `<head>`, once the whole page has rendered.
+/// Otherwise, this is the same as [render_to_string_async_with_context].
+pub async fn render_to_string_async_undisposed_with_context(
+    view: impl FnOnce(Scope) -> View + 'static,
+    additional_context: impl FnOnce(Scope) + 'static,
+) -> (String, HashMap<ResourceId, String>, RuntimeId, ScopeId) {
+    HydrationCtx::reset_id();
+
+    let runtime = create_runtime();
+
+    let ((shell, pending_fragments, serializers), scope, _) =
+        render_shell(|| {
+            run_scope_undisposed(runtime, move |cx| {
+                additional_context(cx);
+                let shell = view(cx).render_to_string(cx);
+                (shell, cx.pending_fragments(), cx.serialization_resolvers())
+            })
+        });
+
+    let mut html = InOrderHtml::new(
+        Scope { runtime, id: scope },
+        shell.into_owned(),
+        pending_fragments,
+        None,
+    );
+    let mut rendered = String::new();
+    while let Some(chunk) = html.next_chunk().await {
+        rendered.push_str(&chunk);
+    }
+    let resources = serializers.collect().await;
+
+    (rendered, resources, runtime, scope)
+}
+
+/// A `<script>` that gives the browser the values of resources that have already
+/// resolved, like those returned by [render_to_string_async], so that an app
+/// hydrating a pre-rendered page doesn't load them again.
+pub fn resolved_resources_script(
+    nonce: Option<&Nonce>,
+    resources: &HashMap<ResourceId, String>,
+) -> String {
+    let nonce = nonce.map(Nonce::attr).unwrap_or_default();
+    let ids =
+        serde_json::to_string(&resources.keys().collect::<Vec<_>>()).unwrap();
+    let resolved = resources
+        .iter()
+        .map(|(id, json)| {
+            let id = serde_json::to_string(id).unwrap();
+            let json = script_string(json);
+            format!("[{id}, {json}]")
+        })
+        .join(", ");
+    format!(
+        r#"<script{nonce}>
+                  __LEPTOS_PENDING_RESOURCES = {ids};
+                  __LEPTOS_RESOLVED_RESOURCES = new Map([{resolved}]);
+                  __LEPTOS_RESOURCE_RESOLVERS = new Map();
+              </script>"#
+    )
+}
+
+type PinnedFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// Renders the synchronous part of the app in a `render_shell` span, so that the
+/// resources it starts loading are traced as part of it.
+fn render_shell<T>(render: impl FnOnce() -> T) -> T {
+    tracing::info_span!("render_shell").in_scope(render)
+}
+
+/// Traces the time from the end of the shell until a `<Suspense/>` fragment resolves.
+fn instrument_fragment(
+    id: &str,
+    fragment: PinnedFuture<String>,
+) -> PinnedFuture<String> {
+    Box::pin(fragment.instrument(tracing::info_span!("fragment", id = %id)))
+}
+
+/// The HTML of a page that is rendered in order, as far as it has been rendered.
+struct InOrderHtml {
+    cx: Scope,
+    /// HTML that has not been returned yet.
+    html: String,
+    /// The `<Suspense/>` fragments that have not been reached yet, by ID.
+    fragments: HashMap<String, PinnedFuture<String>>,
+    /// The `<Suspense/>` the HTML is being held at: its ID and its HTML, once resolved.
+    waiting: Option<(String, PinnedFuture<String>)>,
+    suffix: Option<FragmentSuffix>,
+}
+
+impl InOrderHtml {
+    fn new(
+        cx: Scope,
+        html: String,
+        pending_fragments: HashMap<String, (String, PinnedFuture<String>)>,
+        suffix: Option<FragmentSuffix>,
+    ) -> Self {
+        Self {
+            cx,
+            html,
+            fragments: pending_fragments
+                .into_iter()
+                .map(|(id, (_, fut))| {
+                    let fut = instrument_fragment(&id, fut);
+                    (id, fut)
+                })
+                .collect(),
+            waiting: None,
+            suffix,
+        }
+    }
+
+    /// Returns the HTML up to the next pending `<Suspense/>`, after waiting for the
+    /// previous one to resolve, or `None` once the page is complete.
+    async fn next_chunk(&mut self) -> Option<String> {
+        if let Some((id, fragment)) = self.waiting.take() {
+            let html = fragment.await;
+            // any <Suspense/> inside this one was only reached now
+            self.fragments
+                .extend(self.cx.pending_fragments().into_iter().map(
+                    |(id, (_, fut))| {
+                        let fut = instrument_fragment(&id, fut);
+                        (id, fut)
+                    },
+                ));
+            let suffix = self
+                .suffix
+                .as_ref()
+                .map(|suffix| (suffix.0)())
+                .unwrap_or_default();
+            self.html = format!(
+                "<!--suspense-open-{id}-->{html}<!\
+                 --suspense-close-{id}-->{suffix}{}",
+                self.html
+            );
+        } else if self.html.is_empty() {
+            return None;
+        }
+
+        match self.next_pending_fragment() {
+            Some((id, open_start, close_end)) => {
+                // return everything up to the <Suspense/>, and drop its fallback
+                let chunk = self.html[..open_start].to_string();
+                self.html.replace_range(..close_end, "");
+                let fragment = self.fragments.remove(&id).unwrap();
+                self.waiting = Some((id, fragment));
+                Some(chunk)
+            }
+            None => Some(std::mem::take(&mut self.html)),
+        }
+    }
+
+    /// Finds the first `<Suspense/>` in the unsent HTML that is still pending, returning
+    /// its ID, the start of its opening marker and the end of its closing marker.
+    fn next_pending_fragment(&self) -> Option<(String, usize, usize)> {
+        const OPEN: &str = "<!--suspense-open-";
+        let mut offset = 0;
+        while let Some(start) = self.html[offset..].find(OPEN) {
+            let id_start = offset + start + OPEN.len();
+            let id_end = id_start + self.html[id_start..].find("-->")?;
+            let id = &self.html[id_start..id_end];
+            let open_end = id_end + "-->".len();
+            if self.fragments.contains_key(id) {
+                let close = format!("<!--suspense-close-{id}-->");
+                let close_start =
+                    open_end + self.html[open_end..].find(&close)?;
+                return Some((
+                    id.to_string(),
+                    offset + start,
+                    close_start + close.len(),
+                ));
+            }
+            offset = open_end;
+        }
+        None
+    }
+}
+
+/// Sets up the browser to receive the data for each pending [Resource](leptos_reactive::Resource).
+fn pending_resources_script(nonce: &str, pending_resources: &str) -> String {
+    format!(
+        r#"<script{nonce}>
+                  __LEPTOS_PENDING_RESOURCES = {pending_resources};
+                  __LEPTOS_RESOLVED_RESOURCES = new Map();
+                  __LEPTOS_RESOURCE_RESOLVERS = new Map();
+              </script>"#
+    )
+}
+
+/// Sends the data for a [Resource](leptos_reactive::Resource) that has resolved to the browser.
+fn resource_script(nonce: &str, id: ResourceId, json: String) -> String {
+    let id = serde_json::to_string(&id).unwrap();
+    let json = script_string(&json);
+    format!(
+        r#"<script{nonce}>
+                  if(__LEPTOS_RESOURCE_RESOLVERS.get({id})) {{
+                      __LEPTOS_RESOURCE_RESOLVERS.get({id})({json})
+                  }} else {{
+                      __LEPTOS_RESOLVED_RESOURCES.set({id}, {json});
+                  }}
+              </script>"#,
+    )
+}
+
+/// Quotes the serialized value of a resource as a JavaScript string literal that is
+/// safe to write into a `<script>` element.
+fn script_string(json: &str) -> String {
+    escape_script(&serde_json::to_string(json).unwrap())
+}
+
+/// Escapes the characters in JavaScript or JSON source that could end the `<script>`
+/// element it is written into, or be read as HTML, so that a value like
+/// `"</script>"` stays data. The `\u` escapes it uses parse back to the same value,
+/// so this should only be used on code in which `<`, `>` and `&` appear inside
+/// string literals.
+pub fn escape_script(code: &str) -> String {
+    let mut escaped = String::with_capacity(code.len());
+    for c in code.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl View {
     /// Consumes the node and renders it into an HTML string.
     pub fn render_to_string(self, _cx: Scope) -> Cow<'static, str> {
@@ -5,7 +5,7 @@ use convert_case::{
 use itertools::Itertools;
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::ResultExt;
-use quote::{format_ident, ToTokens, TokenStreamExt};
+use quote::{format_ident, quote, ToTokens, TokenStreamExt};
 use std::collections::HashSet;
 use syn::{
     parse::Parse, parse_quote, AngleBracketedGenericArguments, Attribute,
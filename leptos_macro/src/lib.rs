@@ -199,6 +199,22 @@ mod server;
 /// # });
 /// ```
 ///
+/// The `scoped_style` attribute attaches CSS to an element with
+/// [`leptos_meta::use_scoped_style`](https://docs.rs/leptos_meta/latest/leptos_meta/fn.use_scoped_style.html),
+/// and adds the class it returns, so its selectors only match that element and its
+/// descendants. This requires `leptos_meta` as a dependency.
+/// ```ignore
+/// # use leptos::*;
+/// # run_scope(create_runtime(), |cx| {
+/// view! { cx,
+///   <section scoped_style=":scope { padding: 1rem; } h2 { color: gray; }">
+///     <h2>"Only this heading is gray."</h2>
+///   </section>
+/// }
+/// # ;
+/// # });
+/// ```
+///
 /// 8. You can use the `node_ref` or `_ref` attribute to store a reference to its DOM element in a
 ///    [NodeRef](leptos_dom::NodeRef) to use later.
 /// ```rust
@@ -622,7 +638,7 @@ pub fn component(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
 /// ```
 ///
 /// Note the following:
-/// - You must **register** the server function by calling `T::register()` somewhere in your main function.
+/// - The server function is **registered** automatically on the server, so it can be found at its URL.
 /// - **Server functions must be `async`.** Even if the work being done inside the function body
 ///   can run synchronously on the server, from the client’s perspective it involves an asynchronous
 ///   function call.
@@ -1,7 +1,7 @@
 use cfg_if::cfg_if;
 use leptos_server::Encoding;
 use proc_macro2::{Literal, TokenStream as TokenStream2};
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
@@ -50,9 +50,9 @@ pub fn server_macro_impl(
             use proc_macro::Span;
             let span = Span::call_site();
             #[cfg(not(target_os = "windows"))]
-            let url = format!("{}/{}", span.source_file().path().to_string_lossy(), fn_name_as_str).replace('/', "-");
+            let url = format!("{}/{}", span.file(), fn_name_as_str).replace('/', "-");
             #[cfg(target_os = "windows")]
-            let url = format!("{}\\{}", span.source_file().path().to_string_lossy(), fn_name_as_str).replace("\\", "-");
+            let url = format!("{}\\{}", span.file(), fn_name_as_str).replace("\\", "-");
         } else {
             let url = fn_name_as_str;
         }
@@ -128,6 +128,29 @@ pub fn server_macro_impl(
     let field_names_4 = field_names.clone();
     let field_names_5 = field_names.clone();
 
+    let docs = body
+        .attrs
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(doc),
+                ..
+            })) if path.is_ident("doc") => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(String::from).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let arg_schemas = body.inputs.iter().filter_map(|f| match f {
+        FnArg::Typed(t) if !fn_arg_is_cx(f) => {
+            let name = t.pat.to_token_stream().to_string();
+            let schema = type_schema(&t.ty);
+            Some(quote! { (#name, #schema) })
+        }
+        _ => None,
+    });
+
     let output_arrow = body.output_arrow;
     let return_ty = body.return_ty;
 
@@ -149,6 +172,11 @@ pub fn server_macro_impl(
         panic!("server functions should return Result<T, ServerFnError>");
     };
 
+    let output_schema = match output_ty {
+        GenericArgument::Type(ty) => type_schema(ty),
+        _ => panic!("server functions should return Result<T, ServerFnError>"),
+    };
+
     Ok(quote::quote! {
         #[derive(Clone, Debug, ::serde::Serialize, ::serde::Deserialize)]
         pub struct #struct_name {
@@ -184,6 +212,17 @@ pub fn server_macro_impl(
             }
         }
 
+        #[cfg(feature = "ssr")]
+        ::leptos::leptos_server::inventory::submit! {
+            ::leptos::leptos_server::ServerFnRegistration::new::<#struct_name>()
+                .with_schema(::leptos::leptos_server::ServerFnSchema {
+                    name: #fn_name_as_str,
+                    docs: #docs,
+                    args: &[#(#arg_schemas),*],
+                    output: #output_schema,
+                })
+        }
+
         #[cfg(feature = "ssr")]
         #vis async fn #fn_name(#(#fn_args),*) #output_arrow #return_ty {
             #block
@@ -197,6 +236,82 @@ pub fn server_macro_impl(
     })
 }
 
+/// Describes a type, from the way it is written, for the OpenAPI document of the server
+/// functions.
+fn type_schema(ty: &Type) -> TokenStream2 {
+    let schema = quote! { ::leptos::leptos_server::TypeSchema };
+    match ty {
+        Type::Reference(ty) => type_schema(&ty.elem),
+        Type::Paren(ty) => type_schema(&ty.elem),
+        Type::Group(ty) => type_schema(&ty.elem),
+        Type::Slice(ty) => {
+            let items = type_schema(&ty.elem);
+            quote! { #schema::Array(&#items) }
+        }
+        Type::Array(ty) => {
+            let items = type_schema(&ty.elem);
+            quote! { #schema::Array(&#items) }
+        }
+        Type::Tuple(ty) if ty.elems.is_empty() => quote! { #schema::Null },
+        Type::Tuple(ty) => {
+            let items = ty.elems.iter().map(type_schema);
+            quote! { #schema::Tuple(&[#(#items),*]) }
+        }
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last().unwrap();
+            let args = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            match (segment.ident.to_string().as_str(), args.as_slice()) {
+                ("bool", _) => quote! { #schema::Boolean },
+                ("i8" | "i16" | "i32" | "u8" | "u16", _) => {
+                    quote! { #schema::Integer("int32") }
+                }
+                (
+                    "i64" | "i128" | "isize" | "u32" | "u64" | "u128" | "usize",
+                    _,
+                ) => {
+                    quote! { #schema::Integer("int64") }
+                }
+                ("f32", _) => quote! { #schema::Number("float") },
+                ("f64", _) => quote! { #schema::Number("double") },
+                ("String" | "str" | "char", _) => quote! { #schema::String },
+                ("Option", [inner]) => {
+                    let inner = type_schema(inner);
+                    quote! { #schema::Optional(&#inner) }
+                }
+                (
+                    "Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet",
+                    [items],
+                ) => {
+                    let items = type_schema(items);
+                    quote! { #schema::Array(&#items) }
+                }
+                ("HashMap" | "BTreeMap", [_, values]) => {
+                    let values = type_schema(values);
+                    quote! { #schema::Map(&#values) }
+                }
+                ("Box" | "Rc" | "Arc" | "Cow", [inner]) => type_schema(inner),
+                _ => named_type_schema(ty),
+            }
+        }
+        _ => named_type_schema(ty),
+    }
+}
+
+fn named_type_schema(ty: &Type) -> TokenStream2 {
+    let name = ty.to_token_stream().to_string().replace(' ', "");
+    quote! { ::leptos::leptos_server::TypeSchema::Named(#name) }
+}
+
 pub struct ServerFnName {
     struct_name: Ident,
     _comma: Option<Token![,]>,
@@ -460,9 +460,10 @@ fn attribute_to_tokens_ssr<'a>(
         })
     } else if name.strip_prefix("prop:").is_some()
         || name.strip_prefix("class:").is_some()
+        || name == "scoped_style"
     {
         // ignore props for SSR
-        // ignore classes: we'll handle these separately
+        // ignore classes and scoped styles: we'll handle these separately
     } else if name == "inner_html" {
         return node.value.as_ref();
     } else {
@@ -592,9 +593,26 @@ fn set_class_attribute_ssr(
         })
         .collect::<Vec<_>>();
 
+    let scoped_styles = node
+        .attributes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Attribute(node) if node.key.to_string() == "scoped_style" => {
+                Some(
+                    node.value
+                        .as_ref()
+                        .expect("scoped_style attributes need a value")
+                        .as_ref(),
+                )
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
     if !static_class_attr.is_empty()
         || !dyn_class_attr.is_empty()
         || !class_attrs.is_empty()
+        || !scoped_styles.is_empty()
         || dyn_global_class.is_some()
     {
         template.push_str(" class=\"");
@@ -620,6 +638,13 @@ fn set_class_attribute_ssr(
             });
         }
 
+        for css in scoped_styles {
+            template.push_str(" {}");
+            holes.push(quote! {
+              ::leptos_meta::use_scoped_style(#cx, #css),
+            });
+        }
+
         if let Some(dyn_global_class) = dyn_global_class {
             template.push_str(" {}");
             holes.push(quote! { #dyn_global_class, });
@@ -933,6 +958,15 @@ fn attribute_to_tokens(cx: &Ident, node: &NodeAttribute) -> TokenStream {
         quote! {
             #class(#name, (#cx, #[allow(unused_braces)] #value))
         }
+    } else if name == "scoped_style" {
+        let css = node
+            .value
+            .as_ref()
+            .expect("scoped_style attributes need a value")
+            .as_ref();
+        quote_spanned! {
+            span => .class(::leptos_meta::use_scoped_style(#cx, #css), true)
+        }
     } else {
         let name = name.replacen("attr:", "", 1);
 
@@ -18,6 +18,7 @@ use std::{
     pin::Pin,
     rc::Rc,
 };
+use tracing::Instrument;
 
 /// Creates [Resource](crate::Resource), which is a signal that reflects the
 /// current state of an asynchronous task, allowing you to integrate `async`
@@ -62,6 +63,7 @@ use std::{
 /// # }
 /// # }).dispose();
 /// ```
+#[track_caller]
 pub fn create_resource<S, T, Fu>(
     cx: Scope,
     source: impl Fn() -> S + 'static,
@@ -131,6 +133,9 @@ where
         resolved: Rc::new(Cell::new(resolved)),
         scheduled: Rc::new(Cell::new(false)),
         suspense_contexts: Default::default(),
+        id: Default::default(),
+        #[cfg(debug_assertions)]
+        defined_at: std::panic::Location::caller(),
     });
 
     let id = with_runtime(cx.runtime, |runtime| {
@@ -138,6 +143,7 @@ where
         runtime.create_serializable_resource(r)
     })
     .expect("tried to create a Resource in a Runtime that has been disposed.");
+    r.id.set(id);
 
     create_isomorphic_effect(cx, {
         let r = Rc::clone(&r);
@@ -189,6 +195,7 @@ where
 /// # }
 /// # }).dispose();
 /// ```
+#[track_caller]
 pub fn create_local_resource<S, T, Fu>(
     cx: Scope,
     source: impl Fn() -> S + 'static,
@@ -255,6 +262,9 @@ where
         resolved: Rc::new(Cell::new(resolved)),
         scheduled: Rc::new(Cell::new(false)),
         suspense_contexts: Default::default(),
+        id: Default::default(),
+        #[cfg(debug_assertions)]
+        defined_at: std::panic::Location::caller(),
     });
 
     let id = with_runtime(cx.runtime, |runtime| {
@@ -262,6 +272,7 @@ where
         runtime.create_unserializable_resource(r)
     })
     .expect("tried to create a Resource in a runtime that has been disposed.");
+    r.id.set(id);
 
     create_effect(cx, {
         let r = Rc::clone(&r);
@@ -583,6 +594,10 @@ where
     resolved: Rc<Cell<bool>>,
     scheduled: Rc<Cell<bool>>,
     suspense_contexts: Rc<RefCell<HashSet<SuspenseContext>>>,
+    /// Set once the resource has been added to the runtime.
+    id: Cell<ResourceId>,
+    #[cfg(debug_assertions)]
+    defined_at: &'static std::panic::Location<'static>,
 }
 
 impl<S, T> ResourceState<S, T>
@@ -662,6 +677,17 @@ where
                 suspense_context.increment();
             }
 
+            // the span lasts as long as the Future, so that slow resources can be
+            // found in the trace of the request or interaction that loaded them
+            #[cfg(debug_assertions)]
+            let span = tracing::info_span!(
+                "resource",
+                id = ?self.id.get(),
+                defined_at = %self.defined_at,
+            );
+            #[cfg(not(debug_assertions))]
+            let span = tracing::info_span!("resource", id = ?self.id.get());
+
             // run the Future
             spawn_local({
                 let resolved = self.resolved.clone();
@@ -679,6 +705,7 @@ where
                         suspense_context.decrement();
                     }
                 }
+                .instrument(span)
             })
         });
     }
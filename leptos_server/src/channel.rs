@@ -0,0 +1,292 @@
+use leptos_reactive::{create_signal, ReadSignal, Scope, Serializable};
+use std::{borrow::Cow, marker::PhantomData};
+#[cfg(any(feature = "ssr", doc))]
+use {
+    crate::ServerFnError,
+    futures::channel::mpsc::{channel, Receiver, Sender},
+    std::{collections::HashMap, sync::RwLock},
+};
+
+/// A named channel on which the server pushes messages of type `T` to the browser, using
+/// [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events).
+///
+/// Define the channel where both the server and the client can see it. Server code
+/// [broadcasts](Channel::broadcast) messages on it, and components receive them with
+/// [create_channel_signal]:
+/// ```ignore
+/// fn chat() -> Channel<ChatMessage> {
+///     Channel::new("chat")
+/// }
+///
+/// #[server(SendMessage, "/api")]
+/// pub async fn send_message(text: String) -> Result<(), ServerFnError> {
+///     chat().broadcast(&ChatMessage { text })?;
+///     Ok(())
+/// }
+///
+/// #[component]
+/// fn LastMessage(cx: Scope) -> impl IntoView {
+///     let message = create_channel_signal(cx, chat());
+///     move || message.get().map(|message| message.text)
+/// }
+/// ```
+///
+/// The integrations serve every channel from one route, with `handle_channel`, or with
+/// `handle_channel_with_authorization` to decide who may subscribe. The browser looks for it
+/// at `/channels/{name}`, unless the channel has another [prefix](Channel::with_prefix).
+/// Channels with the same name but different prefixes are separate channels.
+pub struct Channel<T> {
+    prefix: Cow<'static, str>,
+    name: Cow<'static, str>,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        Self {
+            prefix: self.prefix.clone(),
+            name: self.name.clone(),
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Channel")
+            .field("prefix", &self.prefix)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<T> Channel<T> {
+    /// Creates a handle to the channel with the given name, served under `/channels`.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            prefix: Cow::Borrowed("/channels"),
+            name: name.into(),
+            ty: PhantomData,
+        }
+    }
+
+    /// Sets the path of the route the integration serves channels from.
+    pub fn with_prefix(mut self, prefix: impl Into<Cow<'static, str>>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// The name of the channel.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The URL the browser subscribes to the channel at.
+    pub fn url(&self) -> String {
+        format!("{}/{}", self.prefix.trim_end_matches('/'), self.name)
+    }
+}
+
+#[cfg(any(feature = "ssr", doc))]
+impl<T> Channel<T>
+where
+    T: Serializable,
+{
+    /// Sends a message to every browser subscribed to the channel, and returns how many
+    /// there are.
+    ///
+    /// A subscriber that has fallen [CHANNEL_BUFFER] messages behind, because its
+    /// connection can’t keep up, is unsubscribed rather than holding on to every message.
+    /// Its stream ends, and the browser reconnects.
+    pub fn broadcast(&self, message: &T) -> Result<usize, ServerFnError> {
+        let message = message
+            .to_json()
+            .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+        Ok(broadcast_to_channel(&self.url(), message))
+    }
+}
+
+/// How many messages a subscriber to a [Channel] can fall behind by before it is
+/// unsubscribed.
+#[cfg(any(feature = "ssr", doc))]
+pub const CHANNEL_BUFFER: usize = 64;
+
+#[cfg(any(feature = "ssr", doc))]
+lazy_static::lazy_static! {
+    static ref CHANNEL_SUBSCRIBERS: RwLock<HashMap<String, Vec<Sender<String>>>> =
+        Default::default();
+}
+
+/// Subscribes to the messages broadcast on the channel served at the given URL (see
+/// [Channel::url]), serialized as JSON. The integrations use this to stream the messages
+/// to the browser; the subscription ends when the receiver is dropped, or when it falls
+/// [CHANNEL_BUFFER] messages behind.
+#[cfg(any(feature = "ssr", doc))]
+pub fn subscribe_to_channel(url: &str) -> Receiver<String> {
+    let (tx, rx) = channel(CHANNEL_BUFFER);
+    CHANNEL_SUBSCRIBERS
+        .write()
+        .unwrap()
+        .entry(url.trim_end_matches('/').to_string())
+        .or_default()
+        .push(tx);
+    rx
+}
+
+#[cfg(any(feature = "ssr", doc))]
+fn broadcast_to_channel(url: &str, message: String) -> usize {
+    let mut channels = CHANNEL_SUBSCRIBERS.write().unwrap();
+    let subscribers = match channels.get_mut(url) {
+        Some(subscribers) => subscribers,
+        None => return 0,
+    };
+    // sending fails once a subscriber's receiver has been dropped, or its buffer is full
+    subscribers.retain_mut(|tx| tx.try_send(message.clone()).is_ok());
+    let count = subscribers.len();
+    if count == 0 {
+        channels.remove(url);
+    }
+    count
+}
+
+/// Subscribes to a [Channel], and returns a signal that holds the latest message received on
+/// it.
+///
+/// The signal is `None` until the first message arrives, and always `None` while rendering on
+/// the server. In the browser, a dropped connection is reopened, waiting longer after each
+/// failed attempt, and the connection is closed when the [Scope] is disposed.
+///
+/// To handle every message, rather than only the latest, use the signal’s
+/// [to_stream](ReadSignal::to_stream).
+pub fn create_channel_signal<T>(
+    cx: Scope,
+    channel: Channel<T>,
+) -> ReadSignal<Option<T>>
+where
+    T: Serializable + 'static,
+{
+    let (message, set_message) = create_signal(cx, None);
+    #[cfg(all(
+        any(feature = "csr", feature = "hydrate"),
+        not(feature = "ssr")
+    ))]
+    client::connect(cx, channel.url(), set_message);
+    #[cfg(not(all(
+        any(feature = "csr", feature = "hydrate"),
+        not(feature = "ssr")
+    )))]
+    {
+        _ = channel;
+        _ = set_message;
+    }
+    message
+}
+
+#[cfg(all(any(feature = "csr", feature = "hydrate"), not(feature = "ssr")))]
+mod client {
+    use futures::StreamExt;
+    use gloo_net::eventsource::futures::EventSource;
+    use leptos_reactive::{
+        on_cleanup, spawn_local, Scope, Serializable, WriteSignal,
+    };
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+        time::Duration,
+    };
+
+    const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    struct Connection {
+        url: String,
+        source: RefCell<Option<EventSource>>,
+        backoff: Cell<Duration>,
+        disposed: Cell<bool>,
+    }
+
+    pub(crate) fn connect<T>(
+        cx: Scope,
+        url: String,
+        set_message: WriteSignal<Option<T>>,
+    ) where
+        T: Serializable + 'static,
+    {
+        let connection = Rc::new(Connection {
+            url,
+            source: Default::default(),
+            backoff: Cell::new(INITIAL_BACKOFF),
+            disposed: Cell::new(false),
+        });
+        on_cleanup(cx, {
+            let connection = Rc::clone(&connection);
+            move || {
+                connection.disposed.set(true);
+                if let Some(source) = connection.source.take() {
+                    source.close();
+                }
+            }
+        });
+        open(connection, set_message);
+    }
+
+    fn open<T>(connection: Rc<Connection>, set_message: WriteSignal<Option<T>>)
+    where
+        T: Serializable + 'static,
+    {
+        let mut source = match EventSource::new(&connection.url) {
+            Ok(source) => source,
+            Err(e) => {
+                leptos_dom::error!(
+                    "couldn't open channel {}: {e}",
+                    connection.url
+                );
+                return;
+            }
+        };
+        let mut messages = match source.subscribe("message") {
+            Ok(messages) => messages,
+            Err(e) => {
+                leptos_dom::error!(
+                    "couldn't subscribe to channel {}: {e}",
+                    connection.url
+                );
+                return;
+            }
+        };
+        connection.source.replace(Some(source));
+
+        spawn_local(async move {
+            while let Some(Ok((_, event))) = messages.next().await {
+                if connection.disposed.get() {
+                    break;
+                }
+                // a message means the connection works, so start over if it fails again
+                connection.backoff.set(INITIAL_BACKOFF);
+                let json = event.data().as_string().unwrap_or_default();
+                match T::from_json(&json) {
+                    Ok(message) => set_message.set(Some(message)),
+                    Err(e) => leptos_dom::error!(
+                        "couldn't deserialize message on channel {}: {e}",
+                        connection.url
+                    ),
+                }
+            }
+
+            // the connection failed, or was closed because the scope was disposed
+            drop(connection.source.take());
+            if !connection.disposed.get() {
+                let delay = connection.backoff.get();
+                connection.backoff.set((delay * 2).min(MAX_BACKOFF));
+                leptos_dom::helpers::set_timeout(
+                    move || {
+                        if !connection.disposed.get() {
+                            open(connection, set_message);
+                        }
+                    },
+                    delay,
+                );
+            }
+        });
+    }
+}
@@ -32,8 +32,8 @@
 //! indicate that it should only run on the server (i.e., when you have an `ssr` feature in your
 //! crate that is enabled).
 //!
-//! **Important**: All server functions must be registered by calling [ServerFn::register]
-//! somewhere within your `main` function.
+//! Server functions are registered automatically when the `ssr` feature is enabled, so the
+//! server can find them by URL. (See [server_fn_by_path].)
 //!
 //! ```rust,ignore
 //! # use leptos::*;
@@ -51,11 +51,6 @@
 //!   log::debug!("posts = {posts{:#?}");
 //! })
 //! # });
-//!
-//! // make sure you've registered it somewhere in main
-//! fn main() {
-//!   _ = ReadFromDB::register();
-//! }
 //! ```
 //!
 //! If you call this function from the client, it will serialize the function arguments and `POST`
@@ -90,11 +85,29 @@ use syn::{
 use thiserror::Error;
 
 mod action;
+mod channel;
 mod multi_action;
+#[cfg(feature = "ssr")]
+mod openapi;
+#[cfg(any(feature = "ssr", doc))]
+mod page_cache;
+#[cfg(feature = "ssr")]
+mod session;
 pub use action::*;
+pub use channel::*;
+#[cfg(feature = "ssr")]
+#[doc(hidden)]
+pub use inventory;
 pub use multi_action::*;
+#[cfg(feature = "ssr")]
+pub use openapi::*;
+#[cfg(any(feature = "ssr", doc))]
+pub use page_cache::*;
+#[cfg(feature = "ssr")]
+pub use session::*;
 #[cfg(any(feature = "ssr", doc))]
 use std::{
+    any::TypeId,
     collections::HashMap,
     sync::{Arc, RwLock},
 };
@@ -108,10 +121,95 @@ type ServerFnTraitObj = dyn Fn(
     + Sync;
 
 #[cfg(any(feature = "ssr", doc))]
+#[derive(Default)]
+struct ServerFnRegistry {
+    fns: HashMap<&'static str, (TypeId, Arc<ServerFnTraitObj>)>,
+    duplicates: Vec<&'static str>,
+}
+
+#[cfg(any(feature = "ssr", doc))]
+impl ServerFnRegistry {
+    fn insert(
+        &mut self,
+        url: &'static str,
+        type_id: TypeId,
+        server_fn: Arc<ServerFnTraitObj>,
+    ) -> bool {
+        match self.fns.get(url) {
+            Some((prev_type_id, _)) if *prev_type_id == type_id => true,
+            Some(_) => {
+                if !self.duplicates.contains(&url) {
+                    self.duplicates.push(url);
+                }
+                false
+            }
+            None => {
+                self.fns.insert(url, (type_id, server_fn));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
 lazy_static::lazy_static! {
-    static ref REGISTERED_SERVER_FUNCTIONS: Arc<RwLock<HashMap<&'static str, Arc<ServerFnTraitObj>>>> = Default::default();
+    static ref REGISTERED_SERVER_FUNCTIONS: Arc<RwLock<ServerFnRegistry>> = {
+        let mut registry = ServerFnRegistry::default();
+        for registration in inventory::iter::<ServerFnRegistration> {
+            registry.insert(
+                (registration.url)(),
+                (registration.type_id)(),
+                (registration.handler)(),
+            );
+        }
+        for url in &registry.duplicates {
+            leptos_dom::warn!(
+                "More than one server function was registered at {url:?}. \
+                 Only one of them can be called. Give them different names \
+                 or prefixes."
+            );
+        }
+        Arc::new(RwLock::new(registry))
+    };
+}
+
+/// A server function collected by the `#[server]` macro, which registers it before the
+/// first time any server function is looked up.
+#[cfg(feature = "ssr")]
+#[doc(hidden)]
+pub struct ServerFnRegistration {
+    prefix: fn() -> &'static str,
+    url: fn() -> &'static str,
+    encoding: fn() -> Encoding,
+    type_id: fn() -> TypeId,
+    handler: fn() -> Arc<ServerFnTraitObj>,
+    schema: Option<ServerFnSchema>,
+}
+
+#[cfg(feature = "ssr")]
+impl ServerFnRegistration {
+    #[doc(hidden)]
+    pub const fn new<T: ServerFn>() -> Self {
+        Self {
+            prefix: T::prefix,
+            url: T::url,
+            encoding: T::encoding,
+            type_id: TypeId::of::<T>,
+            handler: T::handler,
+            schema: None,
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn with_schema(mut self, schema: ServerFnSchema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
 }
 
+#[cfg(feature = "ssr")]
+inventory::collect!(ServerFnRegistration);
+
 /// A dual type to hold the possible Response datatypes
 #[derive(Debug)]
 pub enum Payload {
@@ -166,23 +264,85 @@ pub enum Payload {
 ///     }
 /// }
 /// ```
-#[cfg(any(feature = "ssr", doc))]
+#[cfg(feature = "ssr")]
 pub fn server_fn_by_path(path: &str) -> Option<Arc<ServerFnTraitObj>> {
     REGISTERED_SERVER_FUNCTIONS
         .read()
         .ok()
-        .and_then(|fns| fns.get(path).cloned())
+        .and_then(|registry| {
+            registry
+                .fns
+                .get(path)
+                .map(|(_, server_fn)| server_fn.clone())
+        })
 }
 
 /// Returns the set of currently-registered server function paths, for debugging purposes.
-#[cfg(any(feature = "ssr", doc))]
+#[cfg(feature = "ssr")]
 pub fn server_fns_by_path() -> Vec<&'static str> {
     REGISTERED_SERVER_FUNCTIONS
         .read()
-        .map(|vals| vals.keys().copied().collect())
+        .map(|registry| registry.fns.keys().copied().collect())
         .unwrap_or_default()
 }
 
+/// Registers the server functions collected by the `#[server]` macro, if that hasn’t
+/// happened yet, and returns the URLs at which more than one of them was registered. A
+/// warning is logged for each of these URLs, and only one of the functions at each can be
+/// reached.
+///
+/// This can happen if you give two server functions the same name in different modules
+/// on `stable` or in `release` mode. Call this when your server starts to find out right
+/// away, rather than when the first server function is called.
+#[cfg(feature = "ssr")]
+pub fn check_server_fn_urls() -> Vec<&'static str> {
+    REGISTERED_SERVER_FUNCTIONS
+        .read()
+        .map(|registry| registry.duplicates.clone())
+        .unwrap_or_default()
+}
+
+/// The name of the hidden form field in which `<ActionForm/>` and `<MultiActionForm/>` send
+/// the CSRF token.
+pub const CSRF_FIELD: &str = "_csrf";
+
+/// The name of the request header in which `call_server_fn` sends the CSRF token
+/// when a server function is called from the browser.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Returns the CSRF token of the current visitor’s session, if the integration is configured
+/// with sessions.
+///
+/// On the server, this is the token of the [Session] provided as context. In the browser, it
+/// is read from the `<meta name="csrf-token">` tag the integrations add to the page.
+pub fn csrf_token(cx: Scope) -> Option<String> {
+    #[cfg(feature = "ssr")]
+    {
+        use_context::<Session>(cx).map(|session| session.csrf_token())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        _ = cx;
+        csrf_token_from_document()
+    }
+}
+
+#[cfg(not(feature = "ssr"))]
+fn csrf_token_from_document() -> Option<String> {
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    {
+        leptos_dom::document()
+            .query_selector(r#"meta[name="csrf-token"]"#)
+            .ok()
+            .flatten()
+            .and_then(|meta| meta.get_attribute("content"))
+    }
+    #[cfg(not(any(feature = "csr", feature = "hydrate")))]
+    {
+        None
+    }
+}
+
 /// Holds the current options for encoding types.
 /// More could be added, but they need to be serde
 #[derive(Debug, PartialEq)]
@@ -239,7 +399,7 @@ impl Parse for Encoding {
 ///
 /// Server functions are created using the `server` macro.
 ///
-/// The function should be registered by calling `ServerFn::register()`. The set of server functions
+/// The function is registered automatically on the server. The set of server functions
 /// can be queried on the server for routing purposes by calling [server_fn_by_path].
 ///
 /// Technically, the trait is implemented on a type that describes the server function's arguments.
@@ -273,13 +433,14 @@ where
         cx: Scope,
     ) -> Pin<Box<dyn Future<Output = Result<Self::Output, ServerFnError>>>>;
 
-    /// Registers the server function, allowing the server to query it by URL.
+    /// Creates the handler the server uses to run the function with serialized arguments.
     #[cfg(any(feature = "ssr", doc))]
-    fn register() -> Result<(), ServerFnError> {
+    #[doc(hidden)]
+    fn handler() -> Arc<ServerFnTraitObj> {
         // create the handler for this server function
         // takes a String -> returns its async value
 
-        let run_server_fn = Arc::new(|cx: Scope, data: &[u8]| {
+        Arc::new(|cx: Scope, data: &[u8]| {
             // decode the args
             let value = match Self::encoding() {
                 Encoding::Url => serde_urlencoded::from_bytes(data)
@@ -323,25 +484,33 @@ where
                 Ok(result)
             })
                 as Pin<Box<dyn Future<Output = Result<Payload, ServerFnError>>>>
-        });
+        })
+    }
 
+    /// Registers the server function, allowing the server to query it by URL.
+    ///
+    /// The `#[server]` macro already registers every server function, so this is only needed
+    /// for types that implement [ServerFn] by hand. Registering a function again is a no-op.
+    #[cfg(feature = "ssr")]
+    fn register() -> Result<(), ServerFnError> {
         // store it in the hashmap
         let mut write = REGISTERED_SERVER_FUNCTIONS
             .write()
             .map_err(|e| ServerFnError::Registration(e.to_string()))?;
-        let prev = write.insert(Self::url(), run_server_fn);
+        let registered =
+            write.insert(Self::url(), TypeId::of::<Self>(), Self::handler());
 
-        // if there was already a server function with this key,
+        // if there was already a different server function with this key,
         // return Err
-        match prev {
-            Some(_) => Err(ServerFnError::Registration(format!(
+        match registered {
+            false => Err(ServerFnError::Registration(format!(
                 "There was already a server function registered at {:?}. This \
                  can happen if you use the same server function name in two \
                  different modules
                 on `stable` or in `release` mode.",
                 Self::url()
             ))),
-            None => Ok(()),
+            true => Ok(()),
         }
     }
 }
@@ -414,21 +583,24 @@ where
         Encoding::Cbor => "application/cbor",
     };
 
+    let mut request = gloo_net::http::Request::post(url)
+        .header("Content-Type", content_type_header)
+        .header("Accept", accept_header);
+    if let Some(token) = csrf_token_from_document() {
+        request = request.header(CSRF_HEADER, &token);
+    }
+
     let resp = match args_encoded {
         Payload::Binary(b) => {
             let slice_ref: &[u8] = &b;
             let js_array = Uint8Array::from(slice_ref).buffer();
-            gloo_net::http::Request::post(url)
-                .header("Content-Type", content_type_header)
-                .header("Accept", accept_header)
+            request
                 .body(js_array)
                 .send()
                 .await
                 .map_err(|e| ServerFnError::Request(e.to_string()))?
         }
-        Payload::Url(s) => gloo_net::http::Request::post(url)
-            .header("Content-Type", content_type_header)
-            .header("Accept", accept_header)
+        Payload::Url(s) => request
             .body(s)
             .send()
             .await
@@ -0,0 +1,246 @@
+use crate::{Encoding, ServerFnRegistration};
+use serde_json::{json, Map, Value};
+
+/// The shape of a server function’s argument or return type, as described by the
+/// `#[server]` macro for [openapi_document].
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum TypeSchema {
+    /// `()`
+    Null,
+    /// `bool`
+    Boolean,
+    /// An integer type, with its OpenAPI format.
+    Integer(&'static str),
+    /// A floating-point type, with its OpenAPI format.
+    Number(&'static str),
+    /// `String`, `&str`, or `char`
+    String,
+    /// `Option<T>`
+    Optional(&'static TypeSchema),
+    /// `Vec<T>`, a slice, or a set
+    Array(&'static TypeSchema),
+    /// A map with string keys
+    Map(&'static TypeSchema),
+    /// A tuple
+    Tuple(&'static [TypeSchema]),
+    /// Any other type, by name
+    Named(&'static str),
+}
+
+/// What the `#[server]` macro knows about a server function, for [openapi_document].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ServerFnSchema {
+    /// The name of the function.
+    pub name: &'static str,
+    /// The function’s doc comments.
+    pub docs: &'static str,
+    /// The name and type of each argument, except the [Scope](leptos_reactive::Scope).
+    pub args: &'static [(&'static str, TypeSchema)],
+    /// The `T` in the function’s `Result<T, ServerFnError>`.
+    pub output: TypeSchema,
+}
+
+impl TypeSchema {
+    /// Describes the type as a JSON Schema. A named type is described by a `$ref` to a
+    /// placeholder schema, which is added to `schemas`.
+    fn to_json(&self, schemas: &mut Map<String, Value>) -> Value {
+        match self {
+            TypeSchema::Null => json!({ "type": "null" }),
+            TypeSchema::Boolean => json!({ "type": "boolean" }),
+            TypeSchema::Integer(format) => {
+                json!({ "type": "integer", "format": format })
+            }
+            TypeSchema::Number(format) => {
+                json!({ "type": "number", "format": format })
+            }
+            TypeSchema::String => json!({ "type": "string" }),
+            TypeSchema::Optional(inner) => {
+                let mut schema = inner.to_json(schemas);
+                match schema.get("type").and_then(Value::as_str) {
+                    Some(ty) if ty != "null" => {
+                        schema["type"] = json!([ty, "null"]);
+                        schema
+                    }
+                    Some(_) => schema,
+                    None => json!({ "anyOf": [schema, { "type": "null" }] }),
+                }
+            }
+            TypeSchema::Array(items) => {
+                json!({ "type": "array", "items": items.to_json(schemas) })
+            }
+            TypeSchema::Map(values) => {
+                json!({
+                    "type": "object",
+                    "additionalProperties": values.to_json(schemas),
+                })
+            }
+            TypeSchema::Tuple(items) => json!({
+                "type": "array",
+                "prefixItems": items
+                    .iter()
+                    .map(|item| item.to_json(schemas))
+                    .collect::<Vec<_>>(),
+                "items": false,
+                "minItems": items.len(),
+                "maxItems": items.len(),
+            }),
+            TypeSchema::Named(name) => {
+                // component names may only contain letters, digits, `.`, `-` and `_`
+                let key = name
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || matches!(c, '.' | '-') {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>();
+                schemas.entry(key.clone()).or_insert_with(|| {
+                    json!({
+                        "type": "object",
+                        "title": name,
+                        "description": format!(
+                            "A `{name}`, as serialized by serde."
+                        ),
+                    })
+                });
+                json!({ "$ref": format!("#/components/schemas/{key}") })
+            }
+        }
+    }
+}
+
+/// Builds an [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document that describes every
+/// server function defined with the `#[server]` macro: the URL it is mounted at, the
+/// arguments it takes in the request body (form-encoded or CBOR, depending on its
+/// [Encoding]), the value it returns, and the error it responds with if it fails.
+///
+/// Argument and return types are described from the way they are written. Primitives,
+/// strings, `Option`, collections, maps and tuples are described in full; any other type
+/// is a `$ref` to a placeholder schema in `components`, which gives only its name.
+///
+/// The integrations can serve this document from a route, for use with tools that generate
+/// API clients or documentation.
+pub fn openapi_document(title: &str, version: &str) -> Value {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    schemas.insert(
+        "ServerFnError".to_string(),
+        json!({
+            "type": "string",
+            "description": "The error returned by the server function, as \
+                            text.",
+            "example": "error running server function: not found",
+        }),
+    );
+    for registration in inventory::iter::<ServerFnRegistration> {
+        let prefix = (registration.prefix)().trim_end_matches('/');
+        let path = format!("{prefix}/{}", (registration.url)());
+        if paths.contains_key(&path) {
+            // only the first server function at each URL can be called
+            continue;
+        }
+        paths.insert(
+            path,
+            json!({ "post": operation(
+                (registration.url)(),
+                (registration.encoding)(),
+                registration.schema.as_ref(),
+                &mut schemas,
+            ) }),
+        );
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": { "title": title, "version": version },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+fn operation(
+    url: &str,
+    encoding: Encoding,
+    schema: Option<&ServerFnSchema>,
+    schemas: &mut Map<String, Value>,
+) -> Value {
+    // the response has the same content type as the request, but a URL-encoded
+    // server function returns its value as JSON
+    let (content_type, response_encoding) = match encoding {
+        Encoding::Url => ("application/x-www-form-urlencoded", "JSON"),
+        Encoding::Cbor => ("application/cbor", "CBOR"),
+    };
+
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (name, ty) in schema.map(|schema| schema.args).unwrap_or_default() {
+        properties.insert(name.to_string(), ty.to_json(schemas));
+        if !matches!(ty, TypeSchema::Optional(_)) {
+            required.push(*name);
+        }
+    }
+    let output = schema
+        .map(|schema| schema.output.to_json(schemas))
+        .unwrap_or_else(|| json!({}));
+
+    let success = format!(
+        "The value returned by the server function, encoded as \
+         {response_encoding}."
+    );
+    let redirect = "Sent instead, to redirect back to the `Referer`, if the \
+                    `Accept` header isn’t `application/json`, \
+                    `application/x-www-form-urlencoded` or \
+                    `application/cbor`, as when a `<form>` is submitted.";
+    let error = json!({ "$ref": "#/components/schemas/ServerFnError" });
+
+    let mut operation = json!({
+        "operationId": schema.map(|schema| schema.name).unwrap_or(url),
+        "requestBody": {
+            "required": true,
+            "content": {
+                content_type: {
+                    "schema": {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    }
+                }
+            }
+        },
+        "responses": {
+            "200": {
+                "description": success,
+                "content": {
+                    content_type: { "schema": output }
+                }
+            },
+            "303": {
+                "description": redirect,
+            },
+            "500": {
+                "description": "The server function returned an error.",
+                "content": {
+                    "text/plain": {
+                        "schema": error
+                    }
+                }
+            }
+        }
+    });
+
+    if let Some(docs) = schema.map(|schema| schema.docs.trim()) {
+        let (summary, description) =
+            docs.split_once("\n\n").unwrap_or((docs, ""));
+        if !summary.is_empty() {
+            operation["summary"] = summary.replace('\n', " ").into();
+        }
+        if !description.is_empty() {
+            operation["description"] = description.into();
+        }
+    }
+    operation
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, SystemTime},
+};
+
+/// Identifies one cached response: the path of the page, plus the values of the request
+/// parts the cache is configured to vary by. (See [PageCache::vary_by_query] and
+/// [PageCache::vary_by_header].)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// The path of the page, without the query string.
+    pub path: String,
+    /// The values of the request parts the cache varies by, in the order they were configured.
+    pub vary: Vec<Option<String>>,
+}
+
+/// A rendered response, as stored in a [PageCache].
+#[derive(Clone, Debug)]
+pub struct CachedPage {
+    /// The status code of the response.
+    pub status: u16,
+    /// The headers of the response.
+    pub headers: Vec<(String, String)>,
+    /// The complete HTML of the page.
+    pub body: String,
+    /// When the page was rendered.
+    pub rendered_at: SystemTime,
+}
+
+impl CachedPage {
+    /// The nonce the page was rendered with, if it was sent with a
+    /// `Content-Security-Policy` header that uses one.
+    pub fn csp_nonce(&self) -> Option<&str> {
+        self.headers
+            .iter()
+            .filter(|(name, _)| {
+                name.eq_ignore_ascii_case("content-security-policy")
+            })
+            .find_map(|(_, value)| {
+                let start = value.find("'nonce-")? + "'nonce-".len();
+                let len = value[start..].find('\'')?;
+                Some(&value[start..start + len])
+            })
+    }
+
+    /// Replaces the nonce the page was rendered with, in its body and its headers.
+    ///
+    /// A nonce only protects a page while it can't be guessed, so the integrations give a
+    /// page served from the cache a new one each time, instead of repeating the nonce
+    /// that every earlier visitor has seen.
+    pub fn replace_nonce(&mut self, nonce: &str) {
+        if let Some(old) = self.csp_nonce().map(String::from) {
+            self.body = self.body.replace(&old, nonce);
+            for (_, value) in &mut self.headers {
+                *value = value.replace(&old, nonce);
+            }
+        }
+    }
+}
+
+/// Where a [PageCache] keeps its pages. The cache uses a [MemoryStore] unless you give it
+/// another one with [PageCache::with_store], for example to share pages between several
+/// servers.
+pub trait PageCacheStore: Send + Sync + 'static {
+    /// Returns the page stored for the key, if any.
+    fn get(&self, key: &CacheKey) -> Option<CachedPage>;
+
+    /// Stores a page, replacing any page already stored for the key.
+    fn insert(&self, key: CacheKey, page: CachedPage);
+
+    /// Removes every page stored for the path, whatever the rest of its key.
+    fn invalidate(&self, path: &str);
+
+    /// Removes every page.
+    fn clear(&self);
+}
+
+/// The default [PageCacheStore], which keeps pages in memory.
+#[derive(Debug, Default)]
+pub struct MemoryStore(RwLock<HashMap<CacheKey, CachedPage>>);
+
+impl PageCacheStore for MemoryStore {
+    fn get(&self, key: &CacheKey) -> Option<CachedPage> {
+        self.0.read().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: CacheKey, page: CachedPage) {
+        self.0.write().unwrap().insert(key, page);
+    }
+
+    fn invalidate(&self, path: &str) {
+        self.0.write().unwrap().retain(|key, _| key.path != path);
+    }
+
+    fn clear(&self) {
+        self.0.write().unwrap().clear();
+    }
+}
+
+/// A server-side cache of rendered pages, which the Axum and Actix integrations use to serve
+/// the same HTML for repeated requests to a page without rendering it again.
+///
+/// A cached page is fresh for the cache’s time-to-live. After that it is stale: it is still
+/// served, but the next request for it also re-renders the page in the background and
+/// replaces it in the cache.
+///
+/// The integrations provide the cache as context to the pages they render, and it can be
+/// provided to server functions too, so that a server function that changes some data can
+/// invalidate the pages that show it:
+/// ```ignore
+/// #[server(UpdatePost, "/api")]
+/// pub async fn update_post(cx: Scope, id: u32, title: String) -> Result<(), ServerFnError> {
+///     save_post(id, title).await?;
+///     if let Some(cache) = use_context::<PageCache>(cx) {
+///         cache.invalidate(&format!("/post/{id}"));
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct PageCache {
+    store: Arc<dyn PageCacheStore>,
+    ttl: Duration,
+    vary_by_query: bool,
+    vary_by_headers: Vec<String>,
+    refreshing: Arc<Mutex<HashSet<CacheKey>>>,
+    generation: Arc<AtomicU64>,
+}
+
+impl std::fmt::Debug for PageCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PageCache")
+            .field("ttl", &self.ttl)
+            .field("vary_by_query", &self.vary_by_query)
+            .field("vary_by_headers", &self.vary_by_headers)
+            .finish()
+    }
+}
+
+impl PageCache {
+    /// Creates a cache that keeps pages in memory, and re-renders them once they are older
+    /// than `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            store: Arc::new(MemoryStore::default()),
+            ttl,
+            vary_by_query: false,
+            vary_by_headers: Vec::new(),
+            refreshing: Default::default(),
+            generation: Default::default(),
+        }
+    }
+
+    /// Keeps pages in the given store, rather than in memory.
+    pub fn with_store(mut self, store: impl PageCacheStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Caches a page separately for each query string. By default, the query string is
+    /// ignored.
+    pub fn vary_by_query(mut self, vary: bool) -> Self {
+        self.vary_by_query = vary;
+        self
+    }
+
+    /// Caches a page separately for each value of the request header, for example
+    /// `accept-language`.
+    pub fn vary_by_header(mut self, name: impl Into<String>) -> Self {
+        self.vary_by_headers.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// How long a page is fresh after it was rendered.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Builds the key for a request from its path, query string, and a function that returns
+    /// the value of a request header by its (lowercase) name.
+    pub fn key(
+        &self,
+        path: &str,
+        query: Option<&str>,
+        header: impl Fn(&str) -> Option<String>,
+    ) -> CacheKey {
+        let query = self
+            .vary_by_query
+            .then(|| query.filter(|query| !query.is_empty()).map(String::from));
+        let vary = query
+            .into_iter()
+            .chain(self.vary_by_headers.iter().map(|name| header(name)))
+            .collect();
+        CacheKey {
+            path: path.to_string(),
+            vary,
+        }
+    }
+
+    /// Returns the cached page for the key, if any.
+    pub fn get(&self, key: &CacheKey) -> Option<CachedPage> {
+        self.store.get(key)
+    }
+
+    /// Whether a page was rendered longer ago than the cache’s time-to-live.
+    pub fn is_stale(&self, page: &CachedPage) -> bool {
+        page.rendered_at
+            .elapsed()
+            .map(|age| age > self.ttl)
+            .unwrap_or(false)
+    }
+
+    /// Returns a number that changes whenever pages are invalidated. Read it before rendering
+    /// a page, and pass it to [PageCache::insert] afterward, so that a page rendered before
+    /// an invalidation doesn’t replace it.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Stores a page that was rendered when [PageCache::generation] returned `generation`,
+    /// unless pages have been invalidated since.
+    pub fn insert(&self, key: CacheKey, page: CachedPage, generation: u64) {
+        if self.generation() == generation {
+            self.store.insert(key, page);
+        }
+    }
+
+    /// Marks a stale page as being re-rendered. Returns `false` if it already is, in which
+    /// case it shouldn’t be rendered again. Call [PageCache::finish_refresh] when done.
+    pub fn start_refresh(&self, key: &CacheKey) -> bool {
+        self.refreshing.lock().unwrap().insert(key.clone())
+    }
+
+    /// Marks a page as no longer being re-rendered.
+    pub fn finish_refresh(&self, key: &CacheKey) {
+        self.refreshing.lock().unwrap().remove(key);
+    }
+
+    /// Removes every cached version of the page at the path, so that the next request for it
+    /// renders it again.
+    pub fn invalidate(&self, path: &str) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.store.invalidate(path);
+    }
+
+    /// Removes every cached page.
+    pub fn invalidate_all(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.store.clear();
+    }
+}
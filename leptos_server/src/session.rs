@@ -0,0 +1,437 @@
+use crate::{ServerFnError, CSRF_FIELD};
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha256;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+/// The values stored in a session, serialized as JSON.
+pub type SessionData = HashMap<String, String>;
+
+/// Where sessions are kept between requests. [SessionOptions] uses a [MemorySessionStore]
+/// unless you give it another one with [SessionOptions::store], for example to keep
+/// sessions in a database that several servers share.
+pub trait SessionStore: Send + Sync + 'static {
+    /// Returns the data stored for the session, if any.
+    fn load(&self, id: &str) -> Option<SessionData>;
+
+    /// Stores the data for the session, replacing any data already stored for it. The
+    /// store can drop the session once it hasn’t been saved for `max_age`.
+    fn save(&self, id: &str, data: SessionData, max_age: Duration);
+
+    /// Removes the session.
+    fn remove(&self, id: &str);
+}
+
+/// The number of sessions a [MemorySessionStore] holds by default.
+pub const DEFAULT_MAX_SESSIONS: usize = 100_000;
+
+/// The default [SessionStore], which keeps sessions in memory.
+///
+/// Expired sessions are dropped when they are next loaded, and all at once whenever the
+/// store is full. If it is still full after that, the sessions closest to expiring make
+/// room for new ones.
+#[derive(Debug)]
+pub struct MemorySessionStore {
+    sessions: RwLock<HashMap<String, (SessionData, Instant)>>,
+    max_sessions: usize,
+}
+
+impl Default for MemorySessionStore {
+    fn default() -> Self {
+        Self::with_max_sessions(DEFAULT_MAX_SESSIONS)
+    }
+}
+
+impl MemorySessionStore {
+    /// Creates a store that holds at most `max_sessions` sessions.
+    pub fn with_max_sessions(max_sessions: usize) -> Self {
+        Self {
+            sessions: Default::default(),
+            max_sessions,
+        }
+    }
+
+    /// The number of sessions in the store, including any that have expired but haven’t
+    /// been dropped yet.
+    pub fn len(&self) -> usize {
+        self.sessions.read().unwrap().len()
+    }
+
+    /// Whether the store holds no sessions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn load(&self, id: &str) -> Option<SessionData> {
+        let (data, expires) = self.sessions.read().unwrap().get(id).cloned()?;
+        if expires <= Instant::now() {
+            self.remove(id);
+            return None;
+        }
+        Some(data)
+    }
+
+    fn save(&self, id: &str, data: SessionData, max_age: Duration) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.write().unwrap();
+        if !sessions.contains_key(id) && sessions.len() >= self.max_sessions {
+            sessions.retain(|_, (_, expires)| *expires > now);
+            while sessions.len() >= self.max_sessions.max(1) {
+                let soonest = sessions
+                    .iter()
+                    .min_by_key(|(_, (_, expires))| *expires)
+                    .map(|(id, _)| id.clone())
+                    .expect("a full store has a session to drop");
+                sessions.remove(&soonest);
+            }
+        }
+        sessions.insert(id.to_string(), (data, now + max_age));
+    }
+
+    fn remove(&self, id: &str) {
+        self.sessions.write().unwrap().remove(id);
+    }
+}
+
+/// Configures the session layer of the Axum and Actix integrations.
+///
+/// Each visitor’s session is identified by a cookie that holds a random ID, signed with the
+/// key so that it can’t be forged. The session’s data is kept in a [SessionStore] on the
+/// server, and never sent to the browser. A session is only stored once something is
+/// written to it, so visitors who only ever get a CSRF token don’t fill up the store.
+/// ```
+/// # use leptos_server::*;
+/// # use std::time::Duration;
+/// let sessions = SessionOptions::new(b"a secret key of at least 32 bytes!")
+///     .cookie_name("my_app_session")
+///     .max_age(Duration::from_secs(60 * 60))
+///     .store(MemorySessionStore::with_max_sessions(10_000));
+/// ```
+#[derive(Clone)]
+pub struct SessionOptions {
+    key: Arc<[u8]>,
+    store: Arc<dyn SessionStore>,
+    cookie_name: String,
+    max_age: Duration,
+    secure: bool,
+}
+
+impl std::fmt::Debug for SessionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionOptions")
+            .field("cookie_name", &self.cookie_name)
+            .field("max_age", &self.max_age)
+            .field("secure", &self.secure)
+            .finish()
+    }
+}
+
+impl SessionOptions {
+    /// Creates options that sign session cookies with `key`, and keep sessions in memory.
+    ///
+    /// # Panics
+    /// Panics if the key is shorter than 32 bytes.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        let key = key.as_ref();
+        assert!(
+            key.len() >= 32,
+            "the key used to sign session cookies must be at least 32 bytes \
+             long"
+        );
+        Self {
+            key: key.into(),
+            store: Arc::new(MemorySessionStore::default()),
+            cookie_name: "leptos_session".to_string(),
+            max_age: DEFAULT_SESSION_MAX_AGE,
+            secure: true,
+        }
+    }
+
+    /// Keeps sessions in the given store, rather than in memory.
+    pub fn store(mut self, store: impl SessionStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Sets the name of the session cookie. Defaults to `leptos_session`.
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// How long a session lasts: the session cookie expires this long after the session
+    /// starts, and the store drops a session that hasn’t been saved for this long.
+    /// Defaults to [DEFAULT_SESSION_MAX_AGE].
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Whether the session cookie is only sent over HTTPS. Defaults to `true`; turn it off
+    /// to use sessions while developing over plain HTTP.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Loads the session named by the request’s `Cookie` header, or starts a new one if
+    /// there is none, or its signature is wrong. A session that has a valid cookie but is
+    /// no longer in the store, or was never stored, keeps its ID with no data.
+    pub fn load(&self, cookie_header: Option<&str>) -> Session {
+        let existing = cookie_header
+            .into_iter()
+            .flat_map(|header| header.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(name, _)| *name == self.cookie_name)
+            .and_then(|(_, value)| self.verify(value));
+        let inner = match existing {
+            Some(id) => SessionInner {
+                id: id.to_string(),
+                data: self.store.load(id).unwrap_or_default(),
+                key: Arc::clone(&self.key),
+                previous_id: None,
+                is_new: false,
+                changed: false,
+                issued_token: false,
+            },
+            None => SessionInner {
+                id: new_session_id(),
+                data: SessionData::new(),
+                key: Arc::clone(&self.key),
+                previous_id: None,
+                is_new: true,
+                changed: false,
+                issued_token: false,
+            },
+        };
+        Session(Arc::new(RwLock::new(inner)))
+    }
+
+    /// Saves the session to the store if something has been written to it, and returns the
+    /// value of the `Set-Cookie` header to send with the response, if any. The cookie is
+    /// sent when a session starts, as long as it has been written to or has issued a CSRF
+    /// token.
+    pub fn save(&self, session: &Session) -> Option<String> {
+        let mut inner = session.0.write().unwrap();
+        if let Some(previous_id) = inner.previous_id.take() {
+            self.store.remove(&previous_id);
+        }
+        let send_cookie = inner.is_new && (inner.changed || inner.issued_token);
+        if inner.changed {
+            inner.changed = false;
+            self.store.save(&inner.id, inner.data.clone(), self.max_age);
+        }
+        if !send_cookie {
+            return None;
+        }
+        inner.is_new = false;
+        Some(self.cookie(&inner.id))
+    }
+
+    fn cookie(&self, id: &str) -> String {
+        let secure = if self.secure { "; Secure" } else { "" };
+        format!(
+            "{}={id}.{}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{secure}",
+            self.cookie_name,
+            hex(&self.sign(id)),
+            self.max_age.as_secs()
+        )
+    }
+
+    fn sign(&self, id: &str) -> Vec<u8> {
+        sign(&self.key, id)
+    }
+
+    fn verify<'a>(&self, value: &'a str) -> Option<&'a str> {
+        let (id, signature) = value.split_once('.')?;
+        let signature = unhex(signature)?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(id.as_bytes());
+        mac.verify_slice(&signature).ok()?;
+        Some(id)
+    }
+}
+
+/// How long a session lasts unless it is set with [SessionOptions::max_age]: one week.
+pub const DEFAULT_SESSION_MAX_AGE: Duration =
+    Duration::from_secs(60 * 60 * 24 * 7);
+
+/// A visitor’s session. The integrations provide it as context to the pages they render
+/// and to server functions, when they are configured with [SessionOptions].
+/// ```ignore
+/// #[server(Login, "/api")]
+/// pub async fn login(cx: Scope, name: String) -> Result<(), ServerFnError> {
+///     let session = use_context::<Session>(cx)
+///         .ok_or_else(|| ServerFnError::ServerError("no session".into()))?;
+///     session.renew();
+///     session.insert("user", &name)
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Session(Arc<RwLock<SessionInner>>);
+
+struct SessionInner {
+    id: String,
+    data: SessionData,
+    key: Arc<[u8]>,
+    previous_id: Option<String>,
+    is_new: bool,
+    changed: bool,
+    issued_token: bool,
+}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session").finish_non_exhaustive()
+    }
+}
+
+impl Session {
+    /// The ID of the session.
+    pub fn id(&self) -> String {
+        self.0.read().unwrap().id.clone()
+    }
+
+    /// Returns the value stored under the key, if there is one and it can be deserialized
+    /// as a `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let inner = self.0.read().unwrap();
+        serde_json::from_str(inner.data.get(key)?).ok()
+    }
+
+    /// Stores a value under the key, replacing any value already stored under it.
+    pub fn insert<T: Serialize + ?Sized>(
+        &self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), ServerFnError> {
+        let value = serde_json::to_string(value)
+            .map_err(|e| ServerFnError::Serialization(e.to_string()))?;
+        let mut inner = self.0.write().unwrap();
+        inner.data.insert(key.to_string(), value);
+        inner.changed = true;
+        Ok(())
+    }
+
+    /// Removes the value stored under the key.
+    pub fn remove(&self, key: &str) {
+        let mut inner = self.0.write().unwrap();
+        if inner.data.remove(key).is_some() {
+            inner.changed = true;
+        }
+    }
+
+    /// Removes every value, including the CSRF token, and moves the session to a new ID.
+    /// Call it when a user logs out.
+    pub fn clear(&self) {
+        self.0.write().unwrap().data.clear();
+        self.renew();
+    }
+
+    /// Moves the session, with its data, to a new ID. Call it when a user logs in, so that
+    /// an ID someone else learned beforehand can’t be used to act as them.
+    pub fn renew(&self) {
+        let mut inner = self.0.write().unwrap();
+        let previous_id = std::mem::replace(&mut inner.id, new_session_id());
+        if !inner.is_new && inner.previous_id.is_none() {
+            inner.previous_id = Some(previous_id);
+        }
+        inner.is_new = true;
+        inner.changed = true;
+    }
+
+    /// The session’s CSRF token, which is stored JSON-encoded under
+    /// [CSRF_FIELD](crate::CSRF_FIELD) the first time it is needed.
+    ///
+    /// The token is derived from the session’s ID, so a session that hasn’t been stored,
+    /// because nothing else has been written to it, gets the same token on every request.
+    pub fn csrf_token(&self) -> String {
+        let mut inner = self.0.write().unwrap();
+        let token = inner.expected_csrf_token();
+        if !inner.data.contains_key(CSRF_FIELD) {
+            let encoded = serde_json::to_string(&token)
+                .expect("a string can be serialized");
+            inner.data.insert(CSRF_FIELD.to_string(), encoded);
+        }
+        inner.issued_token = true;
+        token
+    }
+
+    /// Whether `token` is the session’s CSRF token.
+    pub fn verify_csrf_token(&self, token: &str) -> bool {
+        let expected = self.0.read().unwrap().expected_csrf_token();
+        // compare every byte, so the time taken doesn't reveal the token
+        expected.len() == token.len()
+            && expected
+                .bytes()
+                .zip(token.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    /// Whether a server function call carries the session’s CSRF token, either in the
+    /// [CSRF_HEADER](crate::CSRF_HEADER) header or, when its arguments are URL-encoded, in
+    /// the [CSRF_FIELD] field of the body.
+    pub fn verify_csrf_request(
+        &self,
+        header: Option<&str>,
+        body: &[u8],
+    ) -> bool {
+        match header {
+            Some(token) => self.verify_csrf_token(token),
+            None => form_urlencoded::parse(body)
+                .find(|(name, _)| name == CSRF_FIELD)
+                .map(|(_, token)| self.verify_csrf_token(&token))
+                .unwrap_or(false),
+        }
+    }
+}
+
+impl SessionInner {
+    /// The stored CSRF token if there is one, or else the token for the session’s ID.
+    fn expected_csrf_token(&self) -> String {
+        self.data
+            .get(CSRF_FIELD)
+            .and_then(|token| serde_json::from_str(token).ok())
+            .unwrap_or_else(|| {
+                hex(&sign(&self.key, &format!("csrf.{}", self.id)))
+            })
+    }
+}
+
+fn sign(key: &[u8], value: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    mac.update(value.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn new_session_id() -> String {
+    random_hex()
+}
+
+fn random_hex() -> String {
+    let mut bytes = [0; 32];
+    getrandom::getrandom(&mut bytes)
+        .expect("couldn't generate a random session ID");
+    hex(&bytes)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn unhex(hex: &str) -> Option<Vec<u8>> {
+    // an odd number of digits leaves the last pair incomplete, so `get` fails
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,19 @@
+use leptos::{leptos_config::AssetManifest, use_context, Scope};
+
+/// Returns the path of the fingerprinted file for the static asset at `path`,
+/// using the [AssetManifest] provided as context, or `path` itself if there is
+/// no manifest or the asset isn't in it.
+///
+/// The server integrations provide the manifest given by
+/// [LeptosOptions::asset_manifest](leptos::LeptosOptions::asset_manifest). To
+/// resolve the same paths in the browser, provide the manifest there too, for
+/// example by parsing it from a file included with `include_str!`.
+///
+/// [`<Stylesheet/>`](crate::Stylesheet) and [`<Script/>`](crate::Script) use this
+/// to resolve their URLs.
+pub fn use_asset(cx: Scope, path: &str) -> String {
+    match use_context::<AssetManifest>(cx) {
+        Some(manifest) => manifest.resolve(path),
+        None => path.to_string(),
+    }
+}
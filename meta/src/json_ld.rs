@@ -0,0 +1,72 @@
+use crate::use_head;
+use leptos::*;
+
+/// Injects a `<script type="application/ld+json">` tag into the document head
+/// containing [structured data](https://developers.google.com/search/docs/appearance/structured-data/intro-structured-data)
+/// about the page, serialized from any [Serializable] value.
+///
+/// The JSON is escaped so that it can't close the `<script>` tag early, even if
+/// it contains user-provided strings. If a `key` is given, the tag replaces one
+/// with the same `key` set by a component higher in the tree.
+///
+/// ```
+/// use leptos::*;
+/// use leptos_meta::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Article {
+///     #[serde(rename = "@context")]
+///     context: String,
+///     #[serde(rename = "@type")]
+///     type_: String,
+///     headline: String,
+/// }
+///
+/// #[component]
+/// fn Post(cx: Scope) -> impl IntoView {
+///     let article = Article {
+///         context: "https://schema.org".to_string(),
+///         type_: "Article".to_string(),
+///         headline: "Hello, world!".to_string(),
+///     };
+///
+///     view! { cx, <JsonLd key="article" data=article/> }
+/// }
+/// ```
+#[component(transparent)]
+pub fn JsonLd<T>(
+    cx: Scope,
+    /// The structured data.
+    data: T,
+    /// An identity for the data. Tags with the same `key` replace one another.
+    #[prop(optional, into)]
+    key: Option<String>,
+    /// An ID for the `<script>` tag.
+    #[prop(optional, into)]
+    id: Option<String>,
+) -> impl IntoView
+where
+    T: Serializable,
+{
+    let meta = use_head(cx);
+    let next_id = meta.tags.get_next_id();
+    let id = id.unwrap_or_else(|| format!("leptos-link-{}", next_id.0));
+    let key = key.map(|key| format!("ld+json={key}"));
+
+    let json = match data.to_json() {
+        Ok(json) => leptos::leptos_dom::ssr::escape_script(&json),
+        Err(e) => {
+            leptos::warn!("could not serialize <JsonLd/> data: {e}");
+            return;
+        }
+    };
+
+    let builder_el = leptos::leptos_dom::html::script(cx)
+        .attr("id", &id)
+        .attr("type", "application/ld+json")
+        .child(json);
+
+    meta.tags
+        .register_with_key(cx, key, id, builder_el.into_any());
+}
@@ -46,7 +46,7 @@
 
 use cfg_if::cfg_if;
 use leptos::{
-    leptos_dom::{debug_warn, html::AnyElement},
+    leptos_dom::{debug_warn, html::AnyElement, ssr::Nonce},
     *,
 };
 use std::{
@@ -58,22 +58,32 @@ use std::{
 #[cfg(any(feature = "csr", feature = "hydrate"))]
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 
+mod asset;
 mod body;
 mod html;
+mod json_ld;
 mod link;
 mod meta_tags;
+mod open_graph;
+mod scoped_style;
 mod script;
 mod style;
 mod stylesheet;
 mod title;
+mod twitter_card;
+pub use asset::*;
 pub use body::*;
 pub use html::*;
+pub use json_ld::*;
 pub use link::*;
 pub use meta_tags::*;
+pub use open_graph::*;
+pub use scoped_style::*;
 pub use script::*;
 pub use style::*;
 pub use stylesheet::*;
 pub use title::*;
+pub use twitter_card::*;
 
 /// Contains the current state of meta tags. To access it, you can use [use_head].
 ///
@@ -89,9 +99,33 @@ pub struct MetaContext {
     pub body: BodyContext,
     /// Other metadata tags.
     pub tags: MetaTagsContext,
+    /// The URLs of the stylesheets used by the page.
+    #[cfg(feature = "ssr")]
+    stylesheets: Rc<RefCell<Vec<String>>>,
+    /// The class, CSS and nonce of each style added by [use_scoped_style].
+    scoped_styles: Rc<RefCell<Vec<ScopedStyle>>>,
+    /// The title and tags already sent to the browser while streaming.
+    #[cfg(feature = "ssr")]
+    streamed: Rc<RefCell<StreamedHead>>,
+}
+
+/// The class, scoped CSS and nonce of a style added by [use_scoped_style].
+type ScopedStyle = (String, String, Option<Nonce>);
+
+#[cfg(feature = "ssr")]
+#[derive(Clone, Default, Debug)]
+struct StreamedHead {
+    title: Option<String>,
+    tags: Vec<String>,
+    scoped_styles: usize,
 }
 
 /// Manages all of the element created by components.
+///
+/// Tags can be registered with an identity key (like the `name` of a `<meta>` tag).
+/// Only the most recently registered tag for each key, which belongs to the most
+/// deeply nested component, is rendered. When its scope is cleaned up, the tag
+/// registered before it for the same key is shown again.
 #[derive(Clone, Default)]
 pub struct MetaTagsContext {
     next_id: Rc<Cell<MetaTagId>>,
@@ -104,6 +138,8 @@ pub struct MetaTagsContext {
             >,
         >,
     >,
+    /// The IDs of the tags registered for each identity key, oldest first.
+    keyed: Rc<RefCell<HashMap<String, Vec<String>>>>,
 }
 
 impl std::fmt::Debug for MetaTagsContext {
@@ -114,13 +150,28 @@ impl std::fmt::Debug for MetaTagsContext {
 
 impl MetaTagsContext {
     /// Converts metadata tags into an HTML string.
-    #[cfg(any(feature = "ssr", docs))]
+    #[cfg(any(feature = "ssr", doc))]
     pub fn as_string(&self) -> String {
+        self.rendered().into_iter().map(|(_, html)| html).collect()
+    }
+
+    /// The ID and HTML of each tag that should be rendered.
+    #[cfg(any(feature = "ssr", doc))]
+    fn rendered(&self) -> Vec<(String, String)> {
+        let keyed = self.keyed.borrow();
+        let is_shadowed = |id: &String| {
+            keyed
+                .values()
+                .any(|ids| ids.contains(id) && ids.last() != Some(id))
+        };
         self.els
             .borrow()
             .iter()
-            .map(|(_, (builder_el, cx, _))| {
-                builder_el.clone().into_view(*cx).render_to_string(*cx)
+            .filter(|(id, _)| !is_shadowed(id))
+            .map(|(id, (builder_el, cx, _))| {
+                let html =
+                    builder_el.clone().into_view(*cx).render_to_string(*cx);
+                (id.clone(), html.to_string())
             })
             .collect()
     }
@@ -132,6 +183,26 @@ impl MetaTagsContext {
         id: String,
         builder_el: HtmlElement<AnyElement>,
     ) {
+        self.register_with_key(cx, None, id, builder_el)
+    }
+
+    #[doc(hidden)]
+    pub fn register_with_key(
+        &self,
+        cx: Scope,
+        key: Option<String>,
+        id: String,
+        builder_el: HtmlElement<AnyElement>,
+    ) {
+        // the tag this one replaces, if any
+        let shadowed = key.as_ref().and_then(|key| {
+            let mut keyed = self.keyed.borrow_mut();
+            let ids = keyed.entry(key.clone()).or_default();
+            let prev = ids.last().cloned();
+            ids.push(id.clone());
+            prev
+        });
+
         cfg_if! {
             if #[cfg(any(feature = "csr", feature = "hydrate"))] {
                 use leptos::document;
@@ -151,14 +222,30 @@ impl MetaTagsContext {
                     }
                 });
 
+                let shadowed = shadowed.and_then(|id| {
+                    self.els.borrow().get(&id).and_then(|(_, _, el)| el.clone())
+                });
+                if let Some(shadowed) = shadowed {
+                    let head = document().head().unwrap_throw();
+                    _ = head.remove_child(&shadowed);
+                }
+
                 on_cleanup(cx, {
                     let el = el.clone();
-                    let els = self.els.clone();
+                    let this = self.clone();
                     let id = id.clone();
                     move || {
                         let head = document().head().unwrap_throw();
                         _ = head.remove_child(&el);
-                        els.borrow_mut().remove(&id);
+                        this.els.borrow_mut().remove(&id);
+                        // show the tag this one was replacing again
+                        let restored =
+                            this.unregister_key(key.as_deref(), &id);
+                        if let Some((_, _, Some(el))) = restored
+                            .and_then(|id| this.els.borrow().get(&id).cloned())
+                        {
+                            _ = head.append_child(&el);
+                        }
                     }
                 });
 
@@ -168,10 +255,35 @@ impl MetaTagsContext {
                     .insert(id, (builder_el.into_any(), cx, Some(el)));
 
             } else {
+                _ = shadowed;
+
+                on_cleanup(cx, {
+                    let this = self.clone();
+                    let id = id.clone();
+                    move || {
+                        this.els.borrow_mut().remove(&id);
+                        this.unregister_key(key.as_deref(), &id);
+                    }
+                });
+
                 self.els.borrow_mut().insert(id, (builder_el, cx, None));
             }
         }
     }
+
+    /// Removes the tag with this `id` from the tags registered for `key`. If it was
+    /// the one being shown, returns the ID of the tag that should be shown instead.
+    fn unregister_key(&self, key: Option<&str>, id: &str) -> Option<String> {
+        let mut keyed = self.keyed.borrow_mut();
+        let ids = keyed.get_mut(key?)?;
+        let was_shown = ids.last().map(String::as_str) == Some(id);
+        ids.retain(|other| other != id);
+        let restored = ids.last().cloned().filter(|_| was_shown);
+        if ids.is_empty() {
+            keyed.remove(key?);
+        }
+        restored
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -189,9 +301,27 @@ impl MetaTagsContext {
 /// Provides a [MetaContext], if there is not already one provided. This ensures that you can provide it
 /// at the highest possible level, without overwriting a [MetaContext] that has already been provided
 /// (for example, by a server-rendering integration.)
+///
+/// When rendering on the server, this also provides a
+/// [FragmentSuffix](leptos::leptos_dom::ssr::FragmentSuffix), so that metadata set
+/// inside a `<Suspense/>` that resolves after the `<head>` has been streamed is sent
+/// along with the `<Suspense/>`’s HTML. (See [MetaContext::dehydrate_changes].)
 pub fn provide_meta_context(cx: Scope) {
     if use_context::<MetaContext>(cx).is_none() {
-        provide_context(cx, MetaContext::new());
+        let meta = MetaContext::new();
+        #[cfg(feature = "ssr")]
+        provide_context(
+            cx,
+            leptos::leptos_dom::ssr::FragmentSuffix::new({
+                let meta = meta.clone();
+                move || {
+                    meta.dehydrate_changes(
+                        leptos::leptos_dom::ssr::use_nonce(cx).as_ref(),
+                    )
+                }
+            }),
+        );
+        provide_context(cx, meta);
     }
 }
 
@@ -227,6 +357,14 @@ impl MetaContext {
         Default::default()
     }
 
+    /// The URLs of every [`<Stylesheet/>`](crate::Stylesheet) that has been rendered,
+    /// which the server integrations use to tell the browser to start loading them
+    /// as early as possible.
+    #[cfg(feature = "ssr")]
+    pub fn stylesheets(&self) -> Vec<String> {
+        self.stylesheets.borrow().clone()
+    }
+
     #[cfg(feature = "ssr")]
     /// Converts the existing metadata tags into HTML that can be injected into the document head.
     ///
@@ -266,16 +404,124 @@ impl MetaContext {
         let mut tags = String::new();
 
         // Title
-        if let Some(title) = self.title.as_string() {
+        let title = self.title.as_string();
+        if let Some(title) = &title {
             tags.push_str("<title>");
-            tags.push_str(&title);
+            tags.push_str(title);
             tags.push_str("</title>");
         }
-        tags.push_str(&self.tags.as_string());
+        let rendered = self.tags.rendered();
+        for (_, html) in &rendered {
+            tags.push_str(html);
+        }
+        let scoped_styles = self.scoped_styles.borrow();
+        for style in scoped_styles.iter() {
+            tags.push_str(&scoped_style_tag(style));
+        }
+
+        *self.streamed.borrow_mut() = StreamedHead {
+            title,
+            tags: rendered.into_iter().map(|(id, _)| id).collect(),
+            scoped_styles: scoped_styles.len(),
+        };
 
         HydrationCtx::continue_from(prev_key);
         tags
     }
+
+    #[cfg(feature = "ssr")]
+    /// Generates a `<script>` that brings the document head up to date with any metadata
+    /// that has changed since it was last rendered by [MetaContext::dehydrate] or this function,
+    /// or an empty string if nothing has changed.
+    ///
+    /// When streaming, the `<head>` is sent before any `<Suspense/>` has resolved. Sending
+    /// this after each `<Suspense/>` fragment means that a `<Title/>` or `<Meta/>` inside it
+    /// is applied as soon as it arrives, even before the app has hydrated. If a
+    /// [Nonce] is given, it is added to the `<script>`.
+    pub fn dehydrate_changes(&self, nonce: Option<&Nonce>) -> String {
+        use leptos::leptos_dom::HydrationCtx;
+
+        let prev_key = HydrationCtx::peek();
+        let title = self.title.as_string();
+        let rendered = self.tags.rendered();
+        HydrationCtx::continue_from(prev_key);
+
+        let mut streamed = self.streamed.borrow_mut();
+        let mut script = String::new();
+        if title != streamed.title {
+            if let Some(title) = &title {
+                script.push_str(&format!(
+                    "document.title={};",
+                    escape_js_string(title)
+                ));
+            }
+        }
+        for id in &streamed.tags {
+            if !rendered.iter().any(|(rendered, _)| rendered == id) {
+                script.push_str(&format!(
+                    "document.getElementById({})?.remove();",
+                    escape_js_string(id)
+                ));
+            }
+        }
+        for (id, html) in &rendered {
+            if !streamed.tags.contains(id) {
+                script.push_str(&format!(
+                    "document.head.insertAdjacentHTML(\"beforeend\",{});",
+                    escape_js_string(html)
+                ));
+            }
+        }
+
+        let scoped_styles = self.scoped_styles.borrow();
+        for style in scoped_styles.iter().skip(streamed.scoped_styles) {
+            script.push_str(&format!(
+                "document.head.insertAdjacentHTML(\"beforeend\",{});",
+                escape_js_string(&scoped_style_tag(style))
+            ));
+        }
+
+        *streamed = StreamedHead {
+            title,
+            tags: rendered.into_iter().map(|(id, _)| id).collect(),
+            scoped_styles: scoped_styles.len(),
+        };
+
+        if script.is_empty() {
+            script
+        } else {
+            let nonce = nonce.map(Nonce::attr).unwrap_or_default();
+            format!("<script{nonce}>{script}</script>")
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn scoped_style_tag((class, css, nonce): &ScopedStyle) -> String {
+    let nonce = nonce.as_ref().map(Nonce::attr).unwrap_or_default();
+    format!("<style id=\"{class}\"{nonce}>{css}</style>")
+}
+
+/// Quotes a string as a JavaScript string literal that is safe to include
+/// in a `<script>` element.
+#[cfg(feature = "ssr")]
+fn escape_js_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                quoted.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    leptos::leptos_dom::ssr::escape_script(&quoted)
 }
 
 /// Describes a value that is either a static or a reactive string, i.e.,
@@ -3,6 +3,9 @@ use leptos::*;
 
 /// Injects an [HTMLLinkElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLLinkElement) into the document
 /// head, accepting any of the valid attributes for that tag.
+///
+/// A `<Link rel="canonical"/>` replaces any other canonical link, so that only the
+/// one in the most deeply nested component is rendered.
 /// ```
 /// use leptos::*;
 /// use leptos_meta::*;
@@ -84,6 +87,8 @@ pub fn Link(
     let meta = use_head(cx);
     let next_id = meta.tags.get_next_id();
     let id = id.unwrap_or_else(|| format!("leptos-link-{}", next_id.0));
+    let key = (rel.as_deref() == Some("canonical"))
+        .then(|| "rel=canonical".to_string());
 
     let builder_el = leptos::leptos_dom::html::link(cx)
         .attr("id", &id)
@@ -105,5 +110,6 @@ pub fn Link(
         .attr("type", type_)
         .attr("blocking", blocking);
 
-    meta.tags.register(cx, id, builder_el.into_any());
+    meta.tags
+        .register_with_key(cx, key, id, builder_el.into_any());
 }
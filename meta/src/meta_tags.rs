@@ -4,6 +4,11 @@ use leptos::{component, IntoView, Scope};
 /// Injects an [HTMLMetaElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLMetaElement) into the document
 /// head to set metadata
 ///
+/// Tags with the same `name`, `property`, `http-equiv` or `charset` replace one
+/// another, so a page can override a tag set by its layout: the most deeply nested
+/// `<Meta/>` is the one that is rendered, and the one it replaced comes back when
+/// it is unmounted.
+///
 /// ```
 /// use leptos::*;
 /// use leptos_meta::*;
@@ -16,6 +21,7 @@ use leptos::{component, IntoView, Scope};
 ///     <main>
 ///       <Meta charset="utf-8"/>
 ///       <Meta name="description" content="A Leptos fan site."/>
+///       <Meta property="og:title" content="Leptos"/>
 ///       <Meta http_equiv="refresh" content="3;url=https://github.com/leptos-rs/leptos"/>
 ///     </main>
 ///   }
@@ -30,6 +36,9 @@ pub fn Meta(
     /// The [`name`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/meta#attr-name) attribute.
     #[prop(optional, into)]
     name: Option<TextProp>,
+    /// The `property` attribute, used by [Open Graph](https://ogp.me/) tags.
+    #[prop(optional, into)]
+    property: Option<TextProp>,
     /// The [`http-equiv`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/meta#attr-http-equiv) attribute.
     #[prop(optional, into)]
     http_equiv: Option<TextProp>,
@@ -41,11 +50,31 @@ pub fn Meta(
     let next_id = meta.tags.get_next_id();
     let id = format!("leptos-link-{}", next_id.0);
 
+    // tags that describe the same thing replace one another
+    let key = if charset.is_some() {
+        Some("charset".to_string())
+    } else {
+        [
+            ("name", &name),
+            ("property", &property),
+            ("http-equiv", &http_equiv),
+        ]
+        .into_iter()
+        .find_map(|(attr, value)| {
+            value
+                .as_ref()
+                .map(|value| format!("{attr}={}", value.get()))
+        })
+    };
+
     let builder_el = leptos::leptos_dom::html::meta(cx)
+        .attr("id", &id)
         .attr("charset", move || charset.as_ref().map(|v| v.get()))
         .attr("name", move || name.as_ref().map(|v| v.get()))
+        .attr("property", move || property.as_ref().map(|v| v.get()))
         .attr("http-equiv", move || http_equiv.as_ref().map(|v| v.get()))
         .attr("content", move || content.as_ref().map(|v| v.get()));
 
-    meta.tags.register(cx, id, builder_el.into_any());
+    meta.tags
+        .register_with_key(cx, key, id, builder_el.into_any());
 }
@@ -0,0 +1,71 @@
+use crate::{Meta, MetaProps, TextProp};
+use leptos::{component, view, IntoView, Scope};
+
+/// Injects the [Open Graph](https://ogp.me/) `<meta property="og:…">` tags that
+/// control how a page is previewed when it is shared, for each of the props that
+/// is set.
+///
+/// Like any [`<Meta/>`](crate::Meta), each tag replaces the same tag set by a
+/// component higher in the tree, so a layout can provide defaults that each
+/// page overrides.
+///
+/// ```
+/// use leptos::*;
+/// use leptos_meta::*;
+///
+/// #[component]
+/// fn Post(cx: Scope) -> impl IntoView {
+///     view! { cx,
+///       <OpenGraph
+///         title="Hello, world!"
+///         type_="article"
+///         url="https://example.com/posts/hello"
+///         image="https://example.com/posts/hello.png"
+///       />
+///     }
+/// }
+/// ```
+#[component(transparent)]
+pub fn OpenGraph(
+    cx: Scope,
+    /// The `og:title` of the page.
+    #[prop(optional, into)]
+    title: Option<TextProp>,
+    /// The `og:description` of the page.
+    #[prop(optional, into)]
+    description: Option<TextProp>,
+    /// The `og:type` of the page, like `website` or `article`.
+    #[prop(optional, into)]
+    type_: Option<TextProp>,
+    /// The canonical `og:url` of the page.
+    #[prop(optional, into)]
+    url: Option<TextProp>,
+    /// The URL of the `og:image` shown in the preview.
+    #[prop(optional, into)]
+    image: Option<TextProp>,
+    /// A description of the image, for `og:image:alt`.
+    #[prop(optional, into)]
+    image_alt: Option<TextProp>,
+    /// The `og:site_name` of the site the page is part of.
+    #[prop(optional, into)]
+    site_name: Option<TextProp>,
+    /// The `og:locale` of the page, like `en_US`.
+    #[prop(optional, into)]
+    locale: Option<TextProp>,
+) -> impl IntoView {
+    let tags = [
+        ("og:title", title),
+        ("og:description", description),
+        ("og:type", type_),
+        ("og:url", url),
+        ("og:image", image),
+        ("og:image:alt", image_alt),
+        ("og:site_name", site_name),
+        ("og:locale", locale),
+    ];
+    for (property, content) in tags {
+        if let Some(content) = content {
+            _ = view! { cx, <Meta property content/> };
+        }
+    }
+}
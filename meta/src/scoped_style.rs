@@ -0,0 +1,189 @@
+use crate::use_head;
+use leptos::Scope;
+
+/// Attaches CSS to a component, scoped to the element that has the returned class
+/// and its descendants, so that its selectors can't affect the rest of the page.
+///
+/// Each selector is rewritten to only match inside the element with the class. Use
+/// `:scope` to match that element itself. Rules inside `@media`, `@supports`,
+/// `@container` and `@layer` are scoped too, while other at-rules, like
+/// `@keyframes` and `@font-face`, are left as they are.
+///
+/// The class is generated from a hash of the CSS, so every instance of a component
+/// shares the same `<style>` tag. When rendering on the server, the styles are
+/// collected into the `<head>` by [MetaContext::dehydrate](crate::MetaContext::dehydrate),
+/// with the response’s [Nonce](leptos::leptos_dom::ssr::Nonce), if there is one.
+/// In the browser, the `<style>` is added the first time a component that uses it
+/// is created, unless it was already rendered on the server.
+///
+/// In the [view](leptos::view) macro, the `scoped_style` attribute calls this and
+/// adds the class to the element it is on:
+/// `<section scoped_style="h2 { font-size: 1.2rem; }">`.
+///
+/// ```
+/// use leptos::*;
+/// use leptos_meta::*;
+///
+/// #[component]
+/// fn Card(cx: Scope, children: Children) -> impl IntoView {
+///     let class = use_scoped_style(
+///         cx,
+///         r#"
+///         :scope { border: 1px solid gray; }
+///         h2 { font-size: 1.2rem; }
+///         "#,
+///     );
+///
+///     view! { cx, <section class=class>{children(cx)}</section> }
+/// }
+/// ```
+pub fn use_scoped_style(cx: Scope, css: &str) -> String {
+    let class = format!("leptos-{:08x}", fnv1a(css));
+    let meta = use_head(cx);
+
+    let mut styles = meta.scoped_styles.borrow_mut();
+    if styles.iter().any(|(existing, _, _)| existing == &class) {
+        return class;
+    }
+    // `</` would end the <style> element early; `\/` is the same in CSS
+    let css = scope_css(&class, css).replace("</", "<\\/");
+
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    {
+        use wasm_bindgen::UnwrapThrowExt;
+
+        let document = leptos::document();
+        if document.get_element_by_id(&class).is_none() {
+            let el = document.create_element("style").unwrap_throw();
+            el.set_id(&class);
+            el.set_text_content(Some(&css));
+            document
+                .head()
+                .unwrap_throw()
+                .append_child(&el)
+                .unwrap_throw();
+        }
+    }
+
+    styles.push((class.clone(), css, leptos::ssr::use_nonce(cx)));
+    class
+}
+
+/// A 32-bit FNV-1a hash, which is the same on the server and in the browser.
+fn fnv1a(value: &str) -> u32 {
+    value.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
+
+/// Rewrites every selector in `css` to only match elements inside the one with `class`.
+fn scope_css(class: &str, css: &str) -> String {
+    let css = strip_comments(css);
+    let mut scoped = String::with_capacity(css.len());
+    let mut rest = css.as_str();
+
+    while let Some(open) = rest.find('{') {
+        let mut prelude = rest[..open].trim();
+        let close = matching_brace(rest, open);
+        let block = &rest[open + 1..close];
+
+        // statements like `@import url(…);` are kept as they are
+        if let Some(end) = prelude.rfind(';') {
+            scoped.push_str(&prelude[..=end]);
+            prelude = prelude[end + 1..].trim();
+        }
+
+        if let Some(at_rule) = prelude.strip_prefix('@') {
+            let name = at_rule.split_whitespace().next().unwrap_or_default();
+            scoped.push_str(prelude);
+            scoped.push('{');
+            if matches!(name, "media" | "supports" | "container" | "layer") {
+                scoped.push_str(&scope_css(class, block));
+            } else {
+                scoped.push_str(block.trim());
+            }
+            scoped.push('}');
+        } else {
+            let selectors = split_selectors(prelude)
+                .into_iter()
+                .map(|selector| scope_selector(class, selector))
+                .collect::<Vec<_>>()
+                .join(",");
+            scoped.push_str(&selectors);
+            scoped.push('{');
+            scoped.push_str(block.trim());
+            scoped.push('}');
+        }
+
+        rest = rest.get(close + 1..).unwrap_or_default();
+    }
+    scoped.push_str(rest.trim());
+
+    scoped
+}
+
+fn scope_selector(class: &str, selector: &str) -> String {
+    let selector = selector.trim();
+    if selector.contains(":scope") {
+        selector.replace(":scope", &format!(".{class}"))
+    } else {
+        format!(".{class} {selector}")
+    }
+}
+
+/// Splits a selector list on the commas that aren't inside parentheses.
+fn split_selectors(prelude: &str) -> Vec<&str> {
+    let mut selectors = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in prelude.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                selectors.push(&prelude[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    selectors.push(&prelude[start..]);
+    selectors
+}
+
+/// The index of the `}` that closes the `{` at `open`, or the end of `css` if
+/// it's never closed.
+fn matching_brace(css: &str, open: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    for (idx, c) in css[open..].char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => {}
+            ('{', None) => depth += 1,
+            ('}', None) => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + idx;
+                }
+            }
+            _ => {}
+        }
+    }
+    css.len()
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    stripped
+}
@@ -1,5 +1,5 @@
-use crate::use_head;
-use leptos::*;
+use crate::{use_asset, use_head};
+use leptos::{ssr::use_nonce, *};
 
 /// Injects an [HTMLScriptElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLScriptElement) into the document
 /// head, accepting any of the valid attributes for that tag.
@@ -45,12 +45,14 @@ pub fn Script(
     #[prop(optional, into)]
     nomodule: Option<String>,
     /// The [`nonce`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/script#attr-nonce) attribute.
+    /// Defaults to the response’s [Nonce](leptos::leptos_dom::ssr::Nonce), if one has been provided.
     #[prop(optional, into)]
     nonce: Option<String>,
     /// The [`referrerpolicy`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/script#attr-referrerpolicy) attribute.
     #[prop(optional, into)]
     referrerpolicy: Option<String>,
-    /// The [`src`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/script#attr-src) attribute.
+    /// The [`src`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/script#attr-src) attribute,
+    /// which is resolved to a fingerprinted file with [use_asset](crate::use_asset).
     #[prop(optional, into)]
     src: Option<String>,
     /// The [`type`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/script#attr-type) attribute.
@@ -75,9 +77,12 @@ pub fn Script(
         .attr("fetchpriority ", fetchpriority)
         .attr("integrity", integrity)
         .attr("nomodule", nomodule)
-        .attr("nonce", nonce)
+        .attr(
+            "nonce",
+            nonce.or_else(|| use_nonce(cx).map(|nonce| nonce.to_string())),
+        )
         .attr("referrerpolicy", referrerpolicy)
-        .attr("src", src)
+        .attr("src", src.map(|src| use_asset(cx, &src)))
         .attr("type", type_)
         .attr("blocking", blocking);
     let builder_el = if let Some(children) = children {
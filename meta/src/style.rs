@@ -1,5 +1,5 @@
 use crate::use_head;
-use leptos::*;
+use leptos::{ssr::use_nonce, *};
 
 /// Injects an [HTMLStyleElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLStyleElement) into the document
 /// head, accepting any of the valid attributes for that tag.
@@ -30,6 +30,7 @@ pub fn Style(
     #[prop(optional, into)]
     media: Option<String>,
     /// The [`nonce`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/style#attr-nonce) attribute.
+    /// Defaults to the response’s [Nonce](leptos::leptos_dom::ssr::Nonce), if one has been provided.
     #[prop(optional, into)]
     nonce: Option<String>,
     /// The [`title`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/style#attr-title) attribute.
@@ -49,7 +50,10 @@ pub fn Style(
     let builder_el = leptos::leptos_dom::html::style(cx)
         .attr("id", &id)
         .attr("media", media)
-        .attr("nonce", nonce)
+        .attr(
+            "nonce",
+            nonce.or_else(|| use_nonce(cx).map(|nonce| nonce.to_string())),
+        )
         .attr("title", title)
         .attr("blocking", blocking);
     let builder_el = if let Some(children) = children {
@@ -1,9 +1,13 @@
-use crate::{Link, LinkProps};
+use crate::{use_asset, Link, LinkProps};
 use leptos::*;
 
 /// Injects an [HTMLLinkElement](https://developer.mozilla.org/en-US/docs/Web/API/HTMLLinkElement) into the document
 /// head that loads a stylesheet from the URL given by the `href` property.
 ///
+/// The URL is resolved to a fingerprinted file with [use_asset], and when rendered
+/// on the server, the integrations send a `Link` header so that the browser starts
+/// loading the stylesheet before it has parsed the `<head>`.
+///
 /// ```
 /// use leptos::*;
 /// use leptos_meta::*;
@@ -29,6 +33,16 @@ pub fn Stylesheet(
     #[prop(optional, into)]
     id: Option<String>,
 ) -> impl IntoView {
+    let href = use_asset(cx, &href);
+    #[cfg(feature = "ssr")]
+    {
+        let meta = crate::use_head(cx);
+        let mut stylesheets = meta.stylesheets.borrow_mut();
+        if !stylesheets.contains(&href) {
+            stylesheets.push(href.clone());
+        }
+    }
+
     if let Some(id) = id {
         view! { cx,
             <Link id rel="stylesheet" href/>
@@ -0,0 +1,62 @@
+use crate::{Meta, MetaProps, TextProp};
+use leptos::{component, view, IntoView, Scope};
+
+/// Injects the [Twitter Card](https://developer.twitter.com/en/docs/twitter-for-websites/cards/overview/markup)
+/// `<meta name="twitter:…">` tags that control how a page is previewed when it is
+/// shared, for each of the props that is set.
+///
+/// Like any [`<Meta/>`](crate::Meta), each tag replaces the same tag set by a
+/// component higher in the tree. Twitter falls back to the [`<OpenGraph/>`](crate::OpenGraph)
+/// tags for anything that isn't set here.
+///
+/// ```
+/// use leptos::*;
+/// use leptos_meta::*;
+///
+/// #[component]
+/// fn Post(cx: Scope) -> impl IntoView {
+///     view! { cx,
+///       <TwitterCard card="summary_large_image" site="@leptos_rs"/>
+///     }
+/// }
+/// ```
+#[component(transparent)]
+pub fn TwitterCard(
+    cx: Scope,
+    /// The `twitter:card` type, like `summary` or `summary_large_image`.
+    #[prop(optional, into)]
+    card: Option<TextProp>,
+    /// The `@username` of the site, for `twitter:site`.
+    #[prop(optional, into)]
+    site: Option<TextProp>,
+    /// The `@username` of the author, for `twitter:creator`.
+    #[prop(optional, into)]
+    creator: Option<TextProp>,
+    /// The `twitter:title` of the page.
+    #[prop(optional, into)]
+    title: Option<TextProp>,
+    /// The `twitter:description` of the page.
+    #[prop(optional, into)]
+    description: Option<TextProp>,
+    /// The URL of the `twitter:image` shown in the preview.
+    #[prop(optional, into)]
+    image: Option<TextProp>,
+    /// A description of the image, for `twitter:image:alt`.
+    #[prop(optional, into)]
+    image_alt: Option<TextProp>,
+) -> impl IntoView {
+    let tags = [
+        ("twitter:card", card),
+        ("twitter:site", site),
+        ("twitter:creator", creator),
+        ("twitter:title", title),
+        ("twitter:description", description),
+        ("twitter:image", image),
+        ("twitter:image:alt", image_alt),
+    ];
+    for (name, content) in tags {
+        if let Some(content) = content {
+            _ = view! { cx, <Meta name content/> };
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use leptos::*;
+        use leptos_meta::*;
+
+        #[test]
+        fn nested_meta_replaces_outer() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+
+                _ = view! { cx,
+                    <Meta name="description" content="Layout"/>
+                    <Meta property="og:title" content="Layout"/>
+                    <Meta name="author" content="Alice"/>
+                };
+                let (_, disposer) = cx.run_child_scope(|cx| {
+                    _ = view! { cx,
+                        <Meta name="description" content="Page"/>
+                        <Meta property="og:title" content="Page"/>
+                    };
+                });
+
+                let head = use_head(cx).tags.as_string();
+                assert_eq!(head.matches("name=\"description\"").count(), 1);
+                assert_eq!(head.matches("property=\"og:title\"").count(), 1);
+                assert!(head.contains("content=\"Page\""));
+                assert!(!head.contains("content=\"Layout\""));
+                assert!(head.contains("content=\"Alice\""));
+
+                disposer.dispose();
+
+                let head = use_head(cx).tags.as_string();
+                assert_eq!(head.matches("name=\"description\"").count(), 1);
+                assert!(head.contains("content=\"Layout\""));
+                assert!(!head.contains("content=\"Page\""));
+            });
+        }
+
+        #[test]
+        fn nested_canonical_link_replaces_outer() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+
+                _ = view! { cx,
+                    <Link rel="canonical" href="https://example.com/"/>
+                    <Link rel="stylesheet" href="/a.css"/>
+                    <Link rel="stylesheet" href="/b.css"/>
+                };
+                _ = cx.run_child_scope(|cx| {
+                    _ = view! { cx,
+                        <Link rel="canonical" href="https://example.com/post"/>
+                    };
+                });
+
+                let head = use_head(cx).tags.as_string();
+                assert_eq!(head.matches("rel=\"canonical\"").count(), 1);
+                assert!(head.contains("https://example.com/post"));
+                assert_eq!(head.matches("rel=\"stylesheet\"").count(), 2);
+            });
+        }
+
+        #[test]
+        fn open_graph_overrides_layout_defaults() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+
+                _ = view! { cx, <OpenGraph title="Site" site_name="Site"/> };
+                _ = cx.run_child_scope(|cx| {
+                    _ = view! { cx, <OpenGraph title="Post" type_="article"/> };
+                });
+
+                let head = use_head(cx).tags.as_string();
+                assert_eq!(head.matches("property=\"og:title\"").count(), 1);
+                assert!(head.contains("content=\"Post\""));
+                assert!(head.contains("property=\"og:site_name\""));
+                assert!(head.contains("property=\"og:type\""));
+            });
+        }
+
+        #[test]
+        fn json_ld_escapes_script_content() {
+            #[derive(serde::Serialize, serde::Deserialize)]
+            struct Data {
+                name: String,
+            }
+
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+
+                _ = view! { cx,
+                    <JsonLd key="page" data=Data { name: "</script><b>".into() }/>
+                };
+
+                let head = use_head(cx).tags.as_string();
+                assert!(head.contains("type=\"application/ld+json\""));
+                assert!(head.contains(
+                    r#"{"name":"\u003c/script\u003e\u003cb\u003e"}"#
+                ));
+                assert_eq!(head.matches("</script>").count(), 1);
+            });
+        }
+
+        #[test]
+        fn changes_after_dehydrate_are_patched() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+                let meta = use_head(cx);
+
+                _ = view! { cx,
+                    <Title text="Loading"/>
+                    <Meta name="description" content="Layout"/>
+                };
+                assert!(meta.dehydrate().contains("<title>Loading</title>"));
+                assert_eq!(meta.dehydrate_changes(None), "");
+
+                // as if a <Suspense/> had resolved
+                _ = cx.run_child_scope(|cx| {
+                    _ = view! { cx,
+                        <Title text="Post </script>"/>
+                        <Meta name="description" content="Post"/>
+                    };
+                });
+
+                let patch = meta.dehydrate_changes(None);
+                assert!(patch.starts_with("<script>"));
+                assert!(patch.contains(r#"document.title="Post \u003c/script\u003e";"#));
+                assert!(patch.contains(".remove();"));
+                assert!(patch.contains("insertAdjacentHTML"));
+                assert_eq!(patch.matches("</script>").count(), 1);
+                assert_eq!(meta.dehydrate_changes(None), "");
+            });
+        }
+
+        #[test]
+        fn stylesheet_uses_fingerprinted_asset() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+                provide_context(
+                    cx,
+                    leptos_config::AssetManifest::from_json(
+                        r#"{ "style/main.css": "style/main.8d1e.css" }"#,
+                    )
+                    .unwrap(),
+                );
+
+                _ = view! { cx, <Stylesheet href="/style/main.css"/> };
+                _ = view! { cx, <Stylesheet href="/style/main.css"/> };
+
+                let meta = use_head(cx);
+                assert!(meta
+                    .tags
+                    .as_string()
+                    .contains("href=\"/style/main.8d1e.css\""));
+                assert_eq!(meta.stylesheets(), vec!["/style/main.8d1e.css"]);
+            });
+        }
+
+        #[test]
+        fn scoped_styles_are_rewritten_and_deduplicated() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+                let css = r#"
+                    /* the card itself */
+                    :scope { display: flex; }
+                    h2, p:not(.a, .b) { color: red; }
+                    @media (min-width: 40em) { h2 { font-size: 2em; } }
+                    @keyframes spin { from { opacity: 0; } to { opacity: 1; } }
+                "#;
+
+                let class = use_scoped_style(cx, css);
+                assert_eq!(use_scoped_style(cx, css), class);
+                assert!(class.starts_with("leptos-"));
+
+                let head = use_head(cx).dehydrate();
+                assert_eq!(head.matches("<style").count(), 1);
+                assert!(head.contains(&format!("<style id=\"{class}\">")));
+                assert!(head.contains(&format!(".{class}{{display: flex;}}")));
+                assert!(head.contains(&format!(
+                    ".{class} h2,.{class} p:not(.a, .b){{color: red;}}"
+                )));
+                assert!(head.contains(&format!(
+                    "@media (min-width: 40em){{.{class} h2{{font-size: 2em;}}}}"
+                )));
+                assert!(head.contains("@keyframes spin{from { opacity: 0; }"));
+                assert!(!head.contains("the card itself"));
+            });
+        }
+
+        #[test]
+        fn scoped_style_attribute_adds_class() {
+            run_scope(create_runtime(), |cx| {
+                provide_meta_context(cx);
+                let css = "h2 { color: gray; }";
+
+                let html = view! { cx,
+                    <section class="card" scoped_style=css>
+                        <h2>"Title"</h2>
+                    </section>
+                    <section scoped_style=css/>
+                }
+                .into_view(cx)
+                .render_to_string(cx);
+
+                let class = use_scoped_style(cx, css);
+                assert!(html.contains(&format!("class=\"card {class}\"")));
+                assert!(html.contains(&format!("class=\" {class}\"")));
+                assert!(!html.contains("scoped_style"));
+
+                let head = use_head(cx).dehydrate();
+                assert_eq!(head.matches("<style").count(), 1);
+                assert!(head.contains(&format!(".{class} h2{{color: gray;}}")));
+            });
+        }
+
+        #[test]
+        fn inline_scripts_and_styles_use_nonce() {
+            run_scope(create_runtime(), |cx| {
+                let nonce = ssr::Nonce::new();
+                provide_context(cx, nonce.clone());
+                provide_meta_context(cx);
+                let meta = use_head(cx);
+                let attr = format!("nonce=\"{nonce}\"");
+
+                _ = view! { cx,
+                    <Script>"console.log('hi')"</Script>
+                    <Style>"body { margin: 0; }"</Style>
+                };
+                use_scoped_style(cx, "p { color: red; }");
+                let head = meta.dehydrate();
+                assert_eq!(head.matches(&attr).count(), 3);
+
+                _ = view! { cx, <Title text="Later"/> };
+                let patch = meta.dehydrate_changes(Some(&nonce));
+                assert!(patch.starts_with(&format!("<script {attr}>")));
+            });
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{use_navigate, use_resolved_path, ToHref};
-use leptos::*;
+use leptos::{leptos_server::CSRF_FIELD, *};
 use std::{error::Error, rc::Rc};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use wasm_bindgen_futures::JsFuture;
@@ -247,7 +247,7 @@ where
             .on_response(on_response)
             .method("post")
             .class(class)
-            .children(children)
+            .children(with_csrf_token(children))
             .build(),
     )
 }
@@ -309,11 +309,22 @@ where
             class=class
             on:submit=on_submit
         >
-            {children(cx)}
+            {with_csrf_token(children)(cx)}
         </form>
     }
 }
 
+/// Adds a hidden input with the session’s CSRF token, if there is one, before the form’s
+/// children, so that the server function can check that the form came from this app.
+fn with_csrf_token(children: Children) -> Children {
+    Box::new(move |cx| {
+        let token = csrf_token(cx).map(|token| {
+            view! { cx, <input type="hidden" name=CSRF_FIELD value=token/> }
+        });
+        Fragment::new(vec![token.into_view(cx), children(cx).into_view(cx)])
+    })
+}
+
 fn extract_form_attributes(
     ev: &web_sys::Event,
 ) -> (web_sys::HtmlFormElement, String, String, String) {
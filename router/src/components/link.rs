@@ -1,4 +1,6 @@
-use crate::{use_location, use_resolved_path, State};
+use crate::{
+    use_location, use_resolved_path, use_router, PrefetchStrategy, State,
+};
 use leptos::{leptos_dom::IntoView, *};
 
 /// Describes a value that is either a static or a reactive URL, i.e.,
@@ -42,6 +44,9 @@ where
 /// 2) Sets the `aria-current` attribute if this link is the active link (i.e., it’s a link to the page you’re on).
 ///    This is helpful for accessibility and for styling. For example, maybe you want to set the link a
 ///    different color if it’s a link to the page you’re currently on.
+///
+/// It can also begin loading the data for the route it links to before it is clicked,
+/// according to its [PrefetchStrategy]. Only routes with a `data` loader are prefetched.
 #[component]
 pub fn A<H>(
     cx: Scope,
@@ -62,6 +67,10 @@ pub fn A<H>(
     /// Sets the `class` attribute on the underlying `<a>` tag, making it easier to style.
     #[prop(optional, into)]
     class: Option<AttributeValue>,
+    /// When to start loading the data for the route this links to. Defaults to
+    /// [PrefetchStrategy::None].
+    #[prop(optional)]
+    prefetch: PrefetchStrategy,
     /// The nodes or elements to be shown inside the link.
     children: Children,
 ) -> impl IntoView
@@ -75,6 +84,7 @@ where
         state: Option<State>,
         replace: bool,
         class: Option<AttributeValue>,
+        prefetch: PrefetchStrategy,
         children: Children,
     ) -> HtmlElement<leptos::html::A> {
         let location = use_location(cx);
@@ -96,9 +106,17 @@ where
             }
         });
 
-        view! { cx,
+        let router = use_router(cx);
+        let link = view! { cx,
             <a
-                href=move || href.get().unwrap_or_default()
+                href={
+                    let router = router.clone();
+                    move || {
+                        href.get()
+                            .map(|path| router.inner.href(&path))
+                            .unwrap_or_default()
+                    }
+                }
                 prop:state={state.map(|s| s.to_js_value())}
                 prop:replace={replace}
                 aria-current=move || if is_active.get() { Some("page") } else { None }
@@ -106,9 +124,59 @@ where
             >
                 {children(cx)}
             </a>
+        };
+
+        let load = move || {
+            if let Some(href) = href.get_untracked() {
+                router.prefetch(&href);
+            }
+        };
+        match prefetch {
+            PrefetchStrategy::None => link,
+            PrefetchStrategy::Hover => link
+                .on(ev::mouseenter, {
+                    let load = load.clone();
+                    move |_| load()
+                })
+                .on(ev::focus, move |_| load()),
+            PrefetchStrategy::Visible => {
+                link.on_mount(move |link| on_visible(&link, load))
+            }
+            PrefetchStrategy::Eager => link.on_mount(move |_| load()),
         }
     }
 
     let href = use_resolved_path(cx, move || href.to_href()());
-    inner(cx, href, exact, state, replace, class, children)
+    inner(cx, href, exact, state, replace, class, prefetch, children)
 }
+
+/// Calls `f` once, the first time the element scrolls into the viewport.
+#[cfg(not(feature = "ssr"))]
+fn on_visible(el: &web_sys::Element, f: impl Fn() + 'static) {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let callback = Closure::wrap(Box::new(
+        move |entries: js_sys::Array,
+              observer: web_sys::IntersectionObserver| {
+            let is_visible = entries.iter().any(|entry| {
+                entry
+                    .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                    .is_intersecting()
+            });
+            if is_visible {
+                observer.disconnect();
+                f();
+            }
+        },
+    )
+        as Box<dyn Fn(js_sys::Array, web_sys::IntersectionObserver)>)
+    .into_js_value();
+
+    match web_sys::IntersectionObserver::new(callback.unchecked_ref()) {
+        Ok(observer) => observer.observe(el),
+        Err(e) => log::error!("{e:#?}"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn on_visible(_el: &web_sys::Element, _f: impl Fn() + 'static) {}
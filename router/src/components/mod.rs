@@ -5,6 +5,7 @@ mod redirect;
 mod route;
 mod router;
 mod routes;
+mod status;
 
 pub use form::*;
 pub use link::*;
@@ -13,3 +14,4 @@ pub use redirect::*;
 pub use route::*;
 pub use router::*;
 pub use routes::*;
+pub use status::*;
@@ -1,41 +1,147 @@
-use crate::use_route;
+use crate::{use_route, use_router, RouterContext};
+use cfg_if::cfg_if;
 use leptos::{leptos_dom::HydrationCtx, *};
 use std::{cell::Cell, rc::Rc};
 
 /// Displays the child route nested in a parent route, allowing you to control exactly where
 /// that child route is displayed. Renders nothing if there is no nested child.
+///
+/// When navigating from one child route to another, the previous child stays on the page
+/// until every [`<Transition/>`](leptos::Transition) in the next one has resolved.
 #[component]
 pub fn Outlet(cx: Scope) -> impl IntoView {
     let id = HydrationCtx::id();
     let route = use_route(cx);
+    let router = use_router(cx);
     let is_showing = Rc::new(Cell::new(None::<(usize, Scope)>));
+    let is_pending = Rc::new(Cell::new(None::<(usize, Scope)>));
     let (outlet, set_outlet) = create_signal(cx, None::<View>);
     create_isomorphic_effect(cx, move |_| {
-        match (route.child(cx), &is_showing.get()) {
-            (None, prev) => {
-                if let Some(prev_scope) = prev.map(|(_, scope)| scope) {
+        let child = route.child(cx);
+        let child_id = child.as_ref().map(|child| child.id());
+        let showing_id = is_showing.get().map(|(id, _)| id);
+        let pending_id = is_pending.get().map(|(id, _)| id);
+
+        // we're already waiting to show this child
+        if child_id.is_some() && child_id == pending_id {
+            return;
+        }
+
+        match (child, is_showing.get()) {
+            (None, _) => {
+                if let Some((_, pending_scope)) = is_pending.take() {
+                    pending_scope.dispose();
+                }
+                if let Some((_, prev_scope)) = is_showing.take() {
                     prev_scope.dispose();
                 }
                 set_outlet.set(None);
             }
-            (Some(child), Some((is_showing_val, _)))
-                if child.id() == *is_showing_val =>
-            {
-                // do nothing: we don't need to rerender the component, because it's the same
+            (Some(_), Some(_)) if child_id == showing_id => {
+                // we don't need to rerender the component, because it's the same,
+                // but we no longer want to show anything that was pending
+                if let Some((_, pending_scope)) = is_pending.take() {
+                    pending_scope.dispose();
+                }
             }
             (Some(child), prev) => {
-                if let Some(prev_scope) = prev.map(|(_, scope)| scope) {
-                    prev_scope.dispose();
+                let child_id = child.id();
+                let ((view, transitions, child_cx), _) =
+                    cx.run_child_scope(|child_cx| {
+                        let transitions = PendingTransitions::new(child_cx);
+                        provide_context(child_cx, child.clone());
+                        provide_context(child_cx, transitions);
+                        let view = child.outlet(child_cx).into_view(child_cx);
+                        (view, transitions, child_cx)
+                    });
+
+                if prev.is_none() {
+                    is_showing.set(Some((child_id, child_cx)));
+                    set_outlet.set(Some(view));
+                } else {
+                    let prev_pending =
+                        is_pending.replace(Some((child_id, child_cx)));
+                    show_when_ready(child_cx, &router, transitions, {
+                        let is_showing = Rc::clone(&is_showing);
+                        let is_pending = Rc::clone(&is_pending);
+                        move || {
+                            is_pending.set(None);
+                            if let Some((_, prev_scope)) =
+                                is_showing.replace(Some((child_id, child_cx)))
+                            {
+                                prev_scope.dispose();
+                            }
+                            set_outlet.set(Some(view));
+                        }
+                    });
+                    // a newer navigation supersedes whatever was pending
+                    if let Some((_, pending_scope)) = prev_pending {
+                        pending_scope.dispose();
+                    }
                 }
-                _ = cx.child_scope(|child_cx| {
-                    provide_context(child_cx, child.clone());
-                    set_outlet
-                        .set(Some(child.outlet(child_cx).into_view(child_cx)));
-                    is_showing.set(Some((child.id(), child_cx)));
-                });
             }
         }
     });
 
     leptos::leptos_dom::DynChild::new_with_id(id, move || outlet.get())
 }
+
+/// Calls `show` once every [`<Transition/>`](leptos::Transition) in the view created
+/// in `cx` has resolved, so the router can keep showing the previous route while the
+/// next one loads. If `cx` is disposed first, because a later navigation has
+/// superseded this one, `show` is never called.
+pub(crate) fn show_when_ready(
+    cx: Scope,
+    router: &RouterContext,
+    transitions: PendingTransitions,
+    show: impl FnOnce() + 'static,
+) {
+    let router = Rc::clone(&router.inner);
+    router.begin_pending_view();
+
+    let done = Rc::new(Cell::new(false));
+    on_cleanup(cx, {
+        let router = Rc::clone(&router);
+        let done = Rc::clone(&done);
+        move || {
+            if !done.replace(true) {
+                router.end_pending_view();
+            }
+        }
+    });
+
+    let show = Rc::new(Cell::new(Some(show)));
+    let finish = {
+        let done = Rc::clone(&done);
+        move || {
+            if !done.replace(true) {
+                if let Some(show) = show.take() {
+                    router.swap_view(show);
+                }
+                router.end_pending_view();
+            }
+        }
+    };
+
+    cfg_if! {
+        if #[cfg(feature = "ssr")] {
+            _ = transitions;
+            finish();
+        } else {
+            // resources only tell their <Transition/> they're pending after a microtask,
+            // so wait for those to run before checking whether anything is pending
+            set_timeout(
+                move || {
+                    if !done.get() {
+                        create_effect(cx, move |_| {
+                            if transitions.ready() {
+                                finish();
+                            }
+                        });
+                    }
+                },
+                std::time::Duration::ZERO,
+            );
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
     matching::{resolve_path, PathMatch, RouteDefinition, RouteMatch},
-    ParamsMap, RouterContext,
+    set_server_status, ParamsMap, RouteLoader, RouterContext,
 };
 use leptos::{leptos_dom::Transparent, *};
 use std::{
+    any::Any,
     cell::{Cell, RefCell},
     rc::Rc,
 };
@@ -25,6 +26,17 @@ pub fn Route<E, F, P>(
     /// that takes a [Scope] and returns an [Element] (like `|cx| view! { cx, <p>"Show this"</p> })`
     /// or `|cx| view! { cx, <MyComponent/>` } or even, for a component with no props, `MyComponent`).
     view: F,
+    /// Loads the data for this route, given its params. Anything created in the loader
+    /// is kept alive for as long as the route is shown, and can be read in the view
+    /// with [use_route_data](crate::use_route_data). Routes with a loader can be
+    /// prefetched by an [`<A/>`](crate::A) before they are navigated to.
+    #[prop(optional, into)]
+    data: Option<RouteLoader>,
+    /// The HTTP status code of the response when this route is rendered on the
+    /// server, like `404` for a catch-all “not found” route or `410` for a page
+    /// that has been removed. Defaults to `200`.
+    #[prop(optional)]
+    status: Option<u16>,
     /// `children` may be empty or include nested routes.
     #[prop(optional)]
     children: Option<Children>,
@@ -39,6 +51,8 @@ where
         children: Option<Children>,
         path: String,
         view: Rc<dyn Fn(Scope) -> View>,
+        data: Option<RouteLoader>,
+        status: Option<u16>,
     ) -> RouteDefinition {
         let children = children
             .map(|children| {
@@ -66,6 +80,8 @@ where
             path,
             children,
             view,
+            data,
+            status,
         }
     }
 
@@ -74,6 +90,8 @@ where
         children,
         path.to_string(),
         Rc::new(move |cx| view(cx).into_view(cx)),
+        data,
+        status,
     )
 }
 
@@ -101,12 +119,63 @@ impl RouteContext {
         let RouteMatch { path_match, route } = matcher()?;
         let PathMatch { path, .. } = path_match;
         let RouteDefinition {
-            view: element, id, ..
+            view: element,
+            id,
+            data: loader,
+            status,
+            ..
         } = route.key;
-        let params = create_memo(cx, move |_| {
-            matcher()
-                .map(|matched| matched.path_match.params)
-                .unwrap_or_default()
+        let matcher = Rc::new(matcher);
+        let params = create_memo(cx, {
+            let matcher = Rc::clone(&matcher);
+            move |_| {
+                matcher()
+                    .map(|matched| matched.path_match.params)
+                    .unwrap_or_default()
+            }
+        });
+        let data = loader.map(|loader| {
+            let router = router.clone();
+            let data_cx = RefCell::new(None::<ScopeDisposer>);
+            let left = Cell::new(false);
+            create_memo(cx, move |prev: Option<&RouteDataValue>| {
+                // the matcher follows this route's position in the tree, so once
+                // the route has been left, it matches whatever replaces it: keep
+                // the data, which the old view may still be showing, rather than
+                // loading it again with another route's params
+                let matched = if left.get() { None } else { matcher() }
+                    .filter(|matched| matched.route.id == id);
+                if let (None, Some(prev)) = (&matched, prev) {
+                    left.set(true);
+                    return prev.clone();
+                }
+                let (path, params) = matched
+                    .map(|matched| {
+                        (matched.path_match.path, matched.path_match.params)
+                    })
+                    .unwrap_or_default();
+                // params for this route haven't changed: keep the same data
+                if let Some(prev) = prev.filter(|prev| prev.path == path) {
+                    return prev.clone();
+                }
+                if let Some(prev) = data_cx.borrow_mut().take() {
+                    prev.dispose();
+                }
+                // reuse the data if this route was prefetched
+                let (data, disposer) = router
+                    .inner
+                    .prefetch
+                    .take(id, &path)
+                    .unwrap_or_else(|| {
+                        let data = RefCell::new(None);
+                        let disposer = cx.child_scope(|cx| {
+                            *data.borrow_mut() = Some(loader.load(cx, params));
+                        });
+                        (data.into_inner().unwrap(), disposer)
+                    });
+                *data_cx.borrow_mut() = Some(disposer);
+                RouteDataValue { path, data }
+            })
         });
 
         Some(Self {
@@ -118,7 +187,13 @@ impl RouteContext {
                 path: RefCell::new(path),
                 original_path: route.original_path.to_string(),
                 params,
-                outlet: Box::new(move |cx| Some(element(cx))),
+                data,
+                outlet: Box::new(move |cx| {
+                    if let Some(status) = status {
+                        set_server_status(cx, status);
+                    }
+                    Some(element(cx))
+                }),
             }),
         })
     }
@@ -159,6 +234,13 @@ impl RouteContext {
         self.inner.params
     }
 
+    /// The value returned by the route's data loader, if it has one.
+    pub fn data(&self) -> Option<Rc<dyn Any>> {
+        self.inner
+            .data
+            .map(|data| data.with(|data| Rc::clone(&data.data)))
+    }
+
     pub(crate) fn base(
         cx: Scope,
         path: &str,
@@ -173,6 +255,7 @@ impl RouteContext {
                 path: RefCell::new(path.to_string()),
                 original_path: path.to_string(),
                 params: create_memo(cx, |_| ParamsMap::new()),
+                data: None,
                 outlet: Box::new(move |cx| {
                     fallback.as_ref().map(move |f| f(cx))
                 }),
@@ -205,9 +288,24 @@ pub(crate) struct RouteContextInner {
     pub(crate) path: RefCell<String>,
     pub(crate) original_path: String,
     pub(crate) params: Memo<ParamsMap>,
+    pub(crate) data: Option<Memo<RouteDataValue>>,
     pub(crate) outlet: Box<dyn Fn(Scope) -> Option<View>>,
 }
 
+/// The value returned by a route's data loader, and the path it was loaded for.
+/// Two values are only equal if they come from the same run of the loader.
+#[derive(Clone)]
+pub(crate) struct RouteDataValue {
+    path: String,
+    data: Rc<dyn Any>,
+}
+
+impl PartialEq for RouteDataValue {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+}
+
 impl PartialEq for RouteContextInner {
     fn eq(&self, other: &Self) -> bool {
         self.cx == other.cx
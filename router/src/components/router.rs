@@ -1,6 +1,7 @@
 use crate::{
     create_location, matching::resolve_path, Branch, History, Location,
-    LocationChange, RouteContext, RouterIntegrationContext, State,
+    LocationChange, PrefetchCache, RouteContext, RouterIntegrationContext,
+    State,
 };
 #[cfg(not(feature = "ssr"))]
 use crate::{unescape, Url};
@@ -8,7 +9,11 @@ use cfg_if::cfg_if;
 use leptos::*;
 #[cfg(feature = "transition")]
 use leptos_reactive::use_transition;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
 use thiserror::Error;
 #[cfg(not(feature = "ssr"))]
 use wasm_bindgen::JsCast;
@@ -21,18 +26,42 @@ pub fn Router(
     /// The base URL for the router. Defaults to "".
     #[prop(optional)]
     base: Option<&'static str>,
-    /// A fallback that should be shown if no route is matched.
+    /// A fallback that should be shown if no route is matched. When rendered on
+    /// the server, this sets a `404` status.
     #[prop(optional)]
     fallback: Option<fn(Scope) -> View>,
+    /// How long data loaded by prefetching a route is kept if the route is never
+    /// navigated to. Defaults to [DEFAULT_PREFETCH_TIMEOUT](crate::DEFAULT_PREFETCH_TIMEOUT).
+    #[prop(optional)]
+    prefetch_timeout: Option<Duration>,
+    /// If `true`, swapping from one route to the next will be wrapped in
+    /// [`document.startViewTransition()`](https://developer.mozilla.org/en-US/docs/Web/API/Document/startViewTransition)
+    /// in browsers that support it, so that enter and exit animations can be
+    /// defined in CSS with the `::view-transition-old()` and `::view-transition-new()` selectors.
+    #[prop(optional)]
+    view_transitions: bool,
     /// The `<Router/>` should usually wrap your whole page. It can contain
     /// any elements, and should include a [Routes](crate::Routes) component somewhere
     /// to define and display [Route](crate::Route)s.
     children: Children,
 ) -> impl IntoView {
     // create a new RouterContext and provide it to every component beneath the router
-    let router = RouterContext::new(cx, base, fallback);
+    let router = RouterContext::new(
+        cx,
+        base,
+        fallback,
+        prefetch_timeout,
+        view_transitions,
+    );
     provide_context(cx, router);
 
+    #[cfg(feature = "ssr")]
+    if fallback.is_some() {
+        if let Some(context) = use_context::<crate::PossibleBranchContext>(cx) {
+            context.has_fallback.set(true);
+        }
+    }
+
     children(cx)
 }
 
@@ -54,6 +83,14 @@ pub(crate) struct RouterContextInner {
     referrers: Rc<RefCell<Vec<LocationChange>>>,
     state: ReadSignal<State>,
     set_state: WriteSignal<State>,
+    pub(crate) prefetch: PrefetchCache,
+    is_routing: ReadSignal<bool>,
+    set_is_routing: WriteSignal<bool>,
+    pending_location: ReadSignal<Option<String>>,
+    set_pending_location: WriteSignal<Option<String>>,
+    pending_views: Cell<usize>,
+    #[allow(unused)] // used in CSR/hydrate
+    view_transitions: bool,
 }
 
 impl std::fmt::Debug for RouterContextInner {
@@ -67,6 +104,9 @@ impl std::fmt::Debug for RouterContextInner {
             .field("referrers", &self.referrers)
             .field("state", &self.state)
             .field("set_state", &self.set_state)
+            .field("prefetch", &self.prefetch)
+            .field("is_routing", &self.is_routing)
+            .field("pending_location", &self.pending_location)
             .finish()
     }
 }
@@ -76,6 +116,8 @@ impl RouterContext {
         cx: Scope,
         base: Option<&'static str>,
         fallback: Option<fn(Scope) -> View>,
+        prefetch_timeout: Option<Duration>,
+        view_transitions: bool,
     ) -> Self {
         cfg_if! {
             if #[cfg(any(feature = "csr", feature = "hydrate"))] {
@@ -122,6 +164,11 @@ impl RouterContext {
         let (state, set_state) =
             create_signal(cx, source.with(|s| s.state.clone()));
 
+        // whether a navigation is in progress, and where it's going
+        let (is_routing, set_is_routing) = create_signal(cx, false);
+        let (pending_location, set_pending_location) =
+            create_signal(cx, None::<String>);
+
         // we'll use this transition to wait for async resources to load when navigating to a new route
         #[cfg(feature = "transition")]
         let transition = use_transition(cx);
@@ -162,6 +209,13 @@ impl RouterContext {
             state,
             set_state,
             possible_routes: Default::default(),
+            prefetch: PrefetchCache::new(cx, prefetch_timeout),
+            is_routing,
+            set_is_routing,
+            pending_location,
+            set_pending_location,
+            pending_views: Cell::new(0),
+            view_transitions,
         });
 
         // handle all click events on anchor tags
@@ -185,6 +239,18 @@ impl RouterContext {
         self.inner.base.clone()
     }
 
+    /// Whether a navigation is in progress, i.e., the location has changed but the
+    /// router is still waiting for the new route's
+    /// [`<Transition/>`](leptos::Transition)s to resolve before showing it.
+    pub fn is_routing(&self) -> ReadSignal<bool> {
+        self.inner.is_routing
+    }
+
+    /// The location the router is navigating to, while a navigation is in progress.
+    pub fn pending_location(&self) -> ReadSignal<Option<String>> {
+        self.inner.pending_location
+    }
+
     /// A list of all possible routes this router can match.
     pub fn possible_branches(&self) -> Vec<Branch> {
         self.inner
@@ -196,6 +262,11 @@ impl RouterContext {
 }
 
 impl RouterContextInner {
+    /// The `href` a link to `path` should use with the current router integration.
+    pub(crate) fn href(&self, path: &str) -> String {
+        self.history.href(path)
+    }
+
     pub(crate) fn navigate_from_route(
         self: Rc<Self>,
         to: &str,
@@ -241,6 +312,10 @@ impl RouterContextInner {
                             }
                             let len = self.referrers.borrow().len();
 
+                            self.set_is_routing.set(true);
+                            self.set_pending_location
+                                .set(Some(resolved_to.clone()));
+
                             #[cfg(feature = "transition")]
                             let transition = use_transition(self.cx);
                             //transition.start({
@@ -253,6 +328,11 @@ impl RouterContextInner {
                             let resolved = resolved_to.to_string();
                             let state = options.state.clone();
                             queue_microtask(move || {
+                                // a later navigation has superseded this one
+                                if referrers.borrow().len() != len {
+                                    return;
+                                }
+
                                 set_reference.update(move |r| *r = resolved);
 
                                 set_state.update({
@@ -260,14 +340,21 @@ impl RouterContextInner {
                                     move |state| *state = next_state
                                 });
                                 if referrers.borrow().len() == len {
-                                    this.navigate_end(LocationChange {
-                                        value: resolved_to.to_string(),
-                                        replace: false,
-                                        scroll: true,
-                                        state,
-                                    })
+                                    Rc::clone(&this).navigate_end(
+                                        LocationChange {
+                                            value: resolved_to.to_string(),
+                                            replace: false,
+                                            scroll: true,
+                                            state,
+                                        },
+                                    )
                                     //}
                                 }
+
+                                // nothing is waiting to be shown
+                                if this.pending_views.get() == 0 {
+                                    this.end_routing();
+                                }
                             });
                             //});
                         }
@@ -291,6 +378,52 @@ impl RouterContextInner {
         }
     }
 
+    /// Registers a route view that is waiting for its transitions to resolve
+    /// before it is shown.
+    pub(crate) fn begin_pending_view(&self) {
+        self.pending_views.set(self.pending_views.get() + 1);
+        if !self.is_routing.get_untracked() {
+            self.set_is_routing.set(true);
+        }
+    }
+
+    /// Called once a pending route view has been shown, or abandoned because
+    /// another navigation superseded it.
+    pub(crate) fn end_pending_view(&self) {
+        let pending = self.pending_views.get().saturating_sub(1);
+        self.pending_views.set(pending);
+        if pending == 0 {
+            self.end_routing();
+        }
+    }
+
+    fn end_routing(&self) {
+        self.set_is_routing.set(false);
+        self.set_pending_location.set(None);
+    }
+
+    /// Swaps in a new route view, as a view transition if they're enabled.
+    pub(crate) fn swap_view(&self, swap: impl FnOnce() + 'static) {
+        #[cfg(not(feature = "ssr"))]
+        if self.view_transitions {
+            let document = leptos_dom::document();
+            let start = js_sys::Reflect::get(
+                &document,
+                &wasm_bindgen::JsValue::from_str("startViewTransition"),
+            )
+            .ok()
+            .and_then(|start| start.dyn_into::<js_sys::Function>().ok());
+            if let Some(start) = start {
+                let swap = wasm_bindgen::closure::Closure::once_into_js(swap);
+                if let Err(e) = start.call1(&document, &swap) {
+                    log::error!("{e:#?}");
+                }
+                return;
+            }
+        }
+        swap();
+    }
+
     #[cfg(not(feature = "ssr"))]
     pub(crate) fn handle_anchor_click(self: Rc<Self>, ev: web_sys::Event) {
         let ev = ev.unchecked_into::<web_sys::MouseEvent>();
@@ -351,7 +484,11 @@ impl RouterContextInner {
                 return;
             }
 
-            let to = path_name + &unescape(&url.search) + &unescape(&url.hash);
+            let to = self.history.route_from_url(
+                &path_name,
+                &unescape(&url.search),
+                &unescape(&url.hash),
+            );
             let state =
                 leptos_dom::helpers::get_property(a.unchecked_ref(), "state")
                     .ok()
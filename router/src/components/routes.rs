@@ -3,7 +3,7 @@ use crate::{
         expand_optionals, get_route_matches, join_paths, Branch, Matcher,
         RouteDefinition, RouteMatch,
     },
-    RouteContext, RouterContext,
+    set_server_status, show_when_ready, RouteContext, RouterContext,
 };
 use leptos::{leptos_dom::HydrationCtx, *};
 use std::{
@@ -16,10 +16,18 @@ use std::{
 /// Contains route definitions and manages the actual routing process.
 ///
 /// You should locate the `<Routes/>` component wherever on the page you want the routes to appear.
+///
+/// If no route matches the current path, this shows its `fallback`, or the
+/// [`<Router/>`](crate::Router)’s if it doesn’t have one, and sets a `404` status
+/// when rendered on the server.
 #[component]
 pub fn Routes(
     cx: Scope,
     #[prop(optional)] base: Option<String>,
+    /// The view that should be shown if no route is matched. Defaults to the
+    /// `fallback` of the [`<Router/>`](crate::Router).
+    #[prop(optional)]
+    fallback: Option<fn(Scope) -> View>,
     children: Children,
 ) -> impl IntoView {
     let router = use_context::<RouterContext>(cx)
@@ -46,9 +54,14 @@ pub fn Routes(
         &mut branches,
     );
 
+    *router.inner.possible_routes.borrow_mut() = Some(branches.clone());
+
     #[cfg(feature = "ssr")]
     if let Some(context) = use_context::<crate::PossibleBranchContext>(cx) {
-        *context.0.borrow_mut() = branches.clone();
+        *context.branches.borrow_mut() = branches.clone();
+        if fallback.is_some() {
+            context.has_fallback.set(true);
+        }
     }
 
     // whenever path changes, update matches
@@ -65,6 +78,7 @@ pub fn Routes(
 
     let route_states: Memo<RouterState> = create_memo(cx, {
         let root_equal = Rc::clone(&root_equal);
+        let router = router.clone();
         move |prev: Option<&RouterState>| {
             root_equal.set(true);
             next.borrow_mut().clear();
@@ -114,6 +128,8 @@ pub fn Routes(
                             &RouterContext { inner: router },
                             {
                                 let next = next.clone();
+                                let id = next_match.route.id;
+                                let last_child = RefCell::new(None);
                                 move |cx| {
                                     if let Some(route_states) =
                                         use_context::<Memo<RouterState>>(cx)
@@ -121,7 +137,21 @@ pub fn Routes(
                                         route_states.with(|route_states| {
                                             let routes =
                                                 route_states.routes.borrow();
-                                            routes.get(i + 1).cloned()
+                                            // this route has been replaced, but is still
+                                            // on the page while the next one loads, so
+                                            // it should keep showing the same child
+                                            if routes.get(i).map(|r| r.id())
+                                                != Some(id)
+                                            {
+                                                return last_child
+                                                    .borrow()
+                                                    .clone();
+                                            }
+                                            let child =
+                                                routes.get(i + 1).cloned();
+                                            *last_child.borrow_mut() =
+                                                child.clone();
+                                            child
                                         })
                                     } else {
                                         next.borrow().get(i + 1).cloned()
@@ -169,13 +199,24 @@ pub fn Routes(
     });
 
     // show the root route
+    // when the root route changes, the previous one stays on the page until
+    // every <Transition/> in the next one has resolved
     let id = HydrationCtx::id();
-    let root_cx = RefCell::new(None);
-    let root = create_memo(cx, move |prev| {
+    let root_cx = Rc::new(Cell::new(None::<Scope>));
+    let pending_cx = Rc::new(Cell::new(None::<Scope>));
+    let (root, set_root) = create_signal(cx, None::<View>);
+    create_isomorphic_effect(cx, move |prev: Option<()>| {
         provide_context(cx, route_states);
         route_states.with(|state| {
             if state.routes.borrow().is_empty() {
-                Some(base_route.outlet(cx).into_view(cx))
+                if let Some(pending_cx) = pending_cx.take() {
+                    pending_cx.dispose();
+                }
+                set_server_status(cx, 404);
+                set_root.set(Some(match fallback {
+                    Some(fallback) => fallback(cx),
+                    None => base_route.outlet(cx).into_view(cx),
+                }));
             } else {
                 let root = state.routes.borrow();
                 let root = root.get(0);
@@ -184,20 +225,44 @@ pub fn Routes(
                 }
 
                 if prev.is_none() || !root_equal.get() {
-                    let (root_view, _) = cx.run_child_scope(|cx| {
-                        let prev_cx = std::mem::replace(
-                            &mut *root_cx.borrow_mut(),
-                            Some(cx),
-                        );
-                        if let Some(prev_cx) = prev_cx {
-                            prev_cx.dispose();
+                    let ((root_view, transitions), new_cx) = {
+                        let (res, _) = cx.run_child_scope(|cx| {
+                            let transitions = PendingTransitions::new(cx);
+                            provide_context(cx, transitions);
+                            // reads inside the route view belong to the view,
+                            // not to this effect
+                            let root_view = cx.untrack(|| {
+                                root.as_ref()
+                                    .map(|route| route.outlet(cx).into_view(cx))
+                            });
+                            ((root_view, transitions), cx)
+                        });
+                        res
+                    };
+
+                    if prev.is_none() {
+                        root_cx.set(Some(new_cx));
+                        set_root.set(root_view);
+                    } else {
+                        let prev_pending = pending_cx.replace(Some(new_cx));
+                        show_when_ready(new_cx, &router, transitions, {
+                            let root_cx = Rc::clone(&root_cx);
+                            let pending_cx = Rc::clone(&pending_cx);
+                            move || {
+                                pending_cx.set(None);
+                                if let Some(prev_cx) =
+                                    root_cx.replace(Some(new_cx))
+                                {
+                                    prev_cx.dispose();
+                                }
+                                set_root.set(root_view);
+                            }
+                        });
+                        // a newer navigation supersedes whatever was pending
+                        if let Some(prev_pending) = prev_pending {
+                            prev_pending.dispose();
                         }
-                        root.as_ref()
-                            .map(|route| route.outlet(cx).into_view(cx))
-                    });
-                    root_view
-                } else {
-                    prev.cloned().unwrap()
+                    }
                 }
             }
         })
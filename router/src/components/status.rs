@@ -0,0 +1,46 @@
+use leptos::{provide_context, use_context, Scope};
+use std::rc::Rc;
+
+/// Sets the HTTP status code of the response, if the app is being rendered on
+/// the server. In the browser, this does nothing.
+///
+/// A [`<Route/>`](crate::Route) can declare its status with its `status` prop,
+/// and a [`<Routes/>`](crate::Routes) that doesn't match the current path sets
+/// a `404` automatically, so this is mostly useful for a status that depends on
+/// data, like a `410` for a post that has been deleted. The last status set
+/// while rendering wins.
+///
+/// **Note**: Support for setting the status is provided by the server framework
+/// integrations (`leptos_actix` and `leptos_axum`). If you’re not using one of those
+/// integrations, you should manually provide a way of setting it on the server
+/// using [provide_server_status].
+pub fn set_server_status(cx: Scope, status: u16) {
+    if let Some(status_fn) = use_context::<ServerStatusFunction>(cx) {
+        (status_fn.f)(status);
+    }
+}
+
+/// Wrapping type for a function provided as context to allow for setting
+/// the response status on the server. See [provide_server_status]
+/// and [set_server_status].
+#[derive(Clone)]
+pub struct ServerStatusFunction {
+    f: Rc<dyn Fn(u16)>,
+}
+
+impl std::fmt::Debug for ServerStatusFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerStatusFunction").finish()
+    }
+}
+
+/// Provides a function that can be used to set the HTTP status code of the
+/// response, on the server.
+pub fn provide_server_status(cx: Scope, handler: impl Fn(u16) + 'static) {
+    provide_context(
+        cx,
+        ServerStatusFunction {
+            f: Rc::new(handler),
+        },
+    )
+}
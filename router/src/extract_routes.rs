@@ -1,10 +1,17 @@
-use crate::{Branch, RouterIntegrationContext, ServerIntegration};
+use crate::{Branch, ParamsMap, RouterIntegrationContext, ServerIntegration};
 use leptos::*;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
 
 /// Context to contain all possible routes.
 #[derive(Clone, Default, Debug)]
-pub struct PossibleBranchContext(pub(crate) Rc<RefCell<Vec<Branch>>>);
+pub struct PossibleBranchContext {
+    pub(crate) branches: Rc<RefCell<Vec<Branch>>>,
+    pub(crate) has_fallback: Rc<Cell<bool>>,
+}
 
 /// Generates a list of all routes this application could possibly serve. This returns the raw routes in the leptos_router
 /// format. Odds are you want `generate_route_list()` from either the actix or axum integrations if you want
@@ -12,6 +19,20 @@ pub struct PossibleBranchContext(pub(crate) Rc<RefCell<Vec<Branch>>>);
 pub fn generate_route_list_inner<IV>(
     app_fn: impl FnOnce(Scope) -> IV + 'static,
 ) -> Vec<String>
+where
+    IV: IntoView + 'static,
+{
+    generate_route_list_with_fallback_inner(app_fn).0
+}
+
+/// Like [generate_route_list_inner], but also returns whether the application
+/// defines a fallback view, on its [`<Router/>`](crate::Router) or its
+/// [`<Routes/>`](crate::Routes), for paths that don't match any route. If so, the
+/// server should render the application for unmatched paths too, which will show
+/// the fallback with a `404` status.
+pub fn generate_route_list_with_fallback_inner<IV>(
+    app_fn: impl FnOnce(Scope) -> IV + 'static,
+) -> (Vec<String>, bool)
 where
     IV: IntoView + 'static,
 {
@@ -22,17 +43,99 @@ where
         };
 
         provide_context(cx, RouterIntegrationContext::new(integration));
-        let branches = PossibleBranchContext::default();
-        provide_context(cx, branches.clone());
+        let context = PossibleBranchContext::default();
+        provide_context(cx, context.clone());
 
         let _ = app_fn(cx).into_view(cx);
 
-        let branches = branches.0.borrow();
-        branches
+        let branches = context.branches.borrow();
+        let routes = branches
             .iter()
             .flat_map(|branch| {
                 branch.routes.last().map(|route| route.pattern.clone())
             })
-            .collect()
+            .collect();
+        (routes, context.has_fallback.get())
     })
 }
+
+/// Turns a list of routes, like the one returned by [generate_route_list_inner], into
+/// the paths of the pages that can be generated ahead of time.
+///
+/// A route without any dynamic segments is a single page. A route like `/post/:id` or
+/// `/docs/*path` is a page for each [ParamsMap] given for it in `params`, which is keyed
+/// by the route. Routes with dynamic segments that aren’t in `params`, and param maps
+/// that are missing a segment, are skipped.
+///
+/// Because each path becomes a directory when the site is generated, param maps with a
+/// value that isn’t a single path segment are skipped too: an empty value, `.` or `..`,
+/// or one containing `/` or `\`. For a wildcard segment, each part of the value between
+/// slashes has to be a valid segment.
+///
+/// ```
+/// # use leptos_router::*;
+/// # use std::collections::HashMap;
+/// let routes = vec![
+///     "/".to_string(),
+///     "/post/:id".to_string(),
+///     "/user/:name".to_string(),
+/// ];
+/// let params = HashMap::from([(
+///     "/post/:id".to_string(),
+///     vec![params_map! { "id" => "1" }, params_map! { "id" => "2" }],
+/// )]);
+/// assert_eq!(
+///     expand_static_paths(&routes, &params),
+///     vec!["/", "/post/1", "/post/2"]
+/// );
+/// ```
+pub fn expand_static_paths(
+    routes: &[String],
+    params: &HashMap<String, Vec<ParamsMap>>,
+) -> Vec<String> {
+    let mut paths = Vec::new();
+    for route in routes {
+        let is_dynamic = route.split('/').any(|segment| {
+            segment.starts_with(':') || segment.starts_with('*')
+        });
+        if !is_dynamic {
+            paths.push(route.clone());
+        } else if let Some(params) = params.get(route) {
+            paths.extend(
+                params
+                    .iter()
+                    .filter_map(|params| expand_route(route, params)),
+            );
+        }
+    }
+    paths
+}
+
+/// Fills in the dynamic segments of a route with the given params.
+fn expand_route(route: &str, params: &ParamsMap) -> Option<String> {
+    route
+        .split('/')
+        .map(|segment| {
+            if let Some(name) = segment.strip_prefix(':') {
+                params
+                    .get(name)
+                    .map(String::as_str)
+                    .filter(|value| is_safe_segment(value))
+            } else if let Some(name) = segment.strip_prefix('*') {
+                params
+                    .get(name)
+                    .map(String::as_str)
+                    .filter(|value| value.split('/').all(is_safe_segment))
+            } else {
+                Some(segment)
+            }
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|segments| segments.join("/"))
+}
+
+/// Whether a param value can be written as one segment of a path, without leaving the
+/// directory it is written in.
+fn is_safe_segment(value: &str) -> bool {
+    !matches!(value, "" | "." | "..") && !value.contains(['/', '\\'])
+}
@@ -0,0 +1,122 @@
+use crate::{History, LocationChange};
+use leptos::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// A router integration that keeps its own back/forward stack in memory,
+/// without touching the browser’s URL or the
+/// [`History API`](https://developer.mozilla.org/en-US/docs/Web/API/History).
+///
+/// This is useful for widgets embedded in a larger page, for renderers that are
+/// not a browser at all, and for testing navigation flows without a DOM.
+///
+/// ```
+/// # use leptos_router::*;
+/// # use leptos::*;
+/// # run_scope(create_runtime(), |cx| {
+/// let history = MemoryIntegration::new("/");
+/// provide_context(cx, RouterIntegrationContext::new(history.clone()));
+///
+/// // ... later, from a button in your own UI
+/// history.back();
+/// # });
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemoryIntegration {
+    inner: Rc<RefCell<MemoryHistory>>,
+}
+
+#[derive(Debug)]
+struct MemoryHistory {
+    entries: Vec<LocationChange>,
+    index: usize,
+    set_location: Option<WriteSignal<LocationChange>>,
+}
+
+impl MemoryIntegration {
+    /// Creates a new in-memory history, with `path` as its only entry.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(MemoryHistory {
+                entries: vec![LocationChange {
+                    value: path.into(),
+                    replace: false,
+                    ..Default::default()
+                }],
+                index: 0,
+                set_location: None,
+            })),
+        }
+    }
+
+    /// The entry at the current position in the history stack.
+    pub fn current(&self) -> LocationChange {
+        let inner = self.inner.borrow();
+        inner.entries.get(inner.index).cloned().unwrap_or_default()
+    }
+
+    /// All the entries in the history stack, oldest first.
+    pub fn entries(&self) -> Vec<LocationChange> {
+        self.inner.borrow().entries.clone()
+    }
+
+    /// Whether there is an entry before the current one.
+    pub fn can_go_back(&self) -> bool {
+        self.inner.borrow().index > 0
+    }
+
+    /// Whether there is an entry after the current one.
+    pub fn can_go_forward(&self) -> bool {
+        let inner = self.inner.borrow();
+        inner.index + 1 < inner.entries.len()
+    }
+
+    /// Moves one entry back in the history stack, like the browser’s “back” button.
+    pub fn back(&self) {
+        self.go(-1);
+    }
+
+    /// Moves one entry forward in the history stack, like the browser’s “forward” button.
+    pub fn forward(&self) {
+        self.go(1);
+    }
+
+    /// Moves `delta` entries through the history stack. Does nothing if that would
+    /// move past either end of the stack.
+    pub fn go(&self, delta: isize) {
+        let (current, set_location) = {
+            let mut inner = self.inner.borrow_mut();
+            let index = inner.index as isize + delta;
+            if delta == 0 || index < 0 || index >= inner.entries.len() as isize
+            {
+                return;
+            }
+            inner.index = index as usize;
+            (inner.entries[inner.index].clone(), inner.set_location)
+        };
+
+        if let Some(set_location) = set_location {
+            set_location.set(current);
+        }
+    }
+}
+
+impl History for MemoryIntegration {
+    fn location(&self, cx: Scope) -> ReadSignal<LocationChange> {
+        let (location, set_location) = create_signal(cx, self.current());
+        self.inner.borrow_mut().set_location = Some(set_location);
+        location
+    }
+
+    fn navigate(&self, loc: &LocationChange) {
+        let mut inner = self.inner.borrow_mut();
+        let index = inner.index;
+        if loc.replace && index < inner.entries.len() {
+            inner.entries[index] = loc.clone();
+        } else {
+            // navigating discards any entries we could have gone forward to
+            inner.entries.truncate(index + 1);
+            inner.entries.push(loc.clone());
+            inner.index = inner.entries.len() - 1;
+        }
+    }
+}
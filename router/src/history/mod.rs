@@ -3,12 +3,14 @@ use std::rc::Rc;
 use wasm_bindgen::UnwrapThrowExt;
 
 mod location;
+mod memory;
 mod params;
 mod state;
 mod url;
 
 pub use self::url::*;
 pub use location::*;
+pub use memory::*;
 pub use params::*;
 pub use state::*;
 
@@ -27,6 +29,17 @@ pub trait History {
 
     /// Called to navigate to a new location.
     fn navigate(&self, loc: &LocationChange);
+
+    /// The `href` a link to `path` should use. By default this is the path itself.
+    fn href(&self, path: &str) -> String {
+        path.to_string()
+    }
+
+    /// The path within the app that a clicked link points to, given the path, query
+    /// and hash of the link’s URL. This is the inverse of [`History::href`].
+    fn route_from_url(&self, path: &str, search: &str, hash: &str) -> String {
+        format!("{path}{search}{hash}")
+    }
 }
 
 /// The default integration when you are running in the browser, which uses
@@ -116,6 +129,105 @@ impl History for BrowserIntegration {
     }
 }
 
+/// An integration for the browser that stores the current route in the URL’s hash
+/// fragment (e.g., `https://example.com/#/users/1`), rather than in its path.
+///
+/// Because the server only ever sees requests for the root page, this works with
+/// static hosting that can’t be configured to rewrite every path to `index.html`.
+/// It must be provided as a context above the [Router](crate::Router):
+///
+/// ```
+/// # use leptos_router::*;
+/// # use leptos::*;
+/// # run_scope(create_runtime(), |cx| {
+/// provide_context(cx, RouterIntegrationContext::new(HashIntegration {}));
+/// # });
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HashIntegration {}
+
+impl HashIntegration {
+    fn current() -> LocationChange {
+        let hash = leptos_dom::helpers::location().hash().unwrap_or_default();
+        let state = leptos_dom::window()
+            .history()
+            .and_then(|history| history.state())
+            .ok()
+            .filter(|state| !state.is_null() && !state.is_undefined());
+        LocationChange {
+            value: Self {}.route_from_url("", "", &hash),
+            replace: true,
+            scroll: true,
+            state: State(state),
+        }
+    }
+}
+
+impl History for HashIntegration {
+    fn location(&self, cx: Scope) -> ReadSignal<LocationChange> {
+        use crate::{NavigateOptions, RouterContext};
+
+        let (location, set_location) = create_signal(cx, Self::current());
+
+        leptos::window_event_listener("hashchange", move |_| {
+            let router = use_context::<RouterContext>(cx);
+            if let Some(router) = router {
+                let change = Self::current();
+                if let Err(e) = router.inner.navigate_from_route(
+                    &change.value,
+                    &NavigateOptions {
+                        resolve: false,
+                        replace: change.replace,
+                        scroll: change.scroll,
+                        state: change.state,
+                    },
+                ) {
+                    log::error!("{e:#?}");
+                }
+                set_location.set(Self::current());
+            } else {
+                log::warn!("RouterContext not found");
+            }
+        });
+
+        location
+    }
+
+    fn navigate(&self, loc: &LocationChange) {
+        let history = leptos_dom::window().history().unwrap_throw();
+        let url = self.href(&loc.value);
+
+        // pushState doesn't fire `hashchange`, so this won't loop back into the router
+        if loc.replace {
+            history
+                .replace_state_with_url(
+                    &loc.state.to_js_value(),
+                    "",
+                    Some(&url),
+                )
+                .unwrap_throw();
+        } else {
+            history
+                .push_state_with_url(&loc.state.to_js_value(), "", Some(&url))
+                .unwrap_throw();
+        }
+        if loc.scroll {
+            leptos_dom::window().scroll_to_with_x_and_y(0.0, 0.0);
+        }
+    }
+
+    fn href(&self, path: &str) -> String {
+        format!("#{path}")
+    }
+
+    fn route_from_url(&self, _path: &str, _search: &str, hash: &str) -> String {
+        match hash.strip_prefix('#').unwrap_or(hash) {
+            "" => "/".to_string(),
+            route => route.to_string(),
+        }
+    }
+}
+
 /// The wrapper type that the [Router](crate::Router) uses to interact with a [History].
 /// This is automatically provided in the browser. For the server, it should be provided
 /// as a context.
@@ -148,6 +260,14 @@ impl History for RouterIntegrationContext {
     fn navigate(&self, loc: &LocationChange) {
         self.0.navigate(loc)
     }
+
+    fn href(&self, path: &str) -> String {
+        self.0.href(path)
+    }
+
+    fn route_from_url(&self, path: &str, search: &str, hash: &str) -> String {
+        self.0.route_from_url(path, search, hash)
+    }
 }
 
 /// A generic router integration for the server side. All its need is the current path.
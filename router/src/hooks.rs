@@ -2,7 +2,7 @@ use crate::{
     Location, NavigateOptions, NavigationError, Params, ParamsError, ParamsMap,
     RouteContext, RouterContext,
 };
-use leptos::{create_memo, use_context, Memo, Scope};
+use leptos::{create_memo, use_context, Memo, ReadSignal, Scope};
 use std::rc::Rc;
 
 /// Returns the current [RouterContext], containing information about the router's state.
@@ -84,3 +84,16 @@ pub fn use_navigate(
         Rc::clone(&router.inner).navigate_from_route(to, &options)
     }
 }
+
+/// Returns a signal that is `true` while a navigation is in progress, i.e., after the
+/// location has changed but before the new route is shown. This includes the time spent
+/// waiting for any [`<Transition/>`](leptos::Transition)s in the new route to resolve.
+pub fn use_is_routing(cx: Scope) -> ReadSignal<bool> {
+    use_router(cx).is_routing()
+}
+
+/// Returns a signal containing the location the router is navigating to, while a
+/// navigation is in progress, and `None` otherwise.
+pub fn use_pending_location(cx: Scope) -> ReadSignal<Option<String>> {
+    use_router(cx).pending_location()
+}
@@ -194,9 +194,13 @@ mod history;
 mod hooks;
 #[doc(hidden)]
 pub mod matching;
+mod prefetch;
+mod query;
 pub use components::*;
 #[cfg(any(feature = "ssr", doc))]
 pub use extract_routes::*;
 pub use history::*;
 pub use hooks::*;
 pub use matching::{RouteDefinition, *};
+pub use prefetch::*;
+pub use query::*;
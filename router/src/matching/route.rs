@@ -1,3 +1,4 @@
+use crate::RouteLoader;
 use leptos::{leptos_dom::View, *};
 use std::rc::Rc;
 
@@ -14,6 +15,10 @@ pub struct RouteDefinition {
     pub children: Vec<RouteDefinition>,
     /// The view that should be displayed when this route is matched.
     pub view: Rc<dyn Fn(Scope) -> View>,
+    /// Loads the data for this route, allowing it to be prefetched.
+    pub data: Option<RouteLoader>,
+    /// The HTTP status code set when this route is rendered on the server.
+    pub status: Option<u16>,
 }
 
 impl std::fmt::Debug for RouteDefinition {
@@ -0,0 +1,236 @@
+use crate::{matching::get_route_matches, ParamsMap, RouterContext, Url};
+use leptos::*;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::Duration,
+};
+
+/// How long prefetched route data is kept before it is evicted, if no
+/// `prefetch_timeout` is passed to the [Router](crate::Router).
+pub const DEFAULT_PREFETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Describes when an [`<A/>`](crate::A) should begin loading the data for the
+/// route it links to.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PrefetchStrategy {
+    /// Never prefetch; data is only loaded once the navigation happens.
+    #[default]
+    None,
+    /// Prefetch when the pointer enters the link, or when it receives focus.
+    Hover,
+    /// Prefetch when the link scrolls into the viewport.
+    Visible,
+    /// Prefetch as soon as the link is mounted.
+    Eager,
+}
+
+/// A function that loads the data for a route, given its matched params.
+///
+/// This is usually passed to a [`<Route/>`](crate::Route) as its `data` prop.
+/// Anything created inside the loader (like a [Resource] that calls a server
+/// function) belongs to the [Scope] it receives, so when the route is
+/// prefetched it starts loading immediately and is handed to the route when
+/// the navigation actually happens. Use [use_route_data] to read the value
+/// from inside the route's view.
+#[derive(Clone)]
+pub struct RouteLoader(pub(crate) Rc<dyn Fn(Scope, ParamsMap) -> Rc<dyn Any>>);
+
+impl RouteLoader {
+    pub(crate) fn load(&self, cx: Scope, params: ParamsMap) -> Rc<dyn Any> {
+        (self.0)(cx, params)
+    }
+}
+
+impl<F, T> From<F> for RouteLoader
+where
+    F: Fn(Scope, ParamsMap) -> T + 'static,
+    T: 'static,
+{
+    fn from(f: F) -> Self {
+        Self(Rc::new(move |cx, params| Rc::new(f(cx, params))))
+    }
+}
+
+impl std::fmt::Debug for RouteLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteLoader").finish()
+    }
+}
+
+/// Returns the data loaded by the current route's `data` loader, if it has
+/// one and it returned a value of type `T`.
+///
+/// ```rust
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// # async fn load_post(id: String) -> String { id }
+/// #[component]
+/// fn App(cx: Scope) -> impl IntoView {
+///     view! { cx,
+///         <Router>
+///             <A href="/post/1" prefetch=PrefetchStrategy::Hover>"Post 1"</A>
+///             <Routes>
+///                 <Route
+///                     path="/post/:id"
+///                     view=|cx| view! { cx, <Post/> }
+///                     data=|cx: Scope, params: ParamsMap| {
+///                         let id = params.get("id").cloned().unwrap_or_default();
+///                         create_resource(cx, move || id.clone(), load_post)
+///                     }
+///                 />
+///             </Routes>
+///         </Router>
+///     }
+/// }
+///
+/// #[component]
+/// fn Post(cx: Scope) -> impl IntoView {
+///     let post = use_route_data::<Resource<String, String>>(cx)
+///         .expect("route to have a data loader");
+///     view! { cx, <p>{move || post.read()}</p> }
+/// }
+/// ```
+pub fn use_route_data<T>(cx: Scope) -> Option<T>
+where
+    T: Clone + 'static,
+{
+    crate::use_route(cx)
+        .data()
+        .and_then(|data| data.downcast_ref::<T>().cloned())
+}
+
+struct PrefetchEntry {
+    #[allow(unused)] // used in CSR/hydrate
+    generation: usize,
+    data: Rc<dyn Any>,
+    disposer: ScopeDisposer,
+}
+
+/// Holds the data loaded by prefetching routes until it is either used by a
+/// navigation or evicted.
+pub(crate) struct PrefetchCache {
+    cx: Scope,
+    timeout: Duration,
+    generation: Cell<usize>,
+    entries: Rc<RefCell<HashMap<(usize, String), PrefetchEntry>>>,
+}
+
+impl PrefetchCache {
+    pub fn new(cx: Scope, timeout: Option<Duration>) -> Self {
+        Self {
+            cx,
+            timeout: timeout.unwrap_or(DEFAULT_PREFETCH_TIMEOUT),
+            generation: Cell::new(0),
+            entries: Default::default(),
+        }
+    }
+
+    /// Removes the prefetched data for the route with the given ID at the given
+    /// (matched) path, if any. The caller takes ownership of the scope the
+    /// data was loaded in.
+    pub fn take(
+        &self,
+        route_id: usize,
+        path: &str,
+    ) -> Option<(Rc<dyn Any>, ScopeDisposer)> {
+        self.entries
+            .borrow_mut()
+            .remove(&(route_id, path.to_string()))
+            .map(|entry| (entry.data, entry.disposer))
+    }
+
+    fn load(
+        &self,
+        route_id: usize,
+        path: String,
+        loader: &RouteLoader,
+        params: ParamsMap,
+    ) {
+        if self
+            .entries
+            .borrow()
+            .contains_key(&(route_id, path.clone()))
+        {
+            return;
+        }
+
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+
+        let data = RefCell::new(None);
+        let disposer = self.cx.child_scope(|cx| {
+            *data.borrow_mut() = Some(loader.load(cx, params));
+        });
+        let data = data.into_inner().expect("route loader to have run");
+
+        self.entries.borrow_mut().insert(
+            (route_id, path.clone()),
+            PrefetchEntry {
+                generation,
+                data,
+                disposer,
+            },
+        );
+
+        #[cfg(not(feature = "ssr"))]
+        {
+            let entries = Rc::clone(&self.entries);
+            set_timeout(
+                move || {
+                    let key = (route_id, path);
+                    let expired = entries
+                        .borrow()
+                        .get(&key)
+                        .map(|entry| entry.generation == generation)
+                        .unwrap_or(false);
+                    if expired {
+                        let entry = entries.borrow_mut().remove(&key);
+                        if let Some(entry) = entry {
+                            entry.disposer.dispose();
+                        }
+                    }
+                },
+                self.timeout,
+            );
+        }
+    }
+}
+
+impl std::fmt::Debug for PrefetchCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrefetchCache")
+            .field("timeout", &self.timeout)
+            .field("entries", &self.entries.borrow().len())
+            .finish()
+    }
+}
+
+impl RouterContext {
+    /// Begins loading the data for every route that would be matched by
+    /// navigating to `href`. The results are reused if the navigation happens
+    /// before the router's prefetch timeout elapses.
+    pub fn prefetch(&self, href: &str) {
+        let pathname = match Url::try_from(href) {
+            Ok(url) => url.pathname,
+            Err(e) => {
+                log::error!("[Leptos Router] Cannot prefetch {href}\n\n{e:?}");
+                return;
+            }
+        };
+
+        let matches = get_route_matches(self.possible_branches(), pathname);
+        for route_match in matches {
+            if let Some(loader) = &route_match.route.key.data {
+                self.inner.prefetch.load(
+                    route_match.route.id,
+                    route_match.path_match.path,
+                    loader,
+                    route_match.path_match.params,
+                );
+            }
+        }
+    }
+}
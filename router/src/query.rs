@@ -0,0 +1,263 @@
+use crate::{use_router, NavigateOptions, ParamsError, RouterContext};
+use leptos::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::rc::Rc;
+
+/// A value that can be stored in the URL query string under a single key, and
+/// read back from it, for use with [use_query_signal].
+///
+/// This is implemented for strings, numbers, `bool` and `char`, and for a `Vec` of
+/// any of those, which is stored as the same key repeated once for each item
+/// (`?tag=a&tag=b`).
+pub trait QueryValue: Sized {
+    /// Parses the value from all the values given for its key in the query string,
+    /// in the order they appear. This is only called if there is at least one.
+    fn from_query(values: &[String]) -> Result<Self, ParamsError>;
+
+    /// Converts the value into the values that should be stored for its key.
+    /// Returning an empty `Vec` removes the key from the query string.
+    fn to_query(&self) -> Vec<String>;
+}
+
+macro_rules! query_value {
+    ($($ty:ty),*) => {
+        $(
+            impl QueryValue for $ty {
+                fn from_query(values: &[String]) -> Result<Self, ParamsError> {
+                    let value = values.last().map(String::as_str).unwrap_or_default();
+                    value
+                        .parse()
+                        .map_err(|e| ParamsError::Params(Rc::new(e)))
+                }
+
+                fn to_query(&self) -> Vec<String> {
+                    vec![self.to_string()]
+                }
+            }
+        )*
+    };
+}
+
+query_value!(
+    String, bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64,
+    u128, usize, f32, f64
+);
+
+impl<T> QueryValue for Vec<T>
+where
+    T: QueryValue,
+{
+    fn from_query(values: &[String]) -> Result<Self, ParamsError> {
+        values
+            .iter()
+            .map(|value| T::from_query(std::slice::from_ref(value)))
+            .collect()
+    }
+
+    fn to_query(&self) -> Vec<String> {
+        self.iter().flat_map(T::to_query).collect()
+    }
+}
+
+/// Options for [use_query_signal_with_options] and [use_query_struct].
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuerySignalOptions<T> {
+    /// If `true`, updating the signal replaces the current entry in the history
+    /// stack, rather than pushing a new one. (Defaults to `false`.)
+    pub replace: bool,
+    /// The value of the signal when the query string doesn't contain it, or it
+    /// can't be parsed. Setting the signal to this value removes it from the query
+    /// string, which keeps URLs short.
+    pub default: Option<T>,
+}
+
+impl<T> Default for QuerySignalOptions<T> {
+    fn default() -> Self {
+        Self {
+            replace: false,
+            default: None,
+        }
+    }
+}
+
+/// Returns a signal containing the value stored under `key` in the URL query
+/// string, and a setter that updates the URL to match. Setting `None` removes the key.
+///
+/// Because the state lives in the URL, it survives reloads and can be shared by
+/// copying the link. Each update is a navigation that pushes a new entry onto the
+/// history stack; use [use_query_signal_with_options] to replace it instead.
+///
+/// ```rust
+/// # use leptos::*;
+/// # use leptos_router::*;
+/// #[component]
+/// fn Filters(cx: Scope) -> impl IntoView {
+///     let (page, set_page) = use_query_signal::<u32>(cx, "page");
+///     let (tags, set_tags) = use_query_signal_with_options::<Vec<String>>(
+///         cx,
+///         "tag",
+///         QuerySignalOptions {
+///             replace: true,
+///             default: Some(Vec::new()),
+///         },
+///     );
+///
+///     view! { cx,
+///         <button on:click=move |_| set_page(Some(page().unwrap_or(1) + 1))>
+///             "Next page"
+///         </button>
+///         <button on:click=move |_| set_tags(Some(vec!["rust".to_string()]))>
+///             {move || format!("{} tags", tags().unwrap_or_default().len())}
+///         </button>
+///     }
+/// }
+/// ```
+pub fn use_query_signal<T>(
+    cx: Scope,
+    key: impl Into<String>,
+) -> (Memo<Option<T>>, SignalSetter<Option<T>>)
+where
+    T: QueryValue + Clone + PartialEq + 'static,
+{
+    use_query_signal_with_options(cx, key, QuerySignalOptions::default())
+}
+
+/// Like [use_query_signal], with a default value and the option to replace the
+/// current history entry when the signal is set.
+pub fn use_query_signal_with_options<T>(
+    cx: Scope,
+    key: impl Into<String>,
+    options: QuerySignalOptions<T>,
+) -> (Memo<Option<T>>, SignalSetter<Option<T>>)
+where
+    T: QueryValue + Clone + PartialEq + 'static,
+{
+    let router = use_router(cx);
+    let key = key.into();
+    let QuerySignalOptions { replace, default } = options;
+
+    let value = create_memo(cx, {
+        let key = key.clone();
+        let default = default.clone();
+        let search = router.inner.location.search;
+        move |_| {
+            let values = search.with(|search| {
+                parse_query(search)
+                    .into_iter()
+                    .filter_map(|(k, v)| (k == key).then_some(v))
+                    .collect::<Vec<_>>()
+            });
+            if values.is_empty() {
+                default.clone()
+            } else {
+                T::from_query(&values).ok().or_else(|| default.clone())
+            }
+        }
+    });
+
+    let set_value = SignalSetter::map(cx, move |value: Option<T>| {
+        let values = value
+            .filter(|value| default.as_ref() != Some(value))
+            .map(|value| value.to_query())
+            .unwrap_or_default();
+        update_query(&router, replace, |pairs| {
+            pairs.retain(|(k, _)| k != &key);
+            pairs.extend(values.into_iter().map(|v| (key.clone(), v)));
+        });
+    });
+
+    (value, set_value)
+}
+
+/// Returns a signal containing a struct deserialized from the whole URL query
+/// string with [serde], in which each field is stored under its own key, and a
+/// setter that updates the URL to match. Setting `None` removes all of the
+/// struct's keys. Keys that aren't part of the struct are left alone.
+///
+/// Fields can use `#[serde(default)]` for values that may be missing from the URL.
+/// Sequences (like `Vec`) are not supported as fields; use [use_query_signal]
+/// for repeated keys.
+pub fn use_query_struct<T>(
+    cx: Scope,
+    options: QuerySignalOptions<T>,
+) -> (Memo<Option<T>>, SignalSetter<Option<T>>)
+where
+    T: Serialize + DeserializeOwned + Clone + PartialEq + 'static,
+{
+    let router = use_router(cx);
+    let QuerySignalOptions { replace, default } = options;
+
+    let value = create_memo(cx, {
+        let default = default.clone();
+        let search = router.inner.location.search;
+        move |_| {
+            search
+                .with(|search| {
+                    serde_urlencoded::from_str::<T>(
+                        search.trim_start_matches('?'),
+                    )
+                })
+                .ok()
+                .or_else(|| default.clone())
+        }
+    });
+
+    let set_value = SignalSetter::map(cx, move |new: Option<T>| {
+        let keys_of = |value: Option<&T>| {
+            value
+                .and_then(|value| serde_urlencoded::to_string(value).ok())
+                .map(|query| parse_query(&query))
+                .unwrap_or_default()
+        };
+        let prev = keys_of(value.get_untracked().as_ref());
+        let next =
+            keys_of(new.filter(|new| default.as_ref() != Some(new)).as_ref());
+        update_query(&router, replace, |pairs| {
+            pairs.retain(|(k, _)| {
+                !prev.iter().chain(next.iter()).any(|(key, _)| key == k)
+            });
+            pairs.extend(next);
+        });
+    });
+
+    (value, set_value)
+}
+
+/// Parses a query string into its key-value pairs, keeping repeated keys.
+pub(crate) fn parse_query(search: &str) -> Vec<(String, String)> {
+    serde_urlencoded::from_str(search.trim_start_matches('?'))
+        .unwrap_or_default()
+}
+
+/// Navigates to the current path, with the query string modified by `f`.
+fn update_query(
+    router: &RouterContext,
+    replace: bool,
+    f: impl FnOnce(&mut Vec<(String, String)>),
+) {
+    let location = &router.inner.location;
+    let mut pairs =
+        location.search.with_untracked(|search| parse_query(search));
+    f(&mut pairs);
+
+    let query = serde_urlencoded::to_string(&pairs).unwrap_or_default();
+    let to = format!(
+        "{}{}{}{}",
+        location.pathname.get_untracked(),
+        if query.is_empty() { "" } else { "?" },
+        query,
+        location.hash.get_untracked()
+    );
+
+    if let Err(e) = Rc::clone(&router.inner).navigate_from_route(
+        &to,
+        &NavigateOptions {
+            resolve: false,
+            replace,
+            scroll: false,
+            state: location.state.get_untracked(),
+        },
+    ) {
+        log::error!("{e:#?}");
+    }
+}
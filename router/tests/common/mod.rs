@@ -0,0 +1,28 @@
+use leptos::*;
+use leptos_router::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// Renders a `<Router/>` over `history` containing `routes`, and returns whatever
+/// `capture` reads from inside it, like the router context or a query signal.
+pub fn in_router<T: 'static>(
+    cx: Scope,
+    history: MemoryIntegration,
+    capture: impl FnOnce(Scope) -> T + 'static,
+    routes: impl FnOnce(Scope) -> View + 'static,
+) -> T {
+    provide_context(cx, RouterIntegrationContext::new(history));
+    let captured = Rc::new(RefCell::new(None));
+    let slot = Rc::clone(&captured);
+    _ = Router(
+        cx,
+        RouterProps::builder()
+            .children(Box::new(move |cx| {
+                *slot.borrow_mut() = Some(capture(cx));
+                Fragment::new(vec![routes(cx)])
+            }))
+            .build(),
+    )
+    .into_view(cx);
+    let captured = captured.borrow_mut().take();
+    captured.expect("the router renders its children")
+}
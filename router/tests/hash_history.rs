@@ -0,0 +1,70 @@
+use leptos_router::{HashIntegration, History};
+
+#[test]
+fn hrefs_put_the_route_in_the_hash() {
+    let history = HashIntegration {};
+    assert_eq!(history.href("/users/1"), "#/users/1");
+    assert_eq!(history.href("/search?q=a"), "#/search?q=a");
+}
+
+#[test]
+fn clicked_links_route_to_their_hash() {
+    let history = HashIntegration {};
+    assert_eq!(history.route_from_url("/", "", "#/users/1"), "/users/1");
+    assert_eq!(
+        history.route_from_url("/index.html", "?utm=x", "#/search?q=a"),
+        "/search?q=a"
+    );
+    assert_eq!(history.route_from_url("/", "", ""), "/");
+    assert_eq!(history.route_from_url("/", "", "#"), "/");
+}
+
+#[test]
+fn hrefs_round_trip() {
+    let history = HashIntegration {};
+    for path in ["/", "/users/1", "/search?q=a&tag=b"] {
+        assert_eq!(history.route_from_url("/", "", &history.href(path)), path);
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))] {
+        use leptos::*;
+        use leptos_router::*;
+
+        /// Routes like the server does, but writes links like [HashIntegration].
+        struct HashLinks(ServerIntegration);
+
+        impl History for HashLinks {
+            fn location(&self, cx: Scope) -> ReadSignal<LocationChange> {
+                self.0.location(cx)
+            }
+
+            fn navigate(&self, loc: &LocationChange) {
+                self.0.navigate(loc)
+            }
+
+            fn href(&self, path: &str) -> String {
+                HashIntegration {}.href(path)
+            }
+        }
+
+        #[test]
+        fn links_use_the_integration_href() {
+            run_scope(create_runtime(), |cx| {
+                let history = HashLinks(ServerIntegration { path: "/".to_string() });
+                provide_context(cx, RouterIntegrationContext::new(history));
+
+                let html = view! { cx,
+                    <Router>
+                        <A href="/users/1">"User"</A>
+                    </Router>
+                }
+                .into_view(cx)
+                .render_to_string(cx);
+
+                assert!(html.contains(r##"href="#/users/1""##), "{html}");
+            });
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use leptos::*;
+use leptos_router::{History, LocationChange, MemoryIntegration};
+
+fn push(history: &MemoryIntegration, path: &str) {
+    history.navigate(&LocationChange {
+        value: path.to_string(),
+        replace: false,
+        ..Default::default()
+    });
+}
+
+fn paths(history: &MemoryIntegration) -> Vec<String> {
+    history.entries().into_iter().map(|loc| loc.value).collect()
+}
+
+#[test]
+fn push_and_replace_entries() {
+    let history = MemoryIntegration::new("/");
+    push(&history, "/a");
+    push(&history, "/b");
+    assert_eq!(paths(&history), vec!["/", "/a", "/b"]);
+
+    history.navigate(&LocationChange {
+        value: "/c".to_string(),
+        replace: true,
+        ..Default::default()
+    });
+    assert_eq!(paths(&history), vec!["/", "/a", "/c"]);
+    assert_eq!(history.current().value, "/c");
+}
+
+#[test]
+fn back_and_forward() {
+    let history = MemoryIntegration::new("/");
+    assert!(!history.can_go_back());
+    push(&history, "/a");
+    push(&history, "/b");
+
+    history.back();
+    assert_eq!(history.current().value, "/a");
+    history.back();
+    assert_eq!(history.current().value, "/");
+    history.back();
+    assert_eq!(history.current().value, "/");
+    assert!(history.can_go_forward());
+
+    history.go(2);
+    assert_eq!(history.current().value, "/b");
+    assert!(!history.can_go_forward());
+}
+
+#[test]
+fn navigating_discards_forward_entries() {
+    let history = MemoryIntegration::new("/");
+    push(&history, "/a");
+    push(&history, "/b");
+    history.back();
+    push(&history, "/c");
+
+    assert_eq!(paths(&history), vec!["/", "/a", "/c"]);
+    assert!(!history.can_go_forward());
+}
+
+#[test]
+fn location_signal_follows_traversal() {
+    run_scope(create_runtime(), |cx| {
+        let history = MemoryIntegration::new("/");
+        let location = history.location(cx);
+        push(&history, "/a");
+        assert_eq!(location.get().value, "/");
+
+        history.back();
+        history.forward();
+        assert_eq!(location.get().value, "/a");
+    });
+}
@@ -0,0 +1,35 @@
+#![cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+
+mod common;
+
+use leptos::*;
+use leptos_router::*;
+
+#[test]
+fn navigating_ends_routing() {
+    run_scope(create_runtime(), |cx| {
+        let history = MemoryIntegration::new("/");
+        let (router, navigate) = common::in_router(
+            cx,
+            history.clone(),
+            |cx| (use_router(cx), use_navigate(cx)),
+            |cx| {
+                view! { cx,
+                    <Routes>
+                        <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+                        <Route path="about" view=|cx| view! { cx, <p>"About"</p> }/>
+                    </Routes>
+                }
+                .into_view(cx)
+            },
+        );
+        assert!(!router.is_routing().get());
+
+        navigate("/about", Default::default()).unwrap();
+
+        assert_eq!(router.pathname().get(), "/about");
+        assert!(!router.is_routing().get());
+        assert_eq!(router.pending_location().get(), None);
+        assert_eq!(history.current().value, "/about");
+    });
+}
@@ -0,0 +1,121 @@
+#![cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))]
+
+mod common;
+
+use leptos::*;
+use leptos_router::*;
+use std::{cell::RefCell, rc::Rc};
+
+struct Posts {
+    router: RouterContext,
+    navigate: Box<dyn Fn(&str)>,
+    /// The posts the loader has loaded.
+    loaded: Rc<RefCell<Vec<String>>>,
+    /// The posts the view has read with [use_route_data].
+    shown: Rc<RefCell<Vec<String>>>,
+}
+
+/// Renders a router with a `post/:id` route that has a loader.
+fn posts_router(cx: Scope, history: MemoryIntegration) -> Posts {
+    let loaded = Rc::new(RefCell::new(Vec::new()));
+    let shown = Rc::new(RefCell::new(Vec::new()));
+    let capture = |cx| {
+        let navigate = use_navigate(cx);
+        let navigate: Box<dyn Fn(&str)> =
+            Box::new(move |to| navigate(to, Default::default()).unwrap());
+        (use_router(cx), navigate)
+    };
+    let (router, navigate) = common::in_router(cx, history, capture, {
+        let (loaded, shown) = (Rc::clone(&loaded), Rc::clone(&shown));
+        move |cx| {
+            view! { cx,
+                <Routes>
+                    <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+                    <Route
+                        path="post/:id"
+                        view=move |cx| {
+                            let post = use_route_data::<String>(cx)
+                                .expect("the loader's value");
+                            shown.borrow_mut().push(post.clone());
+                            view! { cx, <p>{post}</p> }
+                        }
+                        data=move |_cx: Scope, params: ParamsMap| {
+                            let post =
+                                format!("post {}", params.get("id").unwrap());
+                            loaded.borrow_mut().push(post.clone());
+                            post
+                        }
+                    />
+                </Routes>
+            }
+            .into_view(cx)
+        }
+    });
+    Posts {
+        router,
+        navigate,
+        loaded,
+        shown,
+    }
+}
+
+#[test]
+fn prefetching_runs_the_loader() {
+    run_scope(create_runtime(), |cx| {
+        let Posts {
+            router,
+            loaded,
+            shown,
+            ..
+        } = posts_router(cx, MemoryIntegration::new("/"));
+
+        router.prefetch("/post/1");
+        assert_eq!(*loaded.borrow(), ["post 1"]);
+        assert!(shown.borrow().is_empty());
+
+        // prefetching again while the data is cached doesn't load it again
+        router.prefetch("/post/1");
+        assert_eq!(loaded.borrow().len(), 1);
+
+        // only routes with a loader load anything
+        router.prefetch("/");
+        assert_eq!(loaded.borrow().len(), 1);
+    });
+}
+
+#[test]
+fn navigating_takes_the_prefetched_data() {
+    run_scope(create_runtime(), |cx| {
+        let Posts {
+            router,
+            navigate,
+            loaded,
+            shown,
+        } = posts_router(cx, MemoryIntegration::new("/"));
+
+        router.prefetch("/post/1");
+        navigate("/post/1");
+        assert_eq!(*loaded.borrow(), ["post 1"]);
+        assert_eq!(*shown.borrow(), ["post 1"]);
+
+        // a route that wasn't prefetched is loaded when it is navigated to
+        navigate("/");
+        navigate("/post/2");
+        assert_eq!(*loaded.borrow(), ["post 1", "post 2"]);
+        assert_eq!(shown.borrow().last().unwrap(), "post 2");
+
+        // the prefetched data was taken, so prefetching loads it again
+        router.prefetch("/post/1");
+        assert_eq!(loaded.borrow().len(), 3);
+    });
+}
+
+#[test]
+fn route_data_is_the_loaders_value() {
+    run_scope(create_runtime(), |cx| {
+        let Posts { loaded, shown, .. } =
+            posts_router(cx, MemoryIntegration::new("/post/7"));
+        assert_eq!(*loaded.borrow(), ["post 7"]);
+        assert_eq!(*shown.borrow(), ["post 7"]);
+    });
+}
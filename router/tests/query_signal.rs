@@ -0,0 +1,115 @@
+use leptos_router::QueryValue;
+
+#[test]
+fn scalar_query_values() {
+    assert_eq!(u32::from_query(&["1".to_string(), "2".to_string()]), Ok(2));
+    assert!(u32::from_query(&["a".to_string()]).is_err());
+    assert_eq!(true.to_query(), vec!["true".to_string()]);
+}
+
+#[test]
+fn repeated_query_values() {
+    let values = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(Vec::<String>::from_query(&values), Ok(values.clone()));
+    assert_eq!(values.to_query(), values);
+    assert!(Vec::<String>::new().to_query().is_empty());
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "ssr", not(any(feature = "csr", feature = "hydrate"))))] {
+        mod common;
+
+        use leptos::*;
+        use leptos_router::*;
+
+        #[test]
+        fn query_signal_updates_url() {
+            run_scope(create_runtime(), |cx| {
+                let history = MemoryIntegration::new("/search?q=leptos&tag=a");
+                let (tags, set_tags) = common::in_router(
+                    cx,
+                    history.clone(),
+                    |cx| {
+                        use_query_signal_with_options(
+                            cx,
+                            "tag",
+                            QuerySignalOptions {
+                                replace: true,
+                                default: Some(Vec::<String>::new()),
+                            },
+                        )
+                    },
+                    |_| View::default(),
+                );
+                assert_eq!(tags.get(), Some(vec!["a".to_string()]));
+
+                set_tags.set(Some(vec!["a".to_string(), "b c".to_string()]));
+                assert_eq!(history.current().value, "/search?q=leptos&tag=a&tag=b+c");
+                assert_eq!(tags.get(), Some(vec!["a".to_string(), "b c".to_string()]));
+                assert_eq!(history.entries().len(), 1);
+
+                // setting the default removes the key
+                set_tags.set(Some(Vec::new()));
+                assert_eq!(history.current().value, "/search?q=leptos");
+                assert_eq!(tags.get(), Some(Vec::new()));
+            });
+        }
+
+        #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+        struct Filter {
+            q: String,
+            #[serde(default)]
+            page: u32,
+        }
+
+        #[test]
+        fn query_struct_round_trips() {
+            run_scope(create_runtime(), |cx| {
+                let history = MemoryIntegration::new("/search?q=leptos&sort=new");
+                let (filter, set_filter) = common::in_router(
+                    cx,
+                    history.clone(),
+                    |cx| {
+                        use_query_struct::<Filter>(
+                            cx,
+                            QuerySignalOptions {
+                                replace: false,
+                                default: None,
+                            },
+                        )
+                    },
+                    |_| View::default(),
+                );
+                assert_eq!(
+                    filter.get(),
+                    Some(Filter {
+                        q: "leptos".to_string(),
+                        page: 0
+                    })
+                );
+
+                set_filter.set(Some(Filter {
+                    q: "leptos router".to_string(),
+                    page: 2,
+                }));
+                assert_eq!(
+                    history.current().value,
+                    "/search?sort=new&q=leptos+router&page=2"
+                );
+                assert_eq!(
+                    filter.get(),
+                    Some(Filter {
+                        q: "leptos router".to_string(),
+                        page: 2
+                    })
+                );
+                assert_eq!(history.entries().len(), 2);
+
+                // keys that aren't part of the struct are left alone
+                set_filter.set(None);
+                assert_eq!(history.current().value, "/search?sort=new");
+                assert_eq!(filter.get(), None);
+            });
+        }
+    }
+}
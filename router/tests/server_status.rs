@@ -0,0 +1,74 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use leptos::*;
+        use leptos_router::*;
+        use std::{cell::Cell, rc::Rc};
+
+        fn render(path: &str) -> (String, Option<u16>) {
+            let status = Rc::new(Cell::new(None));
+            let path = format!("http://leptos{path}");
+            let html = run_scope(create_runtime(), {
+                let status = Rc::clone(&status);
+                move |cx| {
+                    provide_context(
+                        cx,
+                        RouterIntegrationContext::new(ServerIntegration { path }),
+                    );
+                    provide_server_status(cx, move |code| status.set(Some(code)));
+
+                    view! { cx,
+                        <Router>
+                            <Routes fallback=|cx| view! { cx, <p>"Not found"</p> }.into_view(cx)>
+                                <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+                                <Route path="gone" status=410 view=|cx| view! { cx, <p>"Gone"</p> }/>
+                            </Routes>
+                        </Router>
+                    }
+                    .into_view(cx)
+                    .render_to_string(cx)
+                    .to_string()
+                }
+            });
+            (html, status.get())
+        }
+
+        #[test]
+        fn matched_route_keeps_default_status() {
+            let (html, status) = render("/");
+            assert!(html.contains("Home"));
+            assert_eq!(status, None);
+        }
+
+        #[test]
+        fn route_declares_status() {
+            let (html, status) = render("/gone");
+            assert!(html.contains("Gone"));
+            assert_eq!(status, Some(410));
+        }
+
+        #[test]
+        fn unmatched_path_shows_fallback_with_404() {
+            let (html, status) = render("/nowhere");
+            assert!(html.contains("Not found"));
+            assert_eq!(status, Some(404));
+        }
+
+        #[test]
+        fn route_list_reports_fallback() {
+            let (routes, has_fallback) =
+                generate_route_list_with_fallback_inner(|cx| {
+                    view! { cx,
+                        <Router fallback=|cx| view! { cx, <p>"Not found"</p> }.into_view(cx)>
+                            <Routes>
+                                <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+                            </Routes>
+                        </Router>
+                    }
+                });
+            assert_eq!(routes, vec![String::new()]);
+            assert!(has_fallback);
+        }
+    }
+}
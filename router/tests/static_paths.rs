@@ -0,0 +1,94 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use leptos_router::*;
+        use std::collections::HashMap;
+
+        fn routes(routes: &[&str]) -> Vec<String> {
+            routes.iter().map(|route| route.to_string()).collect()
+        }
+
+        #[test]
+        fn static_routes_are_kept() {
+            let routes = routes(&["/", "/about", "/blog/archive"]);
+            assert_eq!(
+                expand_static_paths(&routes, &HashMap::new()),
+                vec!["/", "/about", "/blog/archive"]
+            );
+        }
+
+        #[test]
+        fn dynamic_routes_need_params() {
+            let routes = routes(&["/post/:id", "/user/:name"]);
+            let params = HashMap::from([(
+                "/post/:id".to_string(),
+                vec![params_map! { "id" => "1" }, params_map! { "id" => "2" }],
+            )]);
+            assert_eq!(
+                expand_static_paths(&routes, &params),
+                vec!["/post/1", "/post/2"]
+            );
+        }
+
+        #[test]
+        fn wildcard_takes_the_rest_of_the_path() {
+            let routes = routes(&["/docs/*path"]);
+            let params = HashMap::from([(
+                "/docs/*path".to_string(),
+                vec![params_map! { "path" => "guide/getting-started" }],
+            )]);
+            assert_eq!(
+                expand_static_paths(&routes, &params),
+                vec!["/docs/guide/getting-started"]
+            );
+        }
+
+        #[test]
+        fn params_missing_a_segment_are_skipped() {
+            let routes = routes(&["/:lang/post/:id"]);
+            let params = HashMap::from([(
+                "/:lang/post/:id".to_string(),
+                vec![
+                    params_map! { "lang" => "en", "id" => "1" },
+                    params_map! { "id" => "2" },
+                ],
+            )]);
+            assert_eq!(
+                expand_static_paths(&routes, &params),
+                vec!["/en/post/1"]
+            );
+        }
+
+        #[test]
+        fn params_that_leave_the_route_are_skipped() {
+            let routes = routes(&["/post/:id", "/docs/*path"]);
+            let params = HashMap::from([
+                (
+                    "/post/:id".to_string(),
+                    vec![
+                        params_map! { "id" => ".." },
+                        params_map! { "id" => "." },
+                        params_map! { "id" => "" },
+                        params_map! { "id" => "a/b" },
+                        params_map! { "id" => "a\\b" },
+                        params_map! { "id" => "1" },
+                    ],
+                ),
+                (
+                    "/docs/*path".to_string(),
+                    vec![
+                        params_map! { "path" => "../../etc/passwd" },
+                        params_map! { "path" => "guide/../.." },
+                        params_map! { "path" => "guide//intro" },
+                        params_map! { "path" => "guide/intro" },
+                    ],
+                ),
+            ]);
+            assert_eq!(
+                expand_static_paths(&routes, &params),
+                vec!["/post/1", "/docs/guide/intro"]
+            );
+        }
+    }
+}
@@ -0,0 +1,133 @@
+#![cfg(all(target_arch = "wasm32", feature = "csr"))]
+
+use futures::channel::oneshot;
+use leptos::*;
+use leptos_router::*;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Lets the browser run its timers and microtasks, so that pending transitions can
+/// settle.
+async fn tick() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, 10)
+            .unwrap();
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+}
+
+struct App {
+    root: web_sys::HtmlElement,
+    router: RouterContext,
+    navigate: Box<dyn Fn(&str)>,
+    /// Resolves the resource the `/slow` route is waiting on.
+    load_slow: oneshot::Sender<String>,
+}
+
+/// Mounts a router with a `/slow` route whose `<Transition/>` waits for
+/// [App::load_slow], and an `/other` route that is ready at once.
+fn mount_app() -> App {
+    let root = document().create_element("section").unwrap();
+    document().body().unwrap().append_child(&root).unwrap();
+    let root = root.unchecked_into::<web_sys::HtmlElement>();
+
+    let (load_slow, slow) = oneshot::channel();
+    let slow = Rc::new(RefCell::new(Some(slow)));
+    let captured = Rc::new(RefCell::new(None));
+    mount_to(root.clone(), {
+        let captured = Rc::clone(&captured);
+        move |cx| {
+            provide_context(
+                cx,
+                RouterIntegrationContext::new(MemoryIntegration::new("/")),
+            );
+            view! { cx,
+                <Router>
+                    {
+                        let navigate = use_navigate(cx);
+                        let navigate: Box<dyn Fn(&str)> = Box::new(move |to| {
+                            navigate(to, Default::default()).unwrap()
+                        });
+                        *captured.borrow_mut() = Some((use_router(cx), navigate));
+                    }
+                    <Routes>
+                        <Route path="" view=|cx| view! { cx, <p>"Home"</p> }/>
+                        <Route path="other" view=|cx| view! { cx, <p>"Other"</p> }/>
+                        <Route
+                            path="slow"
+                            view=move |cx| {
+                                let slow = Rc::clone(&slow);
+                                let text = create_resource(cx, || (), move |_| {
+                                    let slow = slow.borrow_mut().take();
+                                    async move {
+                                        match slow {
+                                            Some(slow) => slow.await.unwrap_or_default(),
+                                            None => String::new(),
+                                        }
+                                    }
+                                });
+                                view! { cx,
+                                    <Transition fallback=|| ()>
+                                        {move || text.read().map(|text| view! { cx, <p>{text}</p> })}
+                                    </Transition>
+                                }
+                            }
+                        />
+                    </Routes>
+                </Router>
+            }
+        }
+    });
+
+    let (router, navigate) = captured.borrow_mut().take().unwrap();
+    App {
+        root,
+        router,
+        navigate,
+        load_slow,
+    }
+}
+
+#[wasm_bindgen_test]
+async fn previous_route_stays_until_transitions_resolve() {
+    let app = mount_app();
+    assert_eq!(app.root.inner_text(), "Home");
+
+    (app.navigate)("/slow");
+    tick().await;
+    assert_eq!(app.root.inner_text(), "Home");
+    assert!(app.router.is_routing().get_untracked());
+    assert_eq!(
+        app.router.pending_location().get_untracked().as_deref(),
+        Some("/slow")
+    );
+
+    app.load_slow.send("Slow".to_string()).unwrap();
+    tick().await;
+    assert_eq!(app.root.inner_text(), "Slow");
+    assert!(!app.router.is_routing().get_untracked());
+    assert_eq!(app.router.pending_location().get_untracked(), None);
+}
+
+#[wasm_bindgen_test]
+async fn newer_navigation_cancels_the_pending_one() {
+    let app = mount_app();
+
+    (app.navigate)("/slow");
+    tick().await;
+    assert_eq!(app.root.inner_text(), "Home");
+
+    (app.navigate)("/other");
+    tick().await;
+    assert_eq!(app.root.inner_text(), "Other");
+    assert!(!app.router.is_routing().get_untracked());
+
+    // the superseded route never replaces the current one
+    _ = app.load_slow.send("Slow".to_string());
+    tick().await;
+    assert_eq!(app.root.inner_text(), "Other");
+}